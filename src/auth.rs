@@ -0,0 +1,145 @@
+//! Logging in.
+//!
+//! The original kept Nation::passwd as crypt() output.  We keep an argon2
+//! hash with its own random salt, in the usual $argon2id$... form, so the
+//! save file never holds anything a player could type back in.  An empty
+//! passwd means none has been set and nobody can log in as that nation
+//! until god sets one.
+//!
+//! MAX_TRIES wrong passwords in a row lock the nation out for LOCKOUT turns.
+//! God can reset a password at any time, which also lifts the lock.
+
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+
+use crate::{Race, World};
+
+const MIN_PASSWD        : usize = 4;    // shortest password accepted
+const MAX_TRIES         : u8 = 5;       // failed logins before the lock
+const LOCKOUT           : i16 = 1;      // turns a locked nation waits
+
+/// Salted hash of passwd, for keeping in Nation::passwd.
+pub fn hash_password(passwd : &str) -> Result<String, String> {
+    if passwd.chars().count() < MIN_PASSWD {
+        return Err(format!("a password needs at least {} characters", MIN_PASSWD));
+    }
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default().hash_password(passwd.as_bytes(), &salt)
+        .map(|h| h.to_string())
+        .map_err(|e| e.to_string())
+}
+
+/// Does passwd match the stored hash?  Anything that isn't a hash matches
+/// nothing.
+pub fn check_password(hash : &str, passwd : &str) -> bool {
+    match PasswordHash::new(hash) {
+        Ok(h)   => Argon2::default().verify_password(passwd.as_bytes(), &h).is_ok(),
+        Err(_)  => false,
+    }
+}
+
+/// Is passwd already hashed, rather than plain text?
+pub fn is_hashed(passwd : &str) -> bool {
+    PasswordHash::new(passwd).is_ok()
+}
+
+/// A fresh session token, 32 hex digits nobody could guess.
+pub fn new_token() -> String {
+    let mut bytes = [0u8; 16];
+    OsRng.fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+impl World {
+    /// Nation id logs in with passwd.
+    pub fn login(&mut self, id : i32, passwd : &str) -> Result<(), String> {
+        let idx = self.nation_index(id).ok_or("no such nation")?;
+        let turn = self.turn;
+        let n = &mut self.ntn[idx];
+        if turn < n.locked_until {
+            return Err(format!("{} is locked out until turn {}", n.name, n.locked_until));
+        }
+        if n.passwd.is_empty() {
+            return Err(format!("{} has no password yet; ask god for one", n.name));
+        }
+        if check_password(&n.passwd, passwd) {
+            n.failed_logins = 0;
+            return Ok(());
+        }
+        n.failed_logins += 1;
+        if n.failed_logins >= MAX_TRIES {
+            n.failed_logins = 0;
+            n.locked_until = turn + LOCKOUT;
+            return Err(format!("wrong password; {} is locked out until turn {}", n.name, n.locked_until));
+        }
+        Err("wrong password".to_string())
+    }
+
+    /// Nation id changes its own password.
+    pub fn change_password(&mut self, id : i32, old : &str, new : &str) -> Result<(), String> {
+        self.login(id, old)?;
+        let hash = hash_password(new)?;
+        let idx = self.nation_index(id).ok_or("no such nation")?;
+        self.ntn[idx].passwd = hash;
+        Ok(())
+    }
+
+    /// God sets nation id's password, whatever it was, and lifts any lock.
+    pub fn reset_password(&mut self, god : i32, id : i32, new : &str) -> Result<(), String> {
+        if self.nation(god).map(|n| n.race != Race::GOD).unwrap_or(true) {
+            return Err("only god can do that".to_string());
+        }
+        let idx = self.nation_index(id).ok_or("no such nation")?;
+        let hash = hash_password(new)?;
+        let n = &mut self.ntn[idx];
+        n.passwd = hash;
+        n.failed_logins = 0;
+        n.locked_until = 0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Nation, PC};
+
+    #[test]
+    fn passwords_are_salted_and_checked() {
+        let (a, b) = (hash_password("swordfish").unwrap(), hash_password("swordfish").unwrap());
+        assert_ne!(a, b);
+        assert!(!a.contains("swordfish"));
+        assert!(check_password(&a, "swordfish") && check_password(&b, "swordfish"));
+        assert!(!check_password(&a, "swordfysh"));
+        assert!(!check_password("swordfish", "swordfish"));
+        assert!(hash_password("abc").is_err());
+    }
+
+    #[test]
+    fn too_many_tries_lock_the_nation_until_god_steps_in() {
+        let mut w = World::new(1, 1, 1);
+        let god = Nation { race: Race::GOD, ..Default::default() };
+        let elf = Nation { race: Race::ELF, active: PC, ..Default::default() };
+        let (g, e) = (god.id, elf.id);
+        w.ntn.push(god);
+        w.ntn.push(elf);
+
+        assert!(w.login(e, "").is_err());
+        assert!(w.reset_password(e, e, "mine now").is_err());
+        w.reset_password(g, e, "mellon").unwrap();
+        w.login(e, "mellon").unwrap();
+        for _ in 0..MAX_TRIES {
+            assert!(w.login(e, "friend").is_err());
+        }
+        assert!(w.login(e, "mellon").unwrap_err().contains("locked"));
+        w.turn += LOCKOUT;
+        w.change_password(e, "mellon", "galadriel").unwrap();
+
+        for _ in 0..MAX_TRIES {
+            let _ = w.login(e, "mellon");
+        }
+        w.reset_password(g, e, "elbereth").unwrap();
+        w.login(e, "elbereth").unwrap();
+    }
+}
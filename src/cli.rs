@@ -0,0 +1,547 @@
+// The administration command line, what conqrun was to the original.
+//
+//     rConq [--json] <command> <save> [options]
+//
+// Each command loads the save (or makes it), does one job and, if it
+// changed anything, writes the save back.  Anything done to the game goes
+// through god mode, so it ends up in the audit log.  With --json every
+// command prints a single JSON object instead of text, for scripts, and
+// errors go to stdout as {"ok": false, "error": ...} so a cron job only has
+// one stream to read.  The exit code says how it went: EXIT_OK, EXIT_FAILED
+// when the job couldn't be done, EXIT_USAGE when the command line was wrong.
+
+use std::io::Write;
+
+use rconq::auth::hash_password;
+use rconq::god::GodCommand;
+use rconq::host::Host;
+use rconq::json;
+use rconq::map::GenOptions;
+use rconq::nation::{INACTIVE, NPC_AGGRESSIVE, NPC_DEFENSIVE, NPC_ISOLATIONIST, NPC_TRADER, PC};
+use rconq::render::RenderOptions;
+use rconq::server::Server;
+use rconq::web::Web;
+use rconq::{Elevation, Race, World};
+
+pub const EXIT_OK       : i32 = 0;
+pub const EXIT_FAILED   : i32 = 1;
+pub const EXIT_USAGE    : i32 = 2;
+
+const MAX_MAP           : i16 = 256;    // Sector::owner and Army::xloc are u8
+
+const USAGE : &str = "usage: rConq [--json] <command> <save> [options]
+
+commands:
+    create <save> [--size WxH] [--seed N] [--water PCT] [--lairs N] [--password P] [--force]
+    add <save> <name> --race RACE [--at X,Y] [--npc aggressive|defensive|isolationist|trader] [--password P]
+    update <save> [--turns N]
+    map <save> [--by nation|designation|altitude|vegetation]
+    render <save> <file.png|file.svg> [--nation N] [--scale PIXELS] [--units]
+    nation <save> <name or id>
+    validate <save>
+    list <save>
+    rankings <save>
+    serve <save> [--listen ADDR] [--http ADDR]";
+
+enum Error {
+    Usage(String),
+    Failed(String),
+}
+
+impl From<String> for Error {
+    fn from(e : String) -> Error {
+        Error::Failed(e)
+    }
+}
+
+// What a command has to say, both ways.
+struct Output {
+    text            : String,
+    json            : String,
+}
+
+// The command line, taken apart as it is used.
+struct Args {
+    rest            : Vec<String>,
+}
+
+impl Args {
+    // The next word that isn't an option.
+    fn next(&mut self, what : &str) -> Result<String, Error> {
+        match self.rest.first() {
+            Some(a) if !a.starts_with("--") => Ok(self.rest.remove(0)),
+            _                               => Err(Error::Usage(format!("missing {}", what))),
+        }
+    }
+
+    // The value given for --name, if it was given.
+    fn opt(&mut self, name : &str) -> Result<Option<String>, Error> {
+        let Some(i) = self.rest.iter().position(|a| a == name) else { return Ok(None) };
+        if i + 1 >= self.rest.len() {
+            return Err(Error::Usage(format!("{} needs a value", name)));
+        }
+        self.rest.remove(i);
+        Ok(Some(self.rest.remove(i)))
+    }
+
+    fn num<T : std::str::FromStr>(&mut self, name : &str, default : T) -> Result<T, Error> {
+        match self.opt(name)? {
+            Some(v) => v.parse().map_err(|_| Error::Usage(format!("{} {} isn't a number", name, v))),
+            None    => Ok(default),
+        }
+    }
+
+    fn switch(&mut self, name : &str) -> bool {
+        let Some(i) = self.rest.iter().position(|a| a == name) else { return false };
+        self.rest.remove(i);
+        true
+    }
+
+    fn done(&self) -> Result<(), Error> {
+        match self.rest.first() {
+            Some(a) => Err(Error::Usage(format!("didn't expect {}", a))),
+            None    => Ok(()),
+        }
+    }
+}
+
+// "X,Y" or "WxH".
+fn pair(s : &str, sep : char) -> Option<(usize, usize)> {
+    let (a, b) = s.split_once(sep)?;
+    Some((a.trim().parse().ok()?, b.trim().parse().ok()?))
+}
+
+// A race by name or map character.
+fn race(s : &str) -> Option<Race> {
+    "-OEDLHPSN".chars().map(Race::from)
+        .find(|r| r.to_string().eq_ignore_ascii_case(s) || s.chars().eq(std::iter::once(r.to_char())))
+}
+
+fn strategy(s : &str) -> Option<u8> {
+    match s {
+        "aggressive"    => Some(NPC_AGGRESSIVE),
+        "defensive"     => Some(NPC_DEFENSIVE),
+        "isolationist"  => Some(NPC_ISOLATIONIST),
+        "trader"        => Some(NPC_TRADER),
+        _               => None,
+    }
+}
+
+fn active_name(active : u8) -> &'static str {
+    match active {
+        INACTIVE            => "inactive",
+        PC                  => "player",
+        NPC_AGGRESSIVE      => "aggressive",
+        NPC_DEFENSIVE       => "defensive",
+        NPC_ISOLATIONIST    => "isolationist",
+        NPC_TRADER          => "trader",
+        _                   => "npc",
+    }
+}
+
+// Id of the world's god, who does everything the command line asks.
+fn god(w : &World) -> Result<i32, Error> {
+    w.ntn.iter().find(|n| n.race == Race::GOD).map(|n| n.id)
+        .ok_or(Error::Failed("the world has no god".to_string()))
+}
+
+// Nation by id or name.
+fn find_nation(w : &World, which : &str) -> Result<usize, Error> {
+    which.parse().ok().and_then(|id| w.nation_index(id))
+        .or_else(|| w.ntn.iter().position(|n| n.name == which))
+        .ok_or(Error::Failed(format!("no nation {}", which)))
+}
+
+// Unowned land with nobody else's land next to it, for a new nation.
+fn free_spot(w : &mut World) -> Option<(usize, usize)> {
+    let mut v = Vec::new();
+    for x in 0..w.mapx as usize {
+        for y in 0..w.mapy as usize {
+            let s = &w.sct[x][y];
+            if s.altitude != Elevation::WATER && s.owner == 0
+                && w.neighbours(x, y).iter().all(|&(nx, ny)| w.sct[nx][ny].owner == 0) {
+                v.push((x, y));
+            }
+        }
+    }
+    w.pick(&v)
+}
+
+fn create(a : &mut Args, path : &str) -> Result<Output, Error> {
+    let (mapx, mapy) = match a.opt("--size")? {
+        Some(s) => pair(&s, 'x').ok_or(Error::Usage(format!("--size {} should be WxH", s)))?,
+        None    => (32, 32),
+    };
+    if mapx == 0 || mapy == 0 || mapx > MAX_MAP as usize || mapy > MAX_MAP as usize {
+        return Err(Error::Usage(format!("maps are 1x1 to {}x{}", MAX_MAP, MAX_MAP)));
+    }
+    let seed = a.num("--seed", 0u64)?;
+    let defaults = GenOptions::default();
+    let opts = GenOptions {
+        pwater      : a.num("--water", defaults.pwater)?,
+        lairs       : a.num("--lairs", defaults.lairs)?,
+        ..defaults
+    };
+    let password = a.opt("--password")?;
+    let force = a.switch("--force");
+    a.done()?;
+    if !force && std::path::Path::new(path).exists() {
+        return Err(Error::Failed(format!("{} already exists; --force to replace it", path)));
+    }
+
+    let mut w = World::generate(mapx as i16, mapy as i16, seed, &opts);
+    let god = w.create_nation("god", Race::GOD, INACTIVE, None)?;
+    if let Some(p) = password {
+        let idx = w.nation_index(god).unwrap();
+        w.ntn[idx].passwd = hash_password(&p)?;
+    }
+    w.update_stats();
+    w.save_file(path)?;
+    Ok(Output {
+        text    : format!("created {}: {}x{} world, seed {}", path, mapx, mapy, seed),
+        json    : format!("{{\"ok\": true, \"save\": {}, \"width\": {}, \"height\": {}, \"seed\": {}}}", json::string(path), mapx, mapy, seed),
+    })
+}
+
+fn add(a : &mut Args, path : &str) -> Result<Output, Error> {
+    let name = a.next("nation name")?;
+    let r = a.opt("--race")?.ok_or(Error::Usage("--race is needed".to_string()))?;
+    let r = race(&r).filter(|r| *r != Race::GOD).ok_or(Error::Usage(format!("no race {}", r)))?;
+    let at = match a.opt("--at")? {
+        Some(s) => Some(pair(&s, ',').ok_or(Error::Usage(format!("--at {} should be X,Y", s)))?),
+        None    => None,
+    };
+    let active = match a.opt("--npc")? {
+        Some(s) => strategy(&s).ok_or(Error::Usage(format!("no strategy {}", s)))?,
+        None    => PC,
+    };
+    let password = a.opt("--password")?;
+    a.done()?;
+
+    let mut w = World::load_file(path)?;
+    let god = god(&w)?;
+    let at = match at {
+        Some(at) => at,
+        None     => free_spot(&mut w).ok_or(Error::Failed("no room left for a nation".to_string()))?,
+    };
+    w.god(god, &GodCommand::Create { name: name.clone(), race: r, active, at: Some(at) })?;
+    let id = w.ntn.last().unwrap().id;
+    if let Some(p) = password {
+        w.reset_password(god, id, &p)?;
+    }
+    w.update_stats();
+    w.save_file(path)?;
+    Ok(Output {
+        text    : format!("added nation {} {} at {},{}", id, name, at.0, at.1),
+        json    : format!("{{\"ok\": true, \"nation\": {}, \"name\": {}, \"x\": {}, \"y\": {}}}", id, json::string(&name), at.0, at.1),
+    })
+}
+
+fn update(a : &mut Args, path : &str) -> Result<Output, Error> {
+    let turns = a.num("--turns", 1u32)?;
+    a.done()?;
+
+    let mut w = World::load_file(path)?;
+    let god = god(&w)?;
+    let first = w.turn;
+    for _ in 0..turns {
+        if w.winner.is_some() {
+            break;
+        }
+        w.god(god, &GodCommand::Update)?;
+    }
+    w.save_file(path)?;
+
+    let news : Vec<String> = w.news.iter().filter(|n| n.turn > first).map(|n| n.text.clone()).collect();
+    let winner = w.winner.and_then(|id| w.nation(id)).map(|n| n.name.clone());
+    let mut text = format!("{} is now at turn {}", path, w.turn);
+    if let Some(name) = &winner {
+        text += &format!("; {} has won", name);
+    }
+    Ok(Output {
+        text,
+        json    : format!("{{\"ok\": true, \"turn\": {}, \"winner\": {}, \"news\": {}}}",
+            w.turn, winner.map(|n| json::string(&n)).unwrap_or("null".to_string()),
+            json::list(&news.iter().map(|n| json::string(n)).collect::<Vec<_>>())),
+    })
+}
+
+fn map(a : &mut Args, path : &str) -> Result<Output, Error> {
+    let by = a.opt("--by")?.unwrap_or("nation".to_string());
+    if !["nation", "designation", "altitude", "vegetation"].contains(&by.as_str()) {
+        return Err(Error::Usage(format!("can't draw the map by {}", by)));
+    }
+    a.done()?;
+
+    let w = World::load_file(path)?;
+    let mut rows = Vec::new();
+    for y in 0..w.mapy as usize {
+        let row : String = (0..w.mapx as usize).map(|x| {
+            let s = &w.sct[x][y];
+            match by.as_str() {
+                "designation"   => s.designation.to_char(),
+                "altitude"      => s.altitude.to_char(),
+                "vegetation"    => s.vegetation.to_char(),
+                _ => match w.nation(s.owner as i32) {
+                    Some(n) if s.owner != 0 => n.mark,
+                    _                       => s.altitude.to_char(),
+                },
+            }
+        }).collect();
+        rows.push(row);
+    }
+    Ok(Output {
+        text    : rows.join("\n"),
+        json    : format!("{{\"ok\": true, \"width\": {}, \"height\": {}, \"by\": {}, \"rows\": {}}}",
+            w.mapx, w.mapy, json::string(&by), json::list(&rows.iter().map(|r| json::string(r)).collect::<Vec<_>>())),
+    })
+}
+
+// Draw the map as a picture, PNG or SVG by the file's extension.
+fn render(a : &mut Args, path : &str) -> Result<Output, Error> {
+    let file = a.next("picture file")?;
+    let which = a.opt("--nation")?;
+    let scale = a.num("--scale", RenderOptions::default().scale)?;
+    let units = a.switch("--units");
+    a.done()?;
+    let svg = match file.rsplit('.').next().map(|e| e.to_ascii_lowercase()) {
+        Some(e) if e == "svg"   => true,
+        Some(e) if e == "png"   => false,
+        _                       => return Err(Error::Usage(format!("{} should end in .png or .svg", file))),
+    };
+
+    let w = World::load_file(path)?;
+    let nation = match &which {
+        Some(n) => Some(w.ntn[find_nation(&w, n)?].id),
+        None    => None,
+    };
+    let opts = RenderOptions { nation, scale, units };
+    let picture = if svg { w.render_svg(&opts)?.into_bytes() } else { w.render_png(&opts)? };
+    std::fs::write(&file, picture).map_err(|e| format!("{}: {}", file, e))?;
+    let view = nation.map(|id| format!("nation {}", id)).unwrap_or("god".to_string());
+    Ok(Output {
+        text    : format!("drew the {} view in {}", view, file),
+        json    : format!("{{\"ok\": true, \"file\": {}, \"nation\": {}, \"width\": {}, \"height\": {}}}",
+            json::string(&file), nation.map(|id| id.to_string()).unwrap_or("null".to_string()),
+            w.mapx as u32 * scale, w.mapy as u32 * scale),
+    })
+}
+
+fn nation(a : &mut Args, path : &str) -> Result<Output, Error> {
+    let which = a.next("nation name or id")?;
+    a.done()?;
+
+    let w = World::load_file(path)?;
+    let n = &w.ntn[find_nation(&w, &which)?];
+    let fields : Vec<(&str, String)> = vec![
+        ("id",          n.id.to_string()),
+        ("name",        json::string(&n.name)),
+        ("leader",      json::string(&n.leader)),
+        ("race",        json::string(&n.race.to_string())),
+        ("active",      json::string(active_name(n.active))),
+        ("mark",        json::string(&n.mark.to_string())),
+        ("capitol",     format!("[{}, {}]", n.capx, n.capy)),
+        ("score",       n.score.to_string()),
+        ("sectors",     n.tsctrs.to_string()),
+        ("civilians",   n.tciv.to_string()),
+        ("military",    n.tmil.to_string()),
+        ("armies",      n.arm.len().to_string()),
+        ("navies",      n.nvy.len().to_string()),
+        ("gold",        n.tgold.to_string()),
+        ("jewels",      n.jewels.to_string()),
+        ("metal",       n.metals.to_string()),
+        ("food",        n.tfood.to_string()),
+        ("tax_rate",    n.tax_rate.to_string()),
+        ("charity",     n.charity.to_string()),
+        ("popularity",  n.popularity.to_string()),
+        ("prestige",    n.prestige.to_string()),
+        ("reputation",  n.reputation.to_string()),
+        ("terror",      n.terror.to_string()),
+        ("karma",       n.karma.to_string()),
+        ("spellpts",    n.spellpts.to_string()),
+        ("mail",        n.mail.len().to_string()),
+    ];
+    let text = fields.iter()
+        .map(|(k, v)| format!("{:<12} {}", k, v.trim_matches('"')))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let json = fields.iter().map(|(k, v)| format!("{}: {}", json::string(k), v)).collect::<Vec<_>>().join(", ");
+    Ok(Output { text, json: format!("{{\"ok\": true, {}}}", json) })
+}
+
+fn validate(a : &mut Args, path : &str) -> Result<Output, Error> {
+    a.done()?;
+
+    let w = World::load_file(path)?;
+    let problems = w.problems();
+    if !problems.is_empty() {
+        return Err(Error::Failed(format!("{} has problems:\n  {}", path, problems.join("\n  "))));
+    }
+    Ok(Output {
+        text    : format!("{} is a good save: turn {}, {} nations", path, w.turn, w.ntn.len()),
+        json    : format!("{{\"ok\": true, \"turn\": {}, \"nations\": {}}}", w.turn, w.ntn.len()),
+    })
+}
+
+fn list(a : &mut Args, path : &str) -> Result<Output, Error> {
+    a.done()?;
+
+    let w = World::load_file(path)?;
+    let mut text = format!("{:>4} {:<16} {:<8} {:<12} {:>7} {:>6}", "id", "nation", "race", "played by", "score", "sctrs");
+    let mut json = Vec::new();
+    for n in &w.ntn {
+        text += &format!("\n{:>4} {:<16} {:<8} {:<12} {:>7} {:>6}",
+            n.id, n.name, n.race.to_string(), active_name(n.active), n.score, n.tsctrs);
+        json.push(format!("{{\"id\": {}, \"name\": {}, \"race\": {}, \"active\": {}, \"score\": {}, \"sectors\": {}}}",
+            n.id, json::string(&n.name), json::string(&n.race.to_string()), json::string(active_name(n.active)), n.score, n.tsctrs));
+    }
+    Ok(Output { text, json: format!("{{\"ok\": true, \"turn\": {}, \"nations\": {}}}", w.turn, json::list(&json)) })
+}
+
+fn rankings(a : &mut Args, path : &str) -> Result<Output, Error> {
+    a.done()?;
+
+    let w = World::load_file(path)?;
+    let json : Vec<String> = w.rankings().iter().map(|r| format!(
+        "{{\"id\": {}, \"name\": {}, \"race\": {}, \"score\": {}, \"sectors\": {}, \"civilians\": {}, \"military\": {}, \"gold\": {}, \"jewels\": {}, \"metal\": {}, \"food\": {}}}",
+        r.nation, json::string(&r.name), json::string(&r.race.to_string()), r.score, r.sctrs, r.civ, r.mil, r.gold, r.jewels, r.metal, r.food)).collect();
+    Ok(Output {
+        text    : w.ranking_table().trim_end().to_string(),
+        json    : format!("{{\"ok\": true, \"turn\": {}, \"rankings\": {}}}", w.turn, json::list(&json)),
+    })
+}
+
+// Host the game until killed; see server.rs and web.rs.
+fn serve(a : &mut Args, path : &str) -> Result<Output, Error> {
+    let addr = a.opt("--listen")?.unwrap_or("127.0.0.1:7777".to_string());
+    let http = a.opt("--http")?;
+    a.done()?;
+
+    let w = World::load_file(path)?;
+    let host = Host::start(w, Some(path.to_string()));
+    let server = Server::start(host.handle(), &addr).map_err(|e| format!("{}: {}", addr, e))?;
+    eprintln!("rConq: serving {} on {}", path, server.addr());
+    if let Some(http) = http {
+        let web = Web::start(host.handle(), &http).map_err(|e| format!("{}: {}", http, e))?;
+        eprintln!("rConq: serving {} on http://{}", path, web.addr());
+    }
+    for e in host.errors() {
+        eprintln!("rConq: {}", e);
+    }
+    let w = host.join();
+    Ok(Output {
+        text    : format!("stopped at turn {}", w.turn),
+        json    : format!("{{\"ok\": true, \"turn\": {}}}", w.turn),
+    })
+}
+
+// Run the command line args (without the program name), printing to out
+// and err.  Returns the exit code.
+pub fn run(args : &[String], out : &mut dyn Write, err : &mut dyn Write) -> i32 {
+    let mut a = Args { rest: args.to_vec() };
+    let json = a.switch("--json");
+    if a.switch("--help") {
+        let _ = writeln!(out, "{}", USAGE);
+        return EXIT_OK;
+    }
+
+    let result = a.next("command").and_then(|cmd| {
+        let job : fn(&mut Args, &str) -> Result<Output, Error> = match cmd.as_str() {
+            "create"    => create,
+            "add"       => add,
+            "update"    => update,
+            "map"       => map,
+            "render"    => render,
+            "nation"    => nation,
+            "validate"  => validate,
+            "list"      => list,
+            "rankings"  => rankings,
+            "serve"     => serve,
+            _           => return Err(Error::Usage(format!("no command {}", cmd))),
+        };
+        let path = a.next("save file")?;
+        job(&mut a, &path)
+    });
+
+    let (code, msg) = match result {
+        Ok(o) => {
+            let _ = writeln!(out, "{}", if json { o.json } else { o.text });
+            return EXIT_OK;
+        },
+        Err(Error::Usage(e))    => (EXIT_USAGE, e),
+        Err(Error::Failed(e))   => (EXIT_FAILED, e),
+    };
+    if json {
+        let _ = writeln!(out, "{{\"ok\": false, \"error\": {}}}", json::string(&msg));
+    } else if code == EXIT_USAGE {
+        let _ = writeln!(err, "rConq: {}\n{}", msg, USAGE);
+    } else {
+        let _ = writeln!(err, "rConq: {}", msg);
+    }
+    code
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rconq(args : &[&str]) -> (i32, String) {
+        let args : Vec<String> = args.iter().map(|a| a.to_string()).collect();
+        let (mut out, mut err) = (Vec::new(), Vec::new());
+        let code = run(&args, &mut out, &mut err);
+        (code, String::from_utf8(out).unwrap() + &String::from_utf8(err).unwrap())
+    }
+
+    #[test]
+    fn a_game_run_from_the_command_line() {
+        let path = std::env::temp_dir().join(format!("rconq-cli-{}.save", std::process::id()));
+        let save = path.to_str().unwrap();
+
+        assert_eq!(rconq(&["create", save, "--size", "16x12", "--seed", "7", "--water", "30", "--force"]).0, EXIT_OK);
+        assert_eq!(rconq(&["create", save]).0, EXIT_FAILED);
+        assert_eq!(rconq(&["add", save, "Elfland", "--race", "elf"]).0, EXIT_OK);
+        assert_eq!(rconq(&["add", save, "Dwarfholm", "--race", "D", "--npc", "defensive", "--password", "khazad"]).0, EXIT_OK);
+        assert_eq!(rconq(&["add", save, "Elfland", "--race", "elf"]).0, EXIT_FAILED);
+        assert_eq!(rconq(&["add", save, "Nowhere", "--race", "troll"]).0, EXIT_USAGE);
+
+        let (code, out) = rconq(&["--json", "update", save, "--turns", "2"]);
+        assert_eq!(code, EXIT_OK);
+        assert!(out.starts_with("{\"ok\": true, \"turn\": 2,"), "{}", out);
+
+        let (code, out) = rconq(&["list", save]);
+        assert_eq!(code, EXIT_OK);
+        assert!(out.contains("Elfland") && out.contains("defensive"));
+        let (code, out) = rconq(&["rankings", save]);
+        assert_eq!(code, EXIT_OK);
+        assert!(out.lines().nth(1).unwrap().starts_with("  1 ") && out.contains("Dwarfholm"));
+        let (_, out) = rconq(&["--json", "rankings", save]);
+        assert!(out.contains("\"name\": \"Elfland\"") && !out.contains("\"god\""));
+        let (_, out) = rconq(&["--json", "nation", save, "Dwarfholm"]);
+        assert!(out.contains("\"race\": \"DWARF\""));
+        let (_, out) = rconq(&["map", save]);
+        assert_eq!(out.lines().count(), 12);
+        assert!(out.lines().all(|l| l.chars().count() == 16));
+        assert_eq!(rconq(&["validate", save]).0, EXIT_OK);
+
+        let picture = path.with_extension("svg");
+        let (code, out) = rconq(&["--json", "render", save, picture.to_str().unwrap(), "--nation", "Elfland", "--units"]);
+        assert_eq!(code, EXIT_OK, "{}", out);
+        assert!(out.contains("\"width\": 192") && std::fs::read_to_string(&picture).unwrap().starts_with("<svg"));
+        std::fs::remove_file(&picture).unwrap();
+        assert_eq!(rconq(&["render", save, "map.gif"]).0, EXIT_USAGE);
+
+        let mut w = World::load_file(save).unwrap();
+        assert_eq!(w.audit.len(), 4);
+        w.ntn[1].tgold += 1;
+        w.sct[0][0].owner = 250;
+        w.save_file(save).unwrap();
+        let (code, out) = rconq(&["--json", "validate", save]);
+        assert_eq!(code, EXIT_FAILED);
+        assert!(out.starts_with("{\"ok\": false") && out.contains("missing nation 250"));
+
+        assert_eq!(rconq(&["frobnicate", save]).0, EXIT_USAGE);
+        assert_eq!(rconq(&["list", save, "extra"]).0, EXIT_USAGE);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(rconq(&["list", save]).0, EXIT_FAILED);
+    }
+}
@@ -0,0 +1,118 @@
+// Land combat.
+//
+// Much simpler than the original's combat tables: each side's strength is
+// soldiers scaled by its own bonuses and its own military trade goods, and
+// the defence also by the sector's fortifications.  The odds are the
+// attacker's share of the total, and one roll decides it.  The loser takes
+// heavy losses, the winner light ones.
+
+use crate::{World, DEFEND, GARRISON};
+
+const FORT_BONUS        : i64 = 10;     // percent defence per fortress level
+const DUG_IN_BONUS      : i16 = 20;     // percent defence for defending armies
+
+#[derive(Debug)]
+#[derive(Clone)]
+pub struct Battle {
+    pub x           : usize,
+    pub y           : usize,
+    pub attacker    : i32,      // nation id
+    pub defender    : i32,      // nation id of the sector owner
+    pub defenders   : Vec<i32>, // nation ids of everyone whose armies fought
+    pub att_loss    : i32,      // attacking strength lost, in soldiers
+    pub def_loss    : i32,      // defending soldiers and monsters killed
+    pub won         : bool,     // did the attacker win
+}
+
+impl World {
+    // Armies of anyone but nation id in sector (x, y), as (nation, army)
+    // indexes.
+    pub fn armies_at(&self, x : usize, y : usize, not : i32) -> Vec<(usize, usize)> {
+        let mut v = Vec::new();
+        for (ni, n) in self.ntn.iter().enumerate() {
+            if n.id == not {
+                continue;
+            }
+            for (ai, a) in n.arm.iter().enumerate() {
+                if a.xloc as usize == x && a.yloc as usize == y && a.sold > 0 {
+                    v.push((ni, ai));
+                }
+            }
+        }
+        v
+    }
+
+    // Nation att attacks sector (x, y) with force soldier equivalents, all
+    // bonuses but its trade goods included; those are added here, for the
+    // attacker and every defender alike.  Every other nation's army in the
+    // sector defends, as do the guardians of any lair there.  Defender
+    // losses are applied here; att_loss is out of force, for the caller to
+    // take off whatever attacked.
+    pub fn attack(&mut self, att : i32, force : i32, x : usize, y : usize) -> Battle {
+        let owner = self.sct[x][y].owner as i32;
+        let defenders = self.armies_at(x, y, att);
+        let lair = self.lair_at(x, y);
+        let mut fought = Vec::new();
+        for &(ni, _) in &defenders {
+            if !fought.contains(&self.ntn[ni].id) {
+                fought.push(self.ntn[ni].id);
+            }
+        }
+        let mut b = Battle { x, y, attacker: att, defender: owner, defenders: fought, att_loss: 0, def_loss: 0, won: true };
+
+        if (defenders.is_empty() && lair.is_none()) || force <= 0 {
+            b.won = force > 0;
+            return b;
+        }
+
+        let fort = 100 + self.sct[x][y].fortress as i64 * FORT_BONUS;
+        let mut guards = Vec::new();
+        for &(ni, ai) in &defenders {
+            let (n, a) = (&self.ntn[ni], &self.ntn[ni].arm[ai]);
+            let dug_in = if a.stat == DEFEND || a.stat == GARRISON { DUG_IN_BONUS } else { 0 };
+            let goods = 100 + self.goods_bonus(n.id).military as i64;
+            guards.push((a.guard(n.dplus, dug_in) as i64 * goods / 100) as i32);
+        }
+        let lair_guard = lair.map(|l| self.lairs[l].guard()).unwrap_or(0);
+        let defense = (guards.iter().sum::<i32>() + lair_guard) as i64 * fort / 100;
+
+        let armed = force as i64 * (100 + self.goods_bonus(att).military as i64) / 100;
+        let odds = (armed * 100 / (armed + defense).max(1)) as i32;
+        b.won = (self.rng.below(100) as i32) < odds;
+        let (att_pct, def_pct) = if b.won {
+            (10 + self.rng.below(20) as i32, 40 + self.rng.below(30) as i32)
+        } else {
+            (40 + self.rng.below(30) as i32, 10 + self.rng.below(20) as i32)
+        };
+
+        b.att_loss = force * att_pct / 100;
+        for (&(ni, ai), &g) in defenders.iter().zip(guards.iter()) {
+            b.def_loss += self.ntn[ni].arm[ai].take_losses(g, g * def_pct / 100);
+        }
+        for &(ni, _) in &defenders {
+            self.ntn[ni].arm.retain(|a| a.sold > 0);
+        }
+        if let Some(l) = lair {
+            let lost = ((self.lairs[l].count * def_pct * 2 + 100) / 200).min(self.lairs[l].count);
+            self.lairs[l].count -= lost;
+            b.def_loss += lost;
+            if self.lairs[l].count == 0 {
+                if let Some(i) = self.nation_index(att) {
+                    self.loot_lair(l, i);
+                }
+            }
+        }
+
+        // wins and losses are remembered at home
+        if let Some(i) = self.nation_index(att) {
+            if b.won { self.ntn[i].deeds.victories += 1 } else { self.ntn[i].deeds.defeats += 1 }
+        }
+        for &d in &b.defenders {
+            if let Some(i) = self.nation_index(d) {
+                if b.won { self.ntn[i].deeds.defeats += 1 } else { self.ntn[i].deeds.victories += 1 }
+            }
+        }
+        self.battle_mail(&b);
+        b
+    }
+}
@@ -0,0 +1,603 @@
+//! Orders a nation can give during its turn.
+//!
+//! Players and the computer nations go through the same Command type and the
+//! same execute(), so an NPC can never do anything a human couldn't.  A
+//! command that can't be carried out changes nothing and says why.
+
+use std::str::FromStr;
+
+use crate::mercenary::MERC_COST;
+use crate::news::Category;
+use crate::politics::recruit_limit;
+use crate::trade::{Bid, Goods};
+use crate::units::{monster, MERCENARY};
+use crate::{
+    Army, Designation, Elevation, Race, World,
+    ALLIED, DEFEND, FRIENDLY, HOSTILE, JIHAD, MARCH, NEUTRAL, TREATY, UNMET, WAR,
+};
+
+pub const ENLIST_COST   : i32 = 2;      // gold per soldier drafted
+const MAXARM            : usize = 50;   // most armies a nation can field
+const MAX_TAX           : u8 = 20;      // percent
+const TAKE_SECTOR       : i32 = 75;     // soldiers needed to claim empty land
+
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Command {
+    Designate   { x : usize, y : usize, des : Designation },
+    Draft       { x : usize, y : usize, soldiers : i32 },
+    Move        { army : usize, x : usize, y : usize },     // one step, to a neighbouring sector
+    Attack      { army : usize, x : usize, y : usize },     // a neighbouring sector
+    Tax         { rate : u8 },
+    Charity     { pct : u8 },
+    Diplomacy   { nation : i32, status : i8 },
+    Summon      { x : usize, y : usize, unittyp : u8 },    // a monster, for spell points
+    Disband     { army : usize },                           // soldiers go to the mercenary pool
+    Hire        { x : usize, y : usize, soldiers : i32 },   // from the mercenary pool
+    Post        { goods : Goods },                          // up for trade
+    Bid         { offer : u32, goods : Goods },
+    Accept      { offer : u32, bidder : i32 },              // settled at the turn update
+    Withdraw    { offer : u32 },                            // an offer, or our bid on it
+}
+
+// Diplomatic statuses by name, for orders typed in.
+const STATUS_NAMES : [(&str, i8); 8] = [
+    ("unmet", UNMET), ("treaty", TREATY), ("allied", ALLIED), ("friendly", FRIENDLY),
+    ("neutral", NEUTRAL), ("hostile", HOSTILE), ("war", WAR), ("jihad", JIHAD),
+];
+
+// Orders as players type them, a verb and its numbers: "move 0 3 4",
+// "designate 3 4 m", "diplomacy 2 war", "bid 7 gold 500".
+impl FromStr for Command {
+    type Err = String;
+
+    fn from_str(s : &str) -> Result<Command, String> {
+        let (verb, rest) = s.trim().split_once(char::is_whitespace).unwrap_or((s.trim(), ""));
+        let w : Vec<&str> = rest.split_whitespace().collect();
+        fn num<T : FromStr>(w : &[&str], i : usize) -> Result<T, String> {
+            let v = w.get(i).ok_or("not enough numbers")?;
+            v.parse().map_err(|_| format!("{} isn't a number", v))
+        }
+        let want = |n : usize| if w.len() == n { Ok(()) } else { Err(format!("{} takes {} arguments", verb, n)) };
+        let cmd = match verb {
+            "designate" => {
+                want(3)?;
+                let c = w[2].chars().next().filter(|_| w[2].chars().count() == 1).ok_or("one character for the designation")?;
+                let des = Designation::from(c);
+                if des.to_char() != c {
+                    return Err(format!("no designation {}", c));
+                }
+                Command::Designate { x: num(&w, 0)?, y: num(&w, 1)?, des }
+            },
+            "draft"     => { want(3)?; Command::Draft { x: num(&w, 0)?, y: num(&w, 1)?, soldiers: num(&w, 2)? } },
+            "move"      => { want(3)?; Command::Move { army: num(&w, 0)?, x: num(&w, 1)?, y: num(&w, 2)? } },
+            "attack"    => { want(3)?; Command::Attack { army: num(&w, 0)?, x: num(&w, 1)?, y: num(&w, 2)? } },
+            "tax"       => { want(1)?; Command::Tax { rate: num(&w, 0)? } },
+            "charity"   => { want(1)?; Command::Charity { pct: num(&w, 0)? } },
+            "diplomacy" => {
+                want(2)?;
+                let status = STATUS_NAMES.iter().find(|(name, _)| *name == w[1]).map(|&(_, st)| st);
+                Command::Diplomacy { nation: num(&w, 0)?, status: status.map(Ok).unwrap_or_else(|| num(&w, 1))? }
+            },
+            "summon"    => { want(3)?; Command::Summon { x: num(&w, 0)?, y: num(&w, 1)?, unittyp: num(&w, 2)? } },
+            "disband"   => { want(1)?; Command::Disband { army: num(&w, 0)? } },
+            "hire"      => { want(3)?; Command::Hire { x: num(&w, 0)?, y: num(&w, 1)?, soldiers: num(&w, 2)? } },
+            "post"      => Command::Post { goods: rest.parse()? },
+            "bid"       => {
+                let (offer, goods) = rest.trim().split_once(char::is_whitespace).ok_or("bid takes an offer and goods")?;
+                Command::Bid { offer: num(&[offer], 0)?, goods: goods.parse()? }
+            },
+            "accept"    => { want(2)?; Command::Accept { offer: num(&w, 0)?, bidder: num(&w, 1)? } },
+            "withdraw"  => { want(1)?; Command::Withdraw { offer: num(&w, 0)? } },
+            _           => return Err(format!("no order {:?}", verb)),
+        };
+        Ok(cmd)
+    }
+}
+
+/// What it costs to redesignate a sector, or None if it can't be ordered.
+pub fn designate_cost(des : &Designation) -> Option<i32> {
+    match des {
+        Designation::TOWN           => Some(500),
+        Designation::CITY           => Some(2000),
+        Designation::FORT           => Some(500),
+        Designation::STOCKADE       => Some(200),
+        Designation::UNIVERSITY     => Some(500),
+        Designation::CHURCH         => Some(300),
+        Designation::FARM | Designation::MINE | Designation::GOLDMINE
+        | Designation::LUMBERYARD | Designation::BLACKSMITH | Designation::ROAD
+        | Designation::MILL | Designation::GRANARY | Designation::NONE
+                                    => Some(50),
+        _                           => None,
+    }
+}
+
+impl World {
+    /// Carry out cmd for nation id.
+    pub fn execute(&mut self, id : i32, cmd : &Command) -> Result<(), String> {
+        if self.winner.is_some() {
+            return Err("the game is over".to_string());
+        }
+        let idx = self.nation_index(id).ok_or("no such nation")?;
+
+        match *cmd {
+            Command::Designate { x, y, ref des } => {
+                self.check_owned(id, x, y)?;
+                let cost = designate_cost(des).ok_or(format!("can't designate {}", des))?;
+                if *des == Designation::CITY && self.sct[x][y].designation != Designation::TOWN {
+                    return Err("a city must be built on a town".to_string());
+                }
+                self.spend(idx, cost)?;
+                self.sct[x][y].designation = des.clone();
+            },
+            Command::Draft { x, y, soldiers } => {
+                self.check_owned(id, x, y)?;
+                if soldiers <= 0 {
+                    return Err("must draft at least one soldier".to_string());
+                }
+                if self.ntn[idx].arm.len() >= MAXARM {
+                    return Err("too many armies".to_string());
+                }
+                let limit = recruit_limit(&self.ntn[idx], self.sct[x][y].people);
+                if soldiers > limit {
+                    return Err(format!("only {} will answer the draft", limit));
+                }
+                self.spend(idx, soldiers * ENLIST_COST)?;
+                self.sct[x][y].people -= soldiers;
+                self.ntn[idx].arm.push(Army {
+                    xloc        : x as u8,
+                    yloc        : y as u8,
+                    sold        : soldiers,
+                    stat        : DEFEND,
+                    ..Default::default()
+                });
+            },
+            Command::Move { army, x, y } => {
+                self.check_army(idx, army, x, y)?;
+                let land = self.sct[x][y].altitude != Elevation::WATER && self.sct[x][y].altitude != Elevation::UNKNOWN;
+                let cost = if self.ntn[idx].arm[army].flies() && land {
+                    1
+                } else {
+                    self.sct[x][y].move_cost(&self.season()).ok_or("can't go there")?
+                };
+                if cost > self.ntn[idx].arm[army].smove as i32 {
+                    return Err("not enough movement left".to_string());
+                }
+                if !self.armies_at(x, y, id).is_empty() {
+                    return Err("sector is held by another army".to_string());
+                }
+                if self.lair_at(x, y).is_some() {
+                    return Err("monsters guard that sector; attack instead".to_string());
+                }
+                if let Some(o) = self.sector_owner(x, y) {
+                    let oid = self.ntn[o].id;
+                    if oid != id && self.ntn[idx].status(oid) != ALLIED {
+                        return Err(format!("{} is not an ally; attack instead", self.ntn[o].name));
+                    }
+                }
+                let a = &mut self.ntn[idx].arm[army];
+                a.smove -= cost as u8;
+                a.xloc = x as u8;
+                a.yloc = y as u8;
+                a.stat = MARCH;
+                let force = a.force(0);
+                if self.sector_owner(x, y).is_none() && force >= TAKE_SECTOR {
+                    self.sct[x][y].owner = id as u8;
+                    self.ntn[idx].tsctrs += 1;
+                }
+            },
+            Command::Attack { army, x, y } => {
+                self.check_army(idx, army, x, y)?;
+                // everyone who stands to lose by it: the owner, unless that is
+                // us, and whoever has armies there
+                let mut foes = Vec::new();
+                if let Some(o) = self.sector_owner(x, y).filter(|&o| o != idx) {
+                    foes.push(o);
+                }
+                for (ni, _) in self.armies_at(x, y, id) {
+                    if !foes.contains(&ni) {
+                        foes.push(ni);
+                    }
+                }
+                if foes.is_empty() && self.sector_owner(x, y) == Some(idx) && self.lair_at(x, y).is_none() {
+                    return Err("can't attack yourself".to_string());
+                }
+                for &o in &foes {
+                    let other = &self.ntn[o];
+                    if !other.race.is_special() && self.ntn[idx].status(other.id) < WAR {
+                        return Err(format!("not at war with {}", other.name));
+                    }
+                }
+                let force = self.ntn[idx].arm[army].force(self.ntn[idx].aplus);
+                let victim = if foes.is_empty() {
+                    "nobody".to_string()
+                } else {
+                    foes.iter().map(|&o| self.ntn[o].name.as_str()).collect::<Vec<_>>().join(" and ")
+                };
+                let mut ids = vec![id];
+                ids.extend(foes.iter().map(|&o| self.ntn[o].id));
+                let b = self.attack(id, force, x, y);
+                self.report(Category::BATTLE, &ids, Some((x, y)), format!("{} attacks {} at {},{} and {}",
+                    self.ntn[idx].name, victim, x, y, if b.won { "wins" } else { "is thrown back" }));
+                self.ntn[idx].arm[army].take_losses(force, b.att_loss);
+                self.ntn[idx].arm[army].smove = 0;
+                let standing = self.ntn[idx].arm[army].sold > 0;
+                if b.won && standing && self.armies_at(x, y, id).is_empty() && self.lair_at(x, y).is_none() {
+                    // the field is ours, if anyone is left to hold it
+                    let prev = self.sector_owner(x, y);
+                    let a = &mut self.ntn[idx].arm[army];
+                    a.xloc = x as u8;
+                    a.yloc = y as u8;
+                    if prev != Some(idx) {
+                        self.sct[x][y].owner = id as u8;
+                        self.ntn[idx].tsctrs += 1;
+                    }
+                    if let Some(p) = prev.filter(|&p| p != idx) {
+                        // holy war spares no one
+                        if self.ntn[idx].status(self.ntn[p].id) == JIHAD && self.sct[x][y].people > 0 {
+                            self.report(Category::CONQUEST, &[id, self.ntn[p].id], Some((x, y)), format!(
+                                "{} puts the {} people of {},{} to the sword", self.ntn[idx].name, self.sct[x][y].people, x, y));
+                            self.sct[x][y].people = 0;
+                            self.ntn[idx].deeds.massacres += 1;
+                        }
+                        self.ntn[idx].deeds.captured += 1;
+                        self.ntn[p].deeds.lost += 1;
+                        self.ntn[p].tsctrs = (self.ntn[p].tsctrs - 1).max(0);
+                    }
+                }
+                self.ntn[idx].arm.retain(|a| a.sold > 0);
+            },
+            Command::Summon { x, y, unittyp } => {
+                self.check_owned(id, x, y)?;
+                let m = monster(unittyp).ok_or("no such monster")?;
+                if self.ntn[idx].arm.len() >= MAXARM {
+                    return Err("too many armies".to_string());
+                }
+                if self.ntn[idx].spellpts < m.summon {
+                    return Err(format!("a {} takes {} spell points", m.name, m.summon));
+                }
+                self.ntn[idx].spellpts -= m.summon;
+                self.ntn[idx].arm.push(Army {
+                    unittyp,
+                    xloc        : x as u8,
+                    yloc        : y as u8,
+                    sold        : 1,
+                    stat        : DEFEND,
+                    ..Default::default()
+                });
+            },
+            Command::Disband { army } => {
+                let a = self.ntn[idx].arm.get(army).ok_or("no such army")?;
+                // monsters just go home
+                if monster(a.unittyp).is_none() {
+                    let (aplus, dplus) = self.pool_bonus(idx, army);
+                    self.join_pool(a.sold, aplus, dplus);
+                }
+                self.ntn[idx].arm.remove(army);
+            },
+            Command::Hire { x, y, soldiers } => {
+                self.check_owned(id, x, y)?;
+                if soldiers <= 0 {
+                    return Err("must hire at least one mercenary".to_string());
+                }
+                if self.ntn[idx].arm.len() >= MAXARM {
+                    return Err("too many armies".to_string());
+                }
+                if soldiers > self.m_mil {
+                    return Err(format!("only {} mercenaries are looking for work", self.m_mil));
+                }
+                self.spend(idx, soldiers * MERC_COST)?;
+                self.m_mil -= soldiers;
+                self.ntn[idx].arm.push(Army {
+                    unittyp     : MERCENARY,
+                    xloc        : x as u8,
+                    yloc        : y as u8,
+                    sold        : soldiers,
+                    stat        : DEFEND,
+                    aplus       : self.m_aplus,
+                    dplus       : self.m_dplus,
+                    ..Default::default()
+                });
+            },
+            Command::Post { ref goods } => {
+                self.post_offer(idx, goods.clone())?;
+            },
+            Command::Bid { offer, ref goods } => {
+                let o = self.offer_index(offer).ok_or("no such offer")?;
+                if self.trades[o].seller == id {
+                    return Err("can't bid on your own offer".to_string());
+                }
+                if self.trades[o].accepted.is_some() {
+                    return Err("that offer is already taken".to_string());
+                }
+                self.check_goods(idx, goods)?;
+                let bids = &mut self.trades[o].bids;
+                bids.retain(|b| b.bidder != id);
+                bids.push(Bid { bidder: id, goods: goods.clone() });
+            },
+            Command::Accept { offer, bidder } => {
+                let o = self.offer_index(offer).ok_or("no such offer")?;
+                if self.trades[o].seller != id {
+                    return Err("not your offer".to_string());
+                }
+                if !self.trades[o].bids.iter().any(|b| b.bidder == bidder) {
+                    return Err("no such bid".to_string());
+                }
+                self.trades[o].accepted = Some(bidder);
+            },
+            Command::Withdraw { offer } => {
+                let o = self.offer_index(offer).ok_or("no such offer")?;
+                if self.trades[o].seller == id {
+                    self.trades.remove(o);
+                } else if self.trades[o].accepted == Some(id) {
+                    return Err("your bid has been accepted".to_string());
+                } else {
+                    self.trades[o].bids.retain(|b| b.bidder != id);
+                }
+            },
+            Command::Tax { rate } => {
+                if rate > MAX_TAX {
+                    return Err(format!("taxes can't go above {}%", MAX_TAX));
+                }
+                self.ntn[idx].tax_rate = rate;
+            },
+            Command::Charity { pct } => {
+                if pct > 100 {
+                    return Err("charity is a percent of taxes".to_string());
+                }
+                self.ntn[idx].charity = pct;
+            },
+            Command::Diplomacy { nation, status } => {
+                let o = self.nation_index(nation).ok_or("no such nation")?;
+                if o == idx || self.ntn[o].race == Race::GOD {
+                    return Err("can't have relations with that nation".to_string());
+                }
+                if !(TREATY..=JIHAD).contains(&status) {
+                    return Err("no such diplomatic status".to_string());
+                }
+                let old = self.ntn[idx].status(nation);
+                if (old == TREATY || old == ALLIED) && status >= HOSTILE {
+                    self.ntn[idx].deeds.broken_treaties += 1;
+                }
+                if status >= WAR && old < WAR {
+                    self.report(Category::DIPLOMACY, &[id, nation], None,
+                        format!("{} declares war on {}", self.ntn[idx].name, self.ntn[o].name));
+                }
+                self.ntn[idx].set_status(nation, status);
+                // war is never one sided
+                if status >= WAR && self.ntn[o].status(id) < WAR {
+                    self.ntn[o].set_status(id, WAR);
+                }
+            },
+        }
+        Ok(())
+    }
+
+    fn check_owned(&self, id : i32, x : usize, y : usize) -> Result<(), String> {
+        if !self.on_map(x as i32, y as i32) || self.sct[x][y].owner as i32 != id {
+            return Err("you don't own that sector".to_string());
+        }
+        Ok(())
+    }
+
+    // Check that army exists and (x, y) is next to it.
+    fn check_army(&self, idx : usize, army : usize, x : usize, y : usize) -> Result<(), String> {
+        let a = self.ntn[idx].arm.get(army).ok_or("no such army")?;
+        let (ax, ay) = (a.xloc as usize, a.yloc as usize);
+        if a.sold <= 0 {
+            return Err("army has no soldiers".to_string());
+        }
+        if !self.on_map(x as i32, y as i32) || !self.neighbours(ax, ay).contains(&(x, y)) {
+            return Err("can only go to a neighbouring sector".to_string());
+        }
+        Ok(())
+    }
+
+    fn spend(&mut self, idx : usize, gold : i32) -> Result<(), String> {
+        if self.ntn[idx].tgold < gold {
+            return Err(format!("costs {} gold", gold));
+        }
+        self.ntn[idx].tgold -= gold;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{land, settle};
+    use crate::units::{MINMONSTER, MONSTERS};
+    use crate::{Nation, PC};
+
+    // Elves hold the west two columns of 6x3 land and orcs the east two,
+    // with 1000 people a sector and gold to spend.  The middle is empty.
+    fn world() -> (World, i32, i32) {
+        let mut w = land(6, 3);
+        let mut ids = Vec::new();
+        for (race, cols) in [(Race::ELF, 0..2), (Race::ORC, 4..6)] {
+            let n = Nation { race, active: PC, tgold: 10000, popularity: 50, maxmove: 8, ..Default::default() };
+            let owned : Vec<(usize, usize)> = cols.flat_map(|x| (0..3).map(move |y| (x, y))).collect();
+            ids.push(settle(&mut w, n, &owned, 1000));
+        }
+        (w, ids[0], ids[1])
+    }
+
+    // Elves with an army of 100 at x, y, ready to move.
+    fn army_at(w : &mut World, x : u8, y : u8) {
+        w.ntn[0].arm.push(Army { xloc: x, yloc: y, sold: 100, smove: 8, ..Default::default() });
+    }
+
+    #[test]
+    fn orders_read_as_typed() {
+        let cmd = |s : &str| s.parse::<Command>();
+        assert_eq!(cmd("move 0 3 4"), Ok(Command::Move { army: 0, x: 3, y: 4 }));
+        assert_eq!(cmd(" designate 3 4 m"), Ok(Command::Designate { x: 3, y: 4, des: Designation::MINE }));
+        assert_eq!(cmd("diplomacy 2 war"), Ok(Command::Diplomacy { nation: 2, status: WAR }));
+        assert_eq!(cmd("diplomacy 2 3"), Ok(Command::Diplomacy { nation: 2, status: 3 }));
+        assert_eq!(cmd("bid 7 gold 500"), Ok(Command::Bid { offer: 7, goods: Goods::Gold(500) }));
+        for bad in ["move 0 3", "tax lots", "fly 1 2", "designate 1 1 Q", "designate 1 1 mm", "post food"] {
+            assert!(cmd(bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn designating_costs_gold_and_needs_your_own_land() {
+        let (mut w, e, _) = world();
+        w.execute(e, &Command::Designate { x: 0, y: 0, des: Designation::FARM }).unwrap();
+        assert_eq!((&w.sct[0][0].designation, w.ntn[0].tgold), (&Designation::FARM, 9950));
+        assert!(w.execute(e, &Command::Designate { x: 0, y: 0, des: Designation::CITY }).is_err());
+        assert!(w.execute(e, &Command::Designate { x: 0, y: 0, des: Designation::CAPITOL }).is_err());
+        assert!(w.execute(e, &Command::Designate { x: 4, y: 0, des: Designation::FARM }).is_err());
+        assert!(w.execute(e, &Command::Designate { x: 9, y: 9, des: Designation::FARM }).is_err());
+        w.ntn[0].tgold = 100;
+        assert!(w.execute(e, &Command::Designate { x: 0, y: 1, des: Designation::TOWN }).is_err());
+        assert_eq!((&w.sct[0][1].designation, w.ntn[0].tgold), (&Designation::NONE, 100));
+    }
+
+    #[test]
+    fn drafts_take_as_many_people_as_answer() {
+        let (mut w, e, _) = world();
+        w.execute(e, &Command::Draft { x: 1, y: 1, soldiers: 100 }).unwrap();
+        assert_eq!((w.sct[1][1].people, w.ntn[0].tgold), (900, 10000 - 100 * ENLIST_COST));
+        let a = &w.ntn[0].arm[0];
+        assert_eq!((a.xloc, a.yloc, a.sold, a.stat), (1, 1, 100, DEFEND));
+
+        assert!(w.execute(e, &Command::Draft { x: 1, y: 1, soldiers: 0 }).is_err());
+        assert_eq!(w.execute(e, &Command::Draft { x: 1, y: 1, soldiers: 91 }), Err("only 90 will answer the draft".to_string()));
+        assert!(w.execute(e, &Command::Draft { x: 4, y: 1, soldiers: 10 }).is_err());
+        assert_eq!(w.ntn[0].arm.len(), 1);
+    }
+
+    #[test]
+    fn armies_march_a_step_at_a_time_and_claim_empty_land() {
+        let (mut w, e, _) = world();
+        army_at(&mut w, 1, 1);
+        w.execute(e, &Command::Move { army: 0, x: 2, y: 1 }).unwrap();
+        let a = &w.ntn[0].arm[0];
+        assert_eq!((a.xloc, a.smove, a.stat), (2, 7, MARCH));
+        assert_eq!((w.sct[2][1].owner as i32, w.ntn[0].tsctrs), (e, 1));
+
+        assert!(w.execute(e, &Command::Move { army: 0, x: 4, y: 1 }).is_err(), "two steps");
+        assert!(w.execute(e, &Command::Move { army: 1, x: 3, y: 1 }).is_err(), "no such army");
+        w.execute(e, &Command::Move { army: 0, x: 3, y: 1 }).unwrap();
+        assert!(w.execute(e, &Command::Move { army: 0, x: 4, y: 1 }).is_err(), "orc land");
+        w.sct[3][0].altitude = Elevation::WATER;
+        assert!(w.execute(e, &Command::Move { army: 0, x: 3, y: 0 }).is_err(), "water");
+        w.ntn[0].arm[0].smove = 0;
+        assert!(w.execute(e, &Command::Move { army: 0, x: 2, y: 1 }).is_err(), "tired");
+        assert_eq!(w.ntn[0].arm[0].xloc, 3);
+    }
+
+    #[test]
+    fn attacks_need_a_war_and_a_standing_army() {
+        let (mut w, e, o) = world();
+        army_at(&mut w, 3, 1);
+        w.sct[2][1].owner = e as u8;
+        assert!(w.execute(e, &Command::Attack { army: 0, x: 2, y: 1 }).is_err(), "ourselves");
+        assert!(w.execute(e, &Command::Attack { army: 0, x: 4, y: 1 }).is_err(), "at peace");
+
+        w.execute(e, &Command::Diplomacy { nation: o, status: WAR }).unwrap();
+        assert_eq!(w.ntn[1].status(e), WAR);
+        w.ntn[0].arm[0].sold = 0;
+        assert!(w.execute(e, &Command::Attack { army: 0, x: 4, y: 1 }).is_err(), "nobody to attack with");
+
+        w.ntn[0].arm[0].sold = 100;
+        w.execute(e, &Command::Attack { army: 0, x: 4, y: 1 }).unwrap();
+        let a = &w.ntn[0].arm[0];
+        assert_eq!((a.xloc, a.yloc, a.smove), (4, 1, 0));
+        assert_eq!(w.sct[4][1].owner as i32, e);
+        assert_eq!((w.ntn[0].deeds.captured, w.ntn[1].deeds.lost), (1, 1));
+    }
+
+    #[test]
+    fn every_army_attacked_must_be_an_enemy() {
+        let (mut w, e, o) = world();
+        army_at(&mut w, 3, 1);
+        w.sct[2][1].owner = e as u8;
+        w.ntn[1].arm.push(Army { xloc: 2, yloc: 1, sold: 10, ..Default::default() });
+        assert_eq!(w.execute(e, &Command::Attack { army: 0, x: 2, y: 1 }), Err(format!("not at war with {}", w.ntn[1].name)));
+
+        // invaders on our own land can be thrown out
+        w.execute(e, &Command::Diplomacy { nation: o, status: WAR }).unwrap();
+        w.execute(e, &Command::Attack { army: 0, x: 2, y: 1 }).unwrap();
+        assert!(w.ntn[1].arm.first().map_or(0, |a| a.sold) < 10);
+        assert_eq!((w.sct[2][1].owner as i32, w.ntn[0].tsctrs, w.ntn[0].deeds.captured), (e, 0, 0));
+
+        // but not past a third nation we are at peace with
+        let h = settle(&mut w, Nation { race: Race::HUMAN, active: PC, ..Default::default() }, &[], 0);
+        w.ntn[2].arm.push(Army { xloc: 4, yloc: 1, sold: 1, ..Default::default() });
+        w.ntn[0].arm[0].smove = 8;
+        let refused = w.execute(e, &Command::Attack { army: 0, x: 4, y: 1 });
+        assert_eq!(refused, Err(format!("not at war with {}", w.nation(h).unwrap().name)));
+    }
+
+    #[test]
+    fn taxes_charity_and_diplomacy_have_limits() {
+        let (mut w, e, o) = world();
+        w.execute(e, &Command::Tax { rate: 15 }).unwrap();
+        assert!(w.execute(e, &Command::Tax { rate: MAX_TAX + 1 }).is_err());
+        w.execute(e, &Command::Charity { pct: 30 }).unwrap();
+        assert!(w.execute(e, &Command::Charity { pct: 101 }).is_err());
+        assert_eq!((w.ntn[0].tax_rate, w.ntn[0].charity), (15, 30));
+
+        assert!(w.execute(e, &Command::Diplomacy { nation: e, status: WAR }).is_err());
+        assert!(w.execute(e, &Command::Diplomacy { nation: o, status: JIHAD + 1 }).is_err());
+        let g = settle(&mut w, Nation { race: Race::GOD, ..Default::default() }, &[], 0);
+        assert!(w.execute(e, &Command::Diplomacy { nation: g, status: WAR }).is_err());
+
+        w.execute(e, &Command::Diplomacy { nation: o, status: TREATY }).unwrap();
+        w.execute(e, &Command::Diplomacy { nation: o, status: WAR }).unwrap();
+        assert_eq!(w.ntn[0].deeds.broken_treaties, 1);
+        assert!(w.news.iter().any(|n| n.category == Category::DIPLOMACY));
+    }
+
+    #[test]
+    fn monsters_are_summoned_with_spell_points() {
+        let (mut w, e, _) = world();
+        let summon = Command::Summon { x: 0, y: 0, unittyp: MINMONSTER };
+        assert!(w.execute(e, &summon).is_err());
+        w.ntn[0].spellpts = MONSTERS[0].summon + 1;
+        assert!(w.execute(e, &Command::Summon { x: 0, y: 0, unittyp: 0 }).is_err(), "not a monster");
+        assert!(w.execute(e, &Command::Summon { x: 4, y: 0, unittyp: MINMONSTER }).is_err(), "orc land");
+        w.execute(e, &summon).unwrap();
+        assert_eq!(w.ntn[0].spellpts, 1);
+        assert_eq!((w.ntn[0].arm[0].unittyp, w.ntn[0].arm[0].sold), (MINMONSTER, 1));
+
+        // monsters don't join the mercenaries when let go
+        w.execute(e, &Command::Disband { army: 0 }).unwrap();
+        assert!(w.execute(e, &Command::Disband { army: 0 }).is_err());
+        assert_eq!(w.m_mil, 0);
+        assert!(w.execute(e, &Command::Hire { x: 0, y: 0, soldiers: 1 }).is_err(), "nobody for hire");
+    }
+
+    #[test]
+    fn only_the_seller_accepts_and_only_bidders_withdraw_bids() {
+        let (mut w, e, o) = world();
+        w.execute(e, &Command::Post { goods: Goods::Gold(100) }).unwrap();
+        let offer = w.next_trade;
+        assert!(w.execute(e, &Command::Bid { offer, goods: Goods::Gold(1) }).is_err(), "own offer");
+        assert!(w.execute(o, &Command::Bid { offer: offer + 1, goods: Goods::Gold(1) }).is_err());
+        w.execute(o, &Command::Bid { offer, goods: Goods::Gold(10) }).unwrap();
+        w.execute(o, &Command::Bid { offer, goods: Goods::Gold(50) }).unwrap();
+        assert_eq!(w.trades[0].bids.len(), 1, "a second bid replaces the first");
+        assert_eq!(w.trades[0].bids[0].goods, Goods::Gold(50));
+
+        assert!(w.execute(o, &Command::Accept { offer, bidder: o }).is_err(), "not the seller");
+        assert!(w.execute(e, &Command::Accept { offer, bidder: e }).is_err(), "no such bid");
+        w.execute(e, &Command::Accept { offer, bidder: o }).unwrap();
+        assert!(w.execute(o, &Command::Withdraw { offer }).is_err(), "accepted");
+        w.execute(e, &Command::Withdraw { offer }).unwrap();
+        assert!(w.trades.is_empty());
+        assert!(w.execute(e, &Command::Withdraw { offer }).is_err());
+    }
+
+    #[test]
+    fn nothing_happens_once_the_game_is_won() {
+        let (mut w, e, _) = world();
+        w.winner = Some(e);
+        assert!(w.execute(e, &Command::Tax { rate: 5 }).is_err());
+        assert_eq!(w.ntn[0].tax_rate, 0);
+    }
+}
@@ -0,0 +1,245 @@
+//! Treasury model: taxes, charity, upkeep and inflation.
+//!
+//! This roughly follows the budget screen of the original: every turn each
+//! nation collects taxes from its sectors, gives a slice of them away to the
+//! poor, pays its troops and ships, and watches inflation eat into whatever
+//! it spends.  Numbers are gold talons.
+
+use std::fmt;
+
+use crate::{Designation, World};
+
+pub use crate::trade::{Bid, Goods, Offer};
+
+// gold per 100 people per point of tax rate, by designation
+const TAXCITY           : i64 = 100;    // cities and capitols
+const TAXTOWN           : i64 = 80;
+const TAXFOOD           : i64 = 5;      // farms
+const TAXOTHR           : i64 = 3;      // anything else with people in it
+const TAXMETAL          : i64 = 8;      // per point of metal in a mine
+const TAXGOLD           : i64 = 8;      // per point of jewels in a goldmine
+
+const SOLDIER_UPKEEP    : i64 = 5;      // gold per 100 soldiers
+const SHIP_UPKEEP       : i64 = 20;     // gold per ship of any kind
+
+const GOLD_PER_CIV      : i64 = 10;     // reserves above this per civilian inflate
+const MAX_INFLATION     : i16 = 100;    // percent
+const MAX_RELIEF        : i64 = 10;     // most poverty charity can cut in a turn
+
+/// One nation's gold for one turn: what came in, what went out, and where
+/// the treasury started.
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(Default)]
+pub struct Budget {
+    pub nation      : i32,      // nation id
+    pub tax_people  : i64,      // taxes on towns and cities
+    pub tax_food    : i64,      // taxes on farms
+    pub tax_metal   : i64,      // taxes on mines
+    pub tax_jewels  : i64,      // taxes on goldmines
+    pub tax_other   : i64,      // taxes on everything else
+    pub charity     : i64,      // charity paid out
+    pub army        : i64,      // army upkeep
+    pub navy        : i64,      // navy upkeep
+    pub inflation   : i64,      // extra paid on expenses due to inflation
+    pub start       : i64,      // treasury at start of turn
+}
+
+impl Budget {
+    /// Everything taken in.
+    pub fn taxes(&self) -> i64 {
+        self.tax_people + self.tax_food + self.tax_metal + self.tax_jewels + self.tax_other
+    }
+
+    /// Everything paid out, inflation included.
+    pub fn expenses(&self) -> i64 {
+        self.charity + self.army + self.navy + self.inflation
+    }
+
+    /// Taxes less expenses; negative when the nation is losing money.
+    pub fn net(&self) -> i64 {
+        self.taxes() - self.expenses()
+    }
+
+    /// The treasury left at the end of the turn.
+    pub fn end(&self) -> i64 {
+        self.start + self.net()
+    }
+}
+
+impl fmt::Display for Budget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Budget for nation {}", self.nation)?;
+        writeln!(f, "    treasury at start   {:>10}", self.start)?;
+        writeln!(f, "    town/city taxes     {:>10}", self.tax_people)?;
+        writeln!(f, "    farm taxes          {:>10}", self.tax_food)?;
+        writeln!(f, "    mine taxes          {:>10}", self.tax_metal)?;
+        writeln!(f, "    goldmine taxes      {:>10}", self.tax_jewels)?;
+        writeln!(f, "    other taxes         {:>10}", self.tax_other)?;
+        writeln!(f, "    charity             {:>10}", -self.charity)?;
+        writeln!(f, "    army upkeep         {:>10}", -self.army)?;
+        writeln!(f, "    navy upkeep         {:>10}", -self.navy)?;
+        writeln!(f, "    inflation           {:>10}", -self.inflation)?;
+        writeln!(f, "    net                 {:>10}", self.net())?;
+        write!(f, "    treasury at end     {:>10}", self.end())
+    }
+}
+
+impl World {
+    /// Work out what nation idx will take in and pay out this turn, without
+    /// touching anything.
+    pub fn budget(&self, idx : usize) -> Budget {
+        let n = &self.ntn[idx];
+        let rate = n.tax_rate as i64;
+        let mut b = Budget { nation: n.id, start: n.tgold as i64, ..Default::default() };
+
+        for (x, y) in self.owned_sectors(n.id) {
+            let s = &self.sct[x][y];
+            let people = s.people.max(0) as i64;
+            match s.designation {
+                Designation::CAPITOL | Designation::CITY =>
+                    b.tax_people += people * TAXCITY * rate / 10000,
+                Designation::TOWN =>
+                    b.tax_people += people * TAXTOWN * rate / 10000,
+                Designation::FARM =>
+                    b.tax_food += people * TAXFOOD * rate / 10000,
+                Designation::MINE =>
+                    b.tax_metal += (people * TAXOTHR + s.metal as i64 * TAXMETAL * 100) * rate / 10000,
+                Designation::GOLDMINE =>
+                    b.tax_jewels += (people * TAXOTHR + s.jewels as i64 * TAXGOLD * 100) * rate / 10000,
+                _ =>
+                    b.tax_other += people * TAXOTHR * rate / 10000,
+            }
+        }
+
+        let soldiers : i64 = n.arm.iter().map(|a| a.sold.max(0) as i64).sum();
+        let ships : i64 = n.nvy.iter()
+            .map(|s| s.warships as i64 + s.merchant as i64 + s.galleys as i64)
+            .sum();
+
+        b.charity = b.taxes() * n.charity as i64 / 100;
+        b.army = soldiers * SOLDIER_UPKEEP / 100;
+        b.navy = ships * SHIP_UPKEEP;
+
+        // inflation doesn't change what you owe, just what it costs you
+        b.inflation = (b.charity + b.army + b.navy) * n.inflation.max(0) as i64 / 100;
+        b
+    }
+
+    /// Collect taxes and pay the bills for every nation, adjusting poverty,
+    /// wealth and inflation along the way.  Returns the budgets applied.
+    pub(crate) fn update_economy(&mut self) -> Vec<Budget> {
+        let mut budgets = Vec::new();
+
+        for idx in 0..self.ntn.len() {
+            if self.ntn[idx].race.is_special() {
+                continue;
+            }
+            let b = self.budget(idx);
+            let tciv : i64 = self.owned_sectors(self.ntn[idx].id).iter()
+                .map(|&(x, y)| self.sct[x][y].people.max(0) as i64)
+                .sum();
+
+            let n = &mut self.ntn[idx];
+            n.tgold = b.end().clamp(i32::MIN as i64, i32::MAX as i64) as i32;
+            n.tciv = tciv as i32;
+            n.wealth = if tciv > 0 { (b.taxes() * 100 / tciv).min(255) as u8 } else { 0 };
+
+            // every talon per 100 people given away lifts a point of poverty
+            if tciv > 0 {
+                let relief = (b.charity * 100 / tciv).min(MAX_RELIEF);
+                n.poverty = (n.poverty as i64 - relief).max(0) as u8;
+            }
+
+            // hoarding gold drives prices up, spending it down brings them back
+            if n.tgold as i64 > tciv * GOLD_PER_CIV {
+                n.inflation = (n.inflation + 1).min(MAX_INFLATION);
+            } else if n.inflation > 0 {
+                n.inflation -= 1;
+            }
+
+            budgets.push(b);
+        }
+        budgets
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{land, settle};
+    use crate::{Army, Nation, Navy, Race, PC};
+
+    fn world(gold : i32) -> World {
+        let mut w = land(3, 2);
+        let n = Nation {
+            race        : Race::HUMAN,
+            active      : PC,
+            tgold       : gold,
+            tax_rate    : 10,
+            charity     : 10,
+            inflation   : 20,
+            arm         : vec![Army { sold: 2000, ..Default::default() }],
+            nvy         : vec![Navy { warships: 2, merchant: 1, ..Default::default() }],
+            ..Default::default()
+        };
+        settle(&mut w, n, &[(0, 0), (1, 0), (2, 0), (0, 1), (1, 1), (2, 1)], 100);
+        for (x, y, d, people) in [
+            (0, 0, Designation::CITY, 1000),
+            (1, 0, Designation::TOWN, 500),
+            (2, 0, Designation::FARM, 2000),
+            (0, 1, Designation::MINE, 100),
+            (1, 1, Designation::GOLDMINE, 100),
+            (2, 1, Designation::MILL, 1000),
+        ] {
+            w.sct[x][y].designation = d;
+            w.sct[x][y].people = people;
+        }
+        w.sct[0][1].metal = 5;
+        w.sct[1][1].jewels = 2;
+        w
+    }
+
+    #[test]
+    fn budget_adds_up_taxes_and_bills() {
+        let w = world(1000);
+        let b = w.budget(0);
+        assert_eq!((b.tax_people, b.tax_food, b.tax_metal, b.tax_jewels, b.tax_other), (140, 10, 4, 1, 3));
+        assert_eq!(b.taxes(), 158);
+        assert_eq!((b.charity, b.army, b.navy), (15, 100, 60));
+        assert_eq!(b.inflation, (15 + 100 + 60) * 20 / 100);
+        assert_eq!(b.expenses(), 210);
+        assert_eq!((b.net(), b.end()), (-52, 948));
+        assert_eq!(w.ntn[0].tgold, 1000, "budget only looks");
+    }
+
+    #[test]
+    fn hoarding_inflates_and_debt_deflates() {
+        let mut w = world(1_000_000);
+        let b = w.update_economy();
+        assert_eq!(b.len(), 1);
+        let n = &w.ntn[0];
+        assert_eq!((n.tgold, n.tciv, n.inflation), (1_000_000 - 52, 4700, 21));
+        assert_eq!(n.wealth, (158 * 100 / 4700) as u8);
+
+        w.ntn[0].inflation = MAX_INFLATION;
+        w.update_economy();
+        assert_eq!(w.ntn[0].inflation, MAX_INFLATION);
+
+        // broke: the bills are still paid, into debt, and prices ease off
+        let mut w = world(10);
+        w.update_economy();
+        assert_eq!((w.ntn[0].tgold, w.ntn[0].inflation), (-42, 19));
+        w.update_economy();
+        assert!(w.ntn[0].tgold < -42);
+        assert_eq!(w.ntn[0].inflation, 18);
+    }
+
+    #[test]
+    fn monsters_keep_no_books() {
+        let mut w = world(1000);
+        w.ntn[0].race = Race::SAVAGE;
+        assert!(w.update_economy().is_empty());
+        assert_eq!(w.ntn[0].tgold, 1000);
+    }
+}
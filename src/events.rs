@@ -0,0 +1,324 @@
+// Random events.
+//
+// Every turn each nation rolls against the EVENTS table, with the odds bent
+// by the world's karma.  Whatever comes up is applied to a random spot in
+// the nation and reported in the news.  To add an event, give it a
+// variant, a line in the table and a case in World::happen().
+
+use std::fmt;
+
+use crate::karma::karma_chance;
+use crate::news::{Category, NewsItem};
+use crate::{Designation, Elevation, Vegetation, World};
+
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(PartialEq)]
+pub enum Event {
+    PLAGUE,
+    VOLCANO,
+    GOLD_STRIKE,
+    METAL_STRIKE,
+    STORM,
+    HARVEST,
+    PEASANT_REVOLT,
+}
+
+impl Event {
+    fn to_string(&self) -> String {
+        match self {
+            Event::PLAGUE           => "PLAGUE".to_string(),
+            Event::VOLCANO          => "VOLCANO".to_string(),
+            Event::GOLD_STRIKE      => "GOLD STRIKE".to_string(),
+            Event::METAL_STRIKE     => "METAL STRIKE".to_string(),
+            Event::STORM            => "STORM".to_string(),
+            Event::HARVEST          => "HARVEST".to_string(),
+            Event::PEASANT_REVOLT   => "PEASANT REVOLT".to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_string())
+    }
+}
+
+pub struct EventDef {
+    pub event       : Event,
+    pub chance      : i32,      // per mille, per nation per turn
+    pub good        : bool,     // a blessing rather than a disaster
+}
+
+pub const EVENTS : [EventDef; 7] = [
+    EventDef { event: Event::PLAGUE,            chance: 20, good: false },
+    EventDef { event: Event::VOLCANO,           chance: 30, good: false },
+    EventDef { event: Event::GOLD_STRIKE,       chance: 20, good: true  },
+    EventDef { event: Event::METAL_STRIKE,      chance: 20, good: true  },
+    EventDef { event: Event::STORM,             chance: 30, good: false },
+    EventDef { event: Event::HARVEST,           chance: 40, good: true  },
+    EventDef { event: Event::PEASANT_REVOLT,    chance: 10, good: false },
+];
+
+impl World {
+    // Roll every event for every ordinary nation.
+    pub(crate) fn update_events(&mut self) {
+        let ids : Vec<i32> = self.ntn.iter()
+            .filter(|n| !n.race.is_special())
+            .map(|n| n.id)
+            .collect();
+
+        for id in ids {
+            for def in EVENTS.iter() {
+                if self.rng.permille(karma_chance(def.chance, def.good, self.karma)) {
+                    self.happen(id, def.event.clone());
+                }
+            }
+        }
+    }
+
+    // Make event happen to nation id and put it in the news.  Returns the
+    // news, or None if the nation had nowhere for it to happen.
+    pub(crate) fn happen(&mut self, id : i32, event : Event) -> Option<NewsItem> {
+        let good = EVENTS.iter().any(|d| d.event == event && d.good);
+        let (at, text) = self.strike(id, event)?;
+        self.report(if good { Category::BLESSING } else { Category::DISASTER }, &[id], at, text);
+        self.news.last().cloned()
+    }
+
+    // Where event hit nation id, and what to say about it.
+    fn strike(&mut self, id : i32, event : Event) -> Option<(Option<(usize, usize)>, String)> {
+        let idx = self.nation_index(id)?;
+        let name = self.ntn[idx].name.clone();
+        let populated : Vec<(usize, usize)> = self.owned_sectors(id).into_iter()
+            .filter(|&(x, y)| self.sct[x][y].people > 0)
+            .collect();
+
+        match event {
+            Event::PLAGUE => {
+                let (x, y) = self.pick(&populated)?;
+                let mut dead = 0;
+                let mut area = self.neighbours(x, y);
+                area.push((x, y));
+                for (sx, sy) in area {
+                    if self.sct[sx][sy].owner as i32 == id {
+                        let d = self.sct[sx][sy].people * (25 + self.rng.below(26) as i32) / 100;
+                        self.sct[sx][sy].people -= d;
+                        dead += d;
+                    }
+                }
+                Some((Some((x, y)), format!("plague strikes {} near {},{}; {} people die", name, x, y, dead)))
+            },
+            Event::VOLCANO => {
+                let mut volcanos = Vec::new();
+                for (x, col) in self.sct.iter().enumerate() {
+                    for (y, s) in col.iter().enumerate() {
+                        if s.vegetation == Vegetation::VOLCANO
+                            && self.neighbours(x, y).iter().any(|&(nx, ny)| self.sct[nx][ny].owner as i32 == id) {
+                            volcanos.push((x, y));
+                        }
+                    }
+                }
+                let (x, y) = self.pick(&volcanos)?;
+                let mut dead = 0;
+                let mut area = self.neighbours(x, y);
+                area.push((x, y));
+                for (sx, sy) in area {
+                    let s = &mut self.sct[sx][sy];
+                    if s.altitude == Elevation::WATER {
+                        continue;
+                    }
+                    dead += s.people / 2;
+                    s.people -= s.people / 2;
+                    s.foodstore = 0;
+                    if s.designation != Designation::CAPITOL && s.designation != Designation::NONE {
+                        s.designation = Designation::DEVASTATED;
+                    }
+                }
+                Some((Some((x, y)), format!("volcano erupts at {},{} beside {}; {} people die", x, y, name, dead)))
+            },
+            Event::GOLD_STRIKE => {
+                let (x, y) = self.pick(&populated)?;
+                let found = 1 + self.rng.below(4) as u8;
+                self.sct[x][y].jewels = self.sct[x][y].jewels.saturating_add(found);
+                Some((Some((x, y)), format!("gold and jewels discovered by {} at {},{}", name, x, y)))
+            },
+            Event::METAL_STRIKE => {
+                let (x, y) = self.pick(&populated)?;
+                let found = 1 + self.rng.below(4) as u8;
+                self.sct[x][y].metal = self.sct[x][y].metal.saturating_add(found);
+                Some((Some((x, y)), format!("rich metal deposits found by {} at {},{}", name, x, y)))
+            },
+            Event::STORM => {
+                let at_sea : Vec<usize> = self.ntn[idx].nvy.iter().enumerate()
+                    .filter(|(_, f)| {
+                        let (x, y) = (f.xloc as usize, f.yloc as usize);
+                        (f.warships > 0 || f.merchant > 0 || f.galleys > 0)
+                            && self.on_map(x as i32, y as i32) && self.sct[x][y].altitude == Elevation::WATER
+                    })
+                    .map(|(i, _)| i)
+                    .collect();
+                if at_sea.is_empty() {
+                    return None;
+                }
+                let fleet = at_sea[self.rng.below(at_sea.len() as u32) as usize];
+                let f = &mut self.ntn[idx].nvy[fleet];
+                let lost = (f.warships.div_ceil(3), f.merchant.div_ceil(3), f.galleys.div_ceil(3));
+                f.warships -= lost.0;
+                f.merchant -= lost.1;
+                f.galleys -= lost.2;
+                let sunk = lost.0 + lost.1 + lost.2;
+                if f.warships == 0 && f.merchant == 0 && f.galleys == 0 {
+                    self.ntn[idx].nvy.remove(fleet);
+                }
+                Some((None, format!("storm at sea sinks {} ships of {}", sunk, name)))
+            },
+            Event::HARVEST => {
+                let farms : Vec<(usize, usize)> = populated.into_iter()
+                    .filter(|&(x, y)| self.sct[x][y].designation == Designation::FARM)
+                    .collect();
+                if farms.is_empty() {
+                    return None;
+                }
+                let food : i32 = farms.iter().map(|&(x, y)| self.sct[x][y].people).sum();
+                self.ntn[idx].tfood += food;
+                Some((None, format!("bountiful harvest in {} brings in {} extra food", name, food)))
+            },
+            // monsters have no peasants to speak of
+            Event::PEASANT_REVOLT if self.ntn[idx].race.is_special() => None,
+            Event::PEASANT_REVOLT => {
+                let cap = self.capitol(&self.ntn[idx]);
+                let unrest : Vec<(usize, usize)> = populated.into_iter()
+                    .filter(|&p| Some(p) != cap)
+                    .collect();
+                let sector = self.pick(&unrest)?;
+                let r = self.split_nation(id, vec![sector])?;
+                let rebels = self.nation(r.rebel).map(|n| n.name.clone()).unwrap_or_default();
+                Some((Some(sector), format!("peasants revolt in {} at {},{}; {} declare independence",
+                    name, sector.0, sector.1, rebels)))
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::karma::KARMA_MAX;
+    use crate::testing::{land, settle};
+    use crate::{Nation, Navy, Race, PC};
+
+    // A 3x3 realm of 1000 people a sector in the corner of 5x5 land, its
+    // capitol at 0,0.
+    fn world() -> (World, i32) {
+        let mut w = land(5, 5);
+        let n = Nation {
+            name        : String::from("Realm"),
+            race        : Race::HUMAN,
+            active      : PC,
+            popularity  : 50,
+            ..Default::default()
+        };
+        let realm : Vec<(usize, usize)> = (0..3).flat_map(|x| (0..3).map(move |y| (x, y))).collect();
+        let id = settle(&mut w, n, &realm, 1000);
+        w.sct[0][0].designation = Designation::CAPITOL;
+        (w, id)
+    }
+
+    fn people(w : &World) -> i32 {
+        w.sct.iter().flatten().map(|s| s.people).sum()
+    }
+
+    #[test]
+    fn plague_kills_around_where_it_strikes() {
+        let (mut w, id) = world();
+        let news = w.happen(id, Event::PLAGUE).unwrap();
+        assert_eq!(news.category, Category::DISASTER);
+        let (x, y) = news.at.unwrap();
+        let dead = 9000 - people(&w);
+        assert!(dead > 0 && news.text.contains(&format!("{} people die", dead)));
+        for (sx, col) in w.sct.iter().enumerate() {
+            for (sy, s) in col.iter().enumerate() {
+                let near = sx.abs_diff(x) <= 1 && sy.abs_diff(y) <= 1;
+                if s.owner as i32 == id {
+                    assert_eq!(s.people < 1000, near);
+                    assert!(s.people >= 500);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn volcano_devastates_its_neighbours() {
+        let (mut w, id) = world();
+        assert!(w.happen(id, Event::VOLCANO).is_none());
+
+        w.sct[3][1].vegetation = Vegetation::VOLCANO;
+        w.sct[2][1].designation = Designation::FARM;
+        w.sct[2][1].foodstore = 50;
+        let news = w.happen(id, Event::VOLCANO).unwrap();
+        assert_eq!(news.at, Some((3, 1)));
+        assert_eq!((w.sct[2][1].people, w.sct[2][1].foodstore), (500, 0));
+        assert_eq!(w.sct[2][1].designation, Designation::DEVASTATED);
+        assert_eq!(w.sct[1][1].people, 1000);
+        assert_eq!(people(&w), 9000 - 3 * 500);
+    }
+
+    #[test]
+    fn strikes_add_jewels_and_metal() {
+        let (mut w, id) = world();
+        let news = w.happen(id, Event::GOLD_STRIKE).unwrap();
+        assert_eq!(news.category, Category::BLESSING);
+        let (x, y) = news.at.unwrap();
+        assert!((1..=4).contains(&w.sct[x][y].jewels));
+
+        let (x, y) = w.happen(id, Event::METAL_STRIKE).unwrap().at.unwrap();
+        assert!((1..=4).contains(&w.sct[x][y].metal));
+    }
+
+    #[test]
+    fn storms_sink_a_third_of_a_fleet_at_sea() {
+        let (mut w, id) = world();
+        w.ntn[0].nvy.push(Navy { warships: 3, merchant: 4, xloc: 4, yloc: 4, ..Default::default() });
+        assert!(w.happen(id, Event::STORM).is_none(), "in port");
+
+        w.sct[4][4].altitude = Elevation::WATER;
+        w.happen(id, Event::STORM).unwrap();
+        let f = &w.ntn[0].nvy[0];
+        assert_eq!((f.warships, f.merchant, f.galleys), (2, 2, 0));
+        w.ntn[0].nvy[0] = Navy { warships: 1, xloc: 4, yloc: 4, ..Default::default() };
+        w.happen(id, Event::STORM).unwrap();
+        assert!(w.ntn[0].nvy.is_empty());
+    }
+
+    #[test]
+    fn harvest_feeds_the_nation_from_its_farms() {
+        let (mut w, id) = world();
+        assert!(w.happen(id, Event::HARVEST).is_none());
+        w.sct[1][1].designation = Designation::FARM;
+        w.sct[2][2].designation = Designation::FARM;
+        w.sct[2][2].people = 300;
+        w.happen(id, Event::HARVEST).unwrap();
+        assert_eq!(w.ntn[0].tfood, 1300);
+    }
+
+    #[test]
+    fn peasants_break_away_but_monsters_have_none() {
+        let (mut w, id) = world();
+        let news = w.happen(id, Event::PEASANT_REVOLT).unwrap();
+        let (x, y) = news.at.unwrap();
+        assert_ne!((x, y), (0, 0), "never the capitol");
+        assert_eq!(w.ntn.len(), 2);
+        assert_eq!(w.sct[x][y].owner as i32, w.ntn[1].id);
+
+        let (mut w, id) = world();
+        w.ntn[0].race = Race::SAVAGE;
+        assert!(w.happen(id, Event::PEASANT_REVOLT).is_none());
+        w.karma = -KARMA_MAX;
+        for _ in 0..200 {
+            w.update_events();
+        }
+        assert!(w.news.is_empty());
+        assert_eq!(w.ntn.len(), 1);
+    }
+}
@@ -0,0 +1,429 @@
+//! God mode.
+//!
+//! The game master plays as a nation of Race::GOD and can reach anything:
+//! any field of any sector, a nation's attributes, armies and fleets picked
+//! up and set down anywhere, nations made and unmade, the turn run early.
+//! It goes through World::god() the way a player's orders go through
+//! execute(), and every attempt, done or refused, goes in World::audit so
+//! a dispute can be settled from the record.
+//!
+//! Fields are named as in Sector and Nation and values are given as text,
+//! enums by their map characters, so an admin console can pass along what
+//! it was typed.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::news::Category;
+use crate::{Army, Designation, Elevation, Nation, Race, Sector, Vegetation, World, DEFEND, UNMET};
+
+const START_PEOPLE      : i32 = 1000;   // in a new nation's capitol
+const START_SOLDIERS    : i32 = 300;    // its first army
+const START_GOLD        : i32 = 5000;
+const START_FOOD        : i32 = 10000;
+const START_MOVE        : u8 = 6;
+
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GodCommand {
+    EditSector  { x : usize, y : usize, field : String, value : String },
+    EditNation  { nation : i32, field : String, value : String },
+    Create      { name : String, race : Race, active : u8, at : Option<(usize, usize)> },  // capitol
+    Destroy     { nation : i32 },
+    MoveArmy    { nation : i32, army : usize, x : usize, y : usize },  // anywhere on land
+    MoveNavy    { nation : i32, navy : usize, x : usize, y : usize },  // anywhere at sea
+    Update,                                                         // run the turn now
+}
+
+impl GodCommand {
+    fn to_string(&self) -> String {
+        match self {
+            GodCommand::EditSector { x, y, field, value }
+                => format!("set sector {},{} {} to {:?}", x, y, field, value),
+            GodCommand::EditNation { nation, field, value }
+                => format!("set nation {} {} to {:?}", nation, field, value),
+            GodCommand::Create { name, race, active, at: Some((x, y)) }
+                => format!("create {} nation {:?} ({}) at {},{}", race, name, active, x, y),
+            GodCommand::Create { name, race, active, at: None }
+                => format!("create {} nation {:?} ({}) without land", race, name, active),
+            GodCommand::Destroy { nation }
+                => format!("destroy nation {}", nation),
+            GodCommand::MoveArmy { nation, army, x, y }
+                => format!("move nation {} army {} to {},{}", nation, army, x, y),
+            GodCommand::MoveNavy { nation, navy, x, y }
+                => format!("move nation {} navy {} to {},{}", nation, navy, x, y),
+            GodCommand::Update
+                => "run the turn update".to_string(),
+        }
+    }
+}
+
+impl fmt::Display for GodCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_string())
+    }
+}
+
+/// One line of the audit log.
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Audit {
+    pub turn        : i16,
+    pub god         : i32,              // nation id that asked
+    pub action      : String,           // what was asked, as GodCommand shows it
+    pub outcome     : Result<String, String>,
+}
+
+impl fmt::Display for Audit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.outcome {
+            Ok(done)    => write!(f, "turn {}: nation {}: {}: {}", self.turn, self.god, self.action, done),
+            Err(why)    => write!(f, "turn {}: nation {}: {}: REFUSED: {}", self.turn, self.god, self.action, why),
+        }
+    }
+}
+
+fn parse<T : FromStr>(field : &str, value : &str) -> Result<T, String> {
+    value.trim().parse().map_err(|_| format!("{:?} won't do for {}", value, field))
+}
+
+// The one character of value, if it is the map character of some E.
+fn parse_char<E, F>(field : &str, value : &str, to_char : F) -> Result<E, String>
+    where E : From<char>, F : Fn(&E) -> char
+{
+    let mut chars = value.trim().chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        let e = E::from(c);
+        if to_char(&e) == c {
+            return Ok(e);
+        }
+    }
+    Err(format!("{:?} won't do for {}", value, field))
+}
+
+/// Set a sector's field by name.
+pub fn edit_sector(s : &mut Sector, field : &str, value : &str) -> Result<(), String> {
+    match field {
+        "designation"   => s.designation = parse_char(field, value, Designation::to_char)?,
+        "altitude"      => s.altitude = parse_char(field, value, Elevation::to_char)?,
+        "vegetation"    => s.vegetation = parse_char(field, value, Vegetation::to_char)?,
+        "owner"         => s.owner = parse(field, value)?,
+        "people"        => s.people = parse(field, value)?,
+        "i_people"      => s.i_people = parse(field, value)?,
+        "jewels"        => s.jewels = parse(field, value)?,
+        "fortress"      => s.fortress = parse(field, value)?,
+        "metal"         => s.metal = parse(field, value)?,
+        "tradegood"     => s.tradegood = parse(field, value)?,
+        "region"        => s.region = parse(field, value)?,
+        "foodstore"     => s.foodstore = parse(field, value)?,
+        "jewelstore"    => s.jewelstore = parse(field, value)?,
+        "metalstore"    => s.metalstore = parse(field, value)?,
+        _               => return Err(format!("sectors have no {}", field)),
+    }
+    Ok(())
+}
+
+/// Set a nation's attribute by name.  The id, password, units, diplomacy
+/// and mail have their own ways in.
+pub fn edit_nation(n : &mut Nation, field : &str, value : &str) -> Result<(), String> {
+    match field {
+        "name"          => n.name = value.to_string(),
+        "leader"        => n.leader = value.to_string(),
+        "race"          => n.race = parse_char(field, value, Race::to_char)?,
+        "location"      => n.location = parse(field, value)?,
+        "mark"          => n.mark = parse(field, value)?,
+        "capx"          => n.capx = parse(field, value)?,
+        "capy"          => n.capy = parse(field, value)?,
+        "active"        => n.active = parse(field, value)?,
+        "maxmove"       => n.maxmove = parse(field, value)?,
+        "repro"         => n.repro = parse(field, value)?,
+        "score"         => n.score = parse(field, value)?,
+        "tgold"         => n.tgold = parse(field, value)?,
+        "jewels"        => n.jewels = parse(field, value)?,
+        "tmil"          => n.tmil = parse(field, value)?,
+        "tciv"          => n.tciv = parse(field, value)?,
+        "metals"        => n.metals = parse(field, value)?,
+        "tfood"         => n.tfood = parse(field, value)?,
+        "powers"        => n.powers = parse(field, value)?,
+        "class"         => n.class = parse(field, value)?,
+        "aplus"         => n.aplus = parse(field, value)?,
+        "dplus"         => n.dplus = parse(field, value)?,
+        "spellpts"      => n.spellpts = parse(field, value)?,
+        "tsctrs"        => n.tsctrs = parse(field, value)?,
+        "tships"        => n.tships = parse(field, value)?,
+        "inflation"     => n.inflation = parse(field, value)?,
+        "charity"       => n.charity = parse(field, value)?,
+        "tax_rate"      => n.tax_rate = parse(field, value)?,
+        "prestige"      => n.prestige = parse(field, value)?,
+        "popularity"    => n.popularity = parse(field, value)?,
+        "power"         => n.power = parse(field, value)?,
+        "communications" => n.communications = parse(field, value)?,
+        "wealth"        => n.wealth = parse(field, value)?,
+        "eatrate"       => n.eatrate = parse(field, value)?,
+        "spoilrate"     => n.spoilrate = parse(field, value)?,
+        "knowledge"     => n.knowledge = parse(field, value)?,
+        "farm_ability"  => n.farm_ability = parse(field, value)?,
+        "mine_ability"  => n.mine_ability = parse(field, value)?,
+        "poverty"       => n.poverty = parse(field, value)?,
+        "terror"        => n.terror = parse(field, value)?,
+        "reputation"    => n.reputation = parse(field, value)?,
+        "karma"         => n.karma = parse(field, value)?,
+        _               => return Err(format!("nations have no {} god can set", field)),
+    }
+    Ok(())
+}
+
+impl World {
+    /// Carry out cmd for god, the nation id asking, and note it in the
+    /// audit log whatever happens.  Says what was done.
+    pub fn god(&mut self, god : i32, cmd : &GodCommand) -> Result<String, String> {
+        let outcome = match self.nation(god) {
+            Some(n) if n.race == Race::GOD  => self.god_command(cmd),
+            _                               => Err("only god can do that".to_string()),
+        };
+        self.audit.push(Audit { turn: self.turn, god, action: cmd.to_string(), outcome: outcome.clone() });
+        outcome
+    }
+
+    fn god_command(&mut self, cmd : &GodCommand) -> Result<String, String> {
+        match *cmd {
+            GodCommand::EditSector { x, y, ref field, ref value } => {
+                if !self.on_map(x as i32, y as i32) {
+                    return Err(format!("{},{} is off the map", x, y));
+                }
+                let mut s = self.sct[x][y].clone();
+                edit_sector(&mut s, field, value)?;
+                if s.owner != 0 && self.nation_index(s.owner as i32).is_none() {
+                    return Err(format!("there is no nation {}", s.owner));
+                }
+                self.sct[x][y] = s;
+                Ok(format!("sector {},{} {} is now {}", x, y, field, value))
+            },
+            GodCommand::EditNation { nation, ref field, ref value } => {
+                let idx = self.nation_index(nation).ok_or("no such nation")?;
+                if field == "name" && (value.trim().is_empty() || self.ntn.iter().any(|n| n.name == *value)) {
+                    return Err(format!("{:?} won't do for a name", value));
+                }
+                edit_nation(&mut self.ntn[idx], field, value)?;
+                Ok(format!("{} {} is now {}", self.ntn[idx].name, field, value))
+            },
+            GodCommand::Create { ref name, ref race, active, at } => {
+                let id = self.create_nation(name, race.clone(), active, at)?;
+                Ok(format!("created nation {} {}", id, name))
+            },
+            GodCommand::Destroy { nation } => {
+                let name = self.destroy_nation(nation)?;
+                Ok(format!("destroyed {}", name))
+            },
+            GodCommand::MoveArmy { nation, army, x, y } => {
+                let idx = self.nation_index(nation).ok_or("no such nation")?;
+                if !self.on_map(x as i32, y as i32) || self.sct[x][y].altitude == Elevation::WATER {
+                    return Err(format!("armies can't stand at {},{}", x, y));
+                }
+                let a = self.ntn[idx].arm.get_mut(army).ok_or("no such army")?;
+                a.xloc = x as u8;
+                a.yloc = y as u8;
+                Ok(format!("army {} is at {},{}", army, x, y))
+            },
+            GodCommand::MoveNavy { nation, navy, x, y } => {
+                let idx = self.nation_index(nation).ok_or("no such nation")?;
+                if !self.on_map(x as i32, y as i32) || self.sct[x][y].altitude != Elevation::WATER {
+                    return Err(format!("fleets can't sail at {},{}", x, y));
+                }
+                let f = self.ntn[idx].nvy.get_mut(navy).ok_or("no such navy")?;
+                f.xloc = x as u8;
+                f.yloc = y as u8;
+                Ok(format!("navy {} is at {},{}", navy, x, y))
+            },
+            GodCommand::Update => {
+                if self.winner.is_some() {
+                    return Err("the game is over".to_string());
+                }
+                self.update();
+                Ok(format!("it is now turn {}", self.turn))
+            },
+        }
+    }
+
+    /// A new nation with a capitol at at, or with no land at all if at is
+    /// None.  Returns its id.
+    pub fn create_nation(&mut self, name : &str, race : Race, active : u8, at : Option<(usize, usize)>) -> Result<i32, String> {
+        if name.trim().is_empty() || self.ntn.iter().any(|n| n.name == name) {
+            return Err(format!("{:?} won't do for a name", name));
+        }
+        if let Some((x, y)) = at {
+            if !self.on_map(x as i32, y as i32) || self.sct[x][y].altitude == Elevation::WATER {
+                return Err(format!("no land at {},{}", x, y));
+            }
+            if self.sct[x][y].owner != 0 {
+                return Err(format!("{},{} is already owned", x, y));
+            }
+        }
+        let id = self.claim_id().ok_or("no room for more nations")?;
+        let mut n = Nation {
+            name            : name.to_string(),
+            race,
+            active,
+            mark            : self.free_mark(),
+            maxmove         : START_MOVE,
+            repro           : 5,
+            tax_rate        : 10,
+            popularity      : 50,
+            reputation      : 50,
+            prestige        : 20,
+            eatrate         : 10,
+            spoilrate       : 5,
+            knowledge       : 10,
+            farm_ability    : 10,
+            mine_ability    : 10,
+            ..Nation::with_id(id)
+        };
+        if let Some((x, y)) = at {
+            n.capx = x as u8;
+            n.capy = y as u8;
+            n.tgold = START_GOLD;
+            n.tfood = START_FOOD;
+            n.tsctrs = 1;
+            n.arm.push(Army { xloc: x as u8, yloc: y as u8, sold: START_SOLDIERS, stat: DEFEND, ..Default::default() });
+            let s = &mut self.sct[x][y];
+            s.owner = id as u8;
+            s.designation = Designation::CAPITOL;
+            s.people = START_PEOPLE;
+        }
+        let text = format!("the nation of {} is founded", n.name);
+        self.ntn.push(n);
+        self.report(Category::WORLD, &[id], at, text);
+        Ok(id)
+    }
+
+    /// Wipe nation id from the world.  Its land goes back to nobody, its
+    /// offers are withdrawn, its mail undelivered and nobody remembers it.
+    /// Returns its name.
+    pub fn destroy_nation(&mut self, id : i32) -> Result<String, String> {
+        let idx = self.nation_index(id).ok_or("no such nation")?;
+        let n = self.ntn.remove(idx);
+        for col in self.sct.iter_mut() {
+            for s in col.iter_mut() {
+                if s.owner as i32 == id {
+                    s.owner = 0;
+                }
+            }
+        }
+        self.forget_id(id);
+        self.report(Category::WORLD, &[], None, format!("the nation of {} is no more", n.name));
+        Ok(n.name)
+    }
+
+    // A free id for a new nation, with nothing left over from whoever
+    // last had it.
+    pub(crate) fn claim_id(&mut self) -> Option<i32> {
+        let id = self.free_id()?;
+        self.forget_id(id);
+        Some(id)
+    }
+
+    // Clear id out of everybody's diplomacy, the market and the mail, so
+    // the next nation to get it starts with a clean slate.
+    fn forget_id(&mut self, id : i32) {
+        for n in self.ntn.iter_mut() {
+            if n.status(id) != UNMET {
+                n.set_status(id, UNMET);
+            }
+            n.mail.retain(|m| m.from != id);
+        }
+        self.trades.retain(|o| o.seller != id);
+        for o in self.trades.iter_mut() {
+            o.bids.retain(|b| b.bidder != id);
+            if o.accepted == Some(id) {
+                o.accepted = None;
+            }
+        }
+        self.outbox.retain(|m| m.to != id && m.from != id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::land;
+    use crate::{PC, WAR};
+
+    fn world() -> (World, i32) {
+        let mut w = land(3, 3);
+        w.sct[2][2].altitude = Elevation::WATER;
+        let g = w.create_nation("Olympus", Race::GOD, 0, None).unwrap();
+        (w, g)
+    }
+
+    #[test]
+    fn god_edits_the_world_and_it_is_all_written_down() {
+        let (mut w, g) = world();
+        let c = GodCommand::Create { name: "Elfland".to_string(), race: Race::ELF, active: PC, at: Some((0, 0)) };
+        w.god(g, &c).unwrap();
+        let e = w.ntn[1].id;
+        assert_eq!((w.sct[0][0].owner as i32, w.ntn[1].arm.len()), (e, 1));
+
+        let set = |x, y, field : &str, value : &str| GodCommand::EditSector { x, y, field: field.to_string(), value: value.to_string() };
+        w.god(g, &set(1, 1, "designation", "m")).unwrap();
+        w.god(g, &set(1, 1, "people", "250")).unwrap();
+        assert_eq!((w.sct[1][1].designation.clone(), w.sct[1][1].people), (Designation::MINE, 250));
+        assert!(w.god(g, &set(1, 1, "designation", "Q")).is_err());
+        assert!(w.god(g, &set(1, 1, "owner", "99")).is_err());
+        assert!(w.god(e, &set(1, 1, "people", "0")).is_err());
+        assert_eq!(w.sct[1][1].people, 250);
+
+        let edit = GodCommand::EditNation { nation: e, field: "tgold".to_string(), value: "123".to_string() };
+        w.god(g, &edit).unwrap();
+        assert_eq!(w.ntn[1].tgold, 123);
+
+        assert!(w.god(g, &GodCommand::MoveArmy { nation: e, army: 0, x: 2, y: 2 }).is_err());
+        w.god(g, &GodCommand::MoveArmy { nation: e, army: 0, x: 2, y: 1 }).unwrap();
+        assert_eq!((w.ntn[1].arm[0].xloc, w.ntn[1].arm[0].yloc), (2, 1));
+
+        w.god(g, &GodCommand::Update).unwrap();
+        assert_eq!(w.turn, 1);
+
+        assert_eq!(w.audit.len(), 10);
+        assert_eq!(w.audit.iter().filter(|a| a.outcome.is_err()).count(), 4);
+        assert_eq!(w.audit[5].god, e);
+        assert!(w.audit[5].to_string().contains("REFUSED"));
+    }
+
+    #[test]
+    fn destroyed_nations_leave_nothing_behind() {
+        let (mut w, g) = world();
+        let e = w.create_nation("Elfland", Race::ELF, PC, Some((0, 0))).unwrap();
+        assert!(w.create_nation("Elfland", Race::ELF, PC, Some((1, 0))).is_err());
+        assert!(w.create_nation("Dwarfland", Race::DWARF, PC, Some((0, 0))).is_err());
+        assert!(w.create_nation("Dwarfland", Race::DWARF, PC, Some((2, 2))).is_err());
+        let d = w.create_nation("Dwarfland", Race::DWARF, PC, Some((1, 0))).unwrap();
+        w.ntn[1].arm.push(Army { sold: 10, ..Default::default() });
+        w.send_mail(d, e, "Greetings", "").unwrap();
+
+        w.god(g, &GodCommand::Destroy { nation: e }).unwrap();
+        assert!(w.nation(e).is_none());
+        assert_eq!(w.sct[0][0].owner, 0);
+        assert!(w.outbox.is_empty());
+        assert!(w.god(g, &GodCommand::Destroy { nation: e }).is_err());
+    }
+
+    #[test]
+    fn a_recycled_id_starts_with_a_clean_slate() {
+        let (mut w, g) = world();
+        let e = w.create_nation("Elfland", Race::ELF, PC, Some((0, 0))).unwrap();
+        let d = w.create_nation("Dwarfland", Race::DWARF, PC, Some((1, 0))).unwrap();
+        w.mail_delivery = crate::mail::Delivery::IMMEDIATE;
+        w.send_mail(e, d, "Beware", "").unwrap();
+        w.ntn[2].set_status(e, WAR);
+
+        w.god(g, &GodCommand::Destroy { nation: e }).unwrap();
+        let h = w.create_nation("Hobbiton", Race::HUMAN, PC, Some((0, 0))).unwrap();
+        assert_eq!(h, e);
+        assert_eq!(w.nation(d).unwrap().status(h), UNMET);
+        assert!(w.nation(d).unwrap().mail.is_empty());
+    }
+}
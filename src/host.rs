@@ -0,0 +1,213 @@
+//! Hosting a game for the network front ends.
+//!
+//! A single game thread owns the World.  Front ends (server.rs for the line
+//! protocol, web.rs for HTTP) never touch it themselves: they hand the host
+//! a job, a closure to run against the world, and wait for its answer.
+//! Jobs run one at a time in the order they arrive, which is all the
+//! locking a game needs.  A job says whether it changed the game, and if
+//! a save file was given the world is saved before the answer goes back.
+//!
+//! After every job the host looks for news worth pushing, a new turn or
+//! letters that have reached a mailbox, and sends an Event to everyone who
+//! subscribed.  Trouble no job can be told about, a save that failed, goes
+//! to Host::errors for whoever runs the host to log.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread::{self, JoinHandle};
+
+use crate::World;
+
+// A job's work, which says whether the game changed and hands back the
+// answer to send once it is saved.
+type Call = Box<dyn FnOnce(&mut World) -> (bool, Box<dyn FnOnce()>) + Send>;
+
+enum Job {
+    Call(Call),
+    Subscribe(Sender<Event>),
+    Stop,
+}
+
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(PartialEq)]
+pub enum Event {
+    Turn        { turn : i16 },                                 // an update has run
+    Mail        { to : i32, id : u32, from : i32, subject : String },   // a letter arrived
+}
+
+/// Somewhere to send jobs.  Cheap to clone, one for each session.
+#[derive(Clone)]
+pub struct Handle {
+    queue           : Sender<Job>,
+}
+
+pub struct Host {
+    handle          : Handle,
+    game            : JoinHandle<World>,
+    errors          : Receiver<String>,
+}
+
+// Letter ids in every mailbox, to see what's new.
+fn mailboxes(w : &World) -> HashMap<i32, HashSet<u32>> {
+    w.ntn.iter().map(|n| (n.id, n.mail.iter().map(|m| m.id).collect())).collect()
+}
+
+fn game(mut w : World, jobs : Receiver<Job>, save : Option<String>, errors : Sender<String>) -> World {
+    let mut subscribers : Vec<Sender<Event>> = Vec::new();
+    let mut seen = mailboxes(&w);
+    for job in jobs {
+        let call = match job {
+            Job::Call(call)         => call,
+            Job::Subscribe(s)       => { subscribers.push(s); continue },
+            Job::Stop               => break,
+        };
+        let turn = w.turn;
+        let (changed, answer) = call(&mut w);
+        if !changed {
+            answer();
+            continue;
+        }
+        if let Some(path) = &save {
+            if let Err(e) = w.save_file(path) {
+                let _ = errors.send(e);
+            }
+        }
+        answer();
+
+        let mut events = Vec::new();
+        if w.turn != turn {
+            events.push(Event::Turn { turn: w.turn });
+        }
+        for n in &w.ntn {
+            let old = seen.get(&n.id);
+            for m in n.mail.iter().filter(|m| !old.is_some_and(|o| o.contains(&m.id))) {
+                events.push(Event::Mail { to: n.id, id: m.id, from: m.from, subject: m.subject.clone() });
+            }
+        }
+        seen = mailboxes(&w);
+        for e in events {
+            subscribers.retain(|s| s.send(e.clone()).is_ok());
+        }
+    }
+    w
+}
+
+impl Host {
+    /// Start hosting w, saving to save after every change if it is given.
+    pub fn start(w : World, save : Option<String>) -> Host {
+        let (queue, jobs) = mpsc::channel();
+        let (report, errors) = mpsc::channel();
+        let game = thread::spawn(move || game(w, jobs, save, report));
+        Host { handle: Handle { queue }, game, errors }
+    }
+
+    pub fn handle(&self) -> Handle {
+        self.handle.clone()
+    }
+
+    /// What went wrong in the game thread, such as a save that failed.  Runs
+    /// dry once the game has stopped.
+    pub fn errors(&self) -> &Receiver<String> {
+        &self.errors
+    }
+
+    /// Wait for the game to be stopped and hand back the world.
+    pub fn join(self) -> World {
+        self.game.join().expect("the game thread died")
+    }
+
+    /// Finish what is queued and hand back the world.
+    pub fn stop(self) -> World {
+        self.handle.stop();
+        self.join()
+    }
+}
+
+impl Handle {
+    /// Run f against the world and return what it says, or None if the game
+    /// has stopped.  f returns whether it changed anything.
+    pub fn call<R, F>(&self, f : F) -> Option<R>
+        where R : Send + 'static, F : FnOnce(&mut World) -> (R, bool) + Send + 'static
+    {
+        let (reply, answer) = mpsc::channel();
+        let call : Call = Box::new(move |w| {
+            let (r, changed) = f(w);
+            let answer : Box<dyn FnOnce()> = Box::new(move || { let _ = reply.send(r); });
+            (changed, answer)
+        });
+        self.queue.send(Job::Call(call)).ok()?;
+        answer.recv().ok()
+    }
+
+    /// Every Event from now on.
+    pub fn subscribe(&self) -> Receiver<Event> {
+        let (s, r) = mpsc::channel();
+        let _ = self.queue.send(Job::Subscribe(s));
+        r
+    }
+
+    /// Stop the game once what is already queued is done.
+    pub fn stop(&self) {
+        let _ = self.queue.send(Job::Stop);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mail::Delivery;
+    use crate::testing::players;
+
+    #[test]
+    fn jobs_run_in_the_order_they_arrive() {
+        let (w, ..) = players();
+        let host = Host::start(w, None);
+        let (ran, order) = mpsc::channel();
+        for i in 0..5 {
+            let ran = ran.clone();
+            let call : Call = Box::new(move |w| {
+                w.turn = i;
+                let _ = ran.send(i);
+                (false, Box::new(|| {}))
+            });
+            host.handle.queue.send(Job::Call(call)).unwrap();
+        }
+        assert_eq!(host.handle().call(|w| (w.turn, false)), Some(4));
+
+        let w = host.stop();
+        assert_eq!(order.try_iter().collect::<Vec<_>>(), vec![0, 1, 2, 3, 4]);
+        assert_eq!(w.turn, 4);
+    }
+
+    #[test]
+    fn subscribers_hear_of_turns_and_letters() {
+        let (mut w, g, e, o) = players();
+        w.mail_delivery = Delivery::IMMEDIATE;
+        let host = Host::start(w, None);
+        let h = host.handle();
+        let events = h.subscribe();
+
+        let id = h.call(move |w| (w.send_mail(e, o, "Greetings", "").unwrap(), true)).unwrap();
+        h.call(move |w| (w.god(g, &crate::god::GodCommand::Update).is_ok(), true));
+        // nothing is pushed for a job that changed nothing
+        h.call(move |w| (w.send_mail(o, e, "Unsaid", "").unwrap(), false));
+        host.stop();
+
+        assert_eq!(events.iter().collect::<Vec<_>>(), vec![
+            Event::Mail { to: o, id, from: e, subject: "Greetings".to_string() },
+            Event::Turn { turn: 1 },
+        ]);
+    }
+
+    #[test]
+    fn a_failed_save_is_an_error_for_the_host() {
+        let (w, ..) = players();
+        let host = Host::start(w, Some("/nonexistent/rconq.save".to_string()));
+        host.handle().call(|w| { w.turn = 3; ((), true) });
+        host.handle().stop();
+        let errors : Vec<String> = host.errors().iter().collect();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(host.join().turn, 3);
+    }
+}
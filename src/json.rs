@@ -0,0 +1,45 @@
+//! Writing JSON for the front ends.
+//!
+//! Nothing here reads JSON; requests come in as words or form fields.  The
+//! answers are small enough to put together by hand.
+
+/// A JSON string.
+pub fn string(s : &str) -> String {
+    let mut q = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"'     => q += "\\\"",
+            '\\'    => q += "\\\\",
+            '\n'    => q += "\\n",
+            '\r'    => q += "\\r",
+            '\t'    => q += "\\t",
+            c if (c as u32) < 0x20 => q += &format!("\\u{:04x}", c as u32),
+            c       => q.push(c),
+        }
+    }
+    q.push('"');
+    q
+}
+
+/// A JSON array of values already written.
+pub fn list(items : &[String]) -> String {
+    format!("[{}]", items.join(", "))
+}
+
+/// A JSON object of values already written.
+pub fn object(fields : &[(&str, String)]) -> String {
+    let f : Vec<_> = fields.iter().map(|(k, v)| format!("{}: {}", string(k), v)).collect();
+    format!("{{{}}}", f.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strings_are_escaped() {
+        assert_eq!(string("say \"hi\"\n\u{1}"), "\"say \\\"hi\\\"\\n\\u0001\"");
+        let o = object(&[("a", list(&["1".to_string(), string("b")])), ("c", "null".to_string())]);
+        assert_eq!(o, "{\"a\": [1, \"b\"], \"c\": null}");
+    }
+}
@@ -0,0 +1,92 @@
+// World karma.
+//
+// Every nation carries the weight of what it has done: massacres, dark
+// masters and broken treaties drag its karma down, charity and honest trade
+// lift it.  World::karma is the sum of them all, and it is what the heavens
+// answer to.  Good karma makes blessings likelier and disasters rarer for
+// everyone, bad karma the reverse, so one nation's crimes are everybody's
+// problem.
+//
+// Karma lives in -KARMA_MAX..=KARMA_MAX.  A nation's fades a point a turn.
+
+use crate::{Nation, Race, World, C_DEMON, C_SHADOW};
+
+pub const KARMA_MAX     : i16 = 100;
+
+const KARMA_MASSACRE    : i32 = 10;     // per sector of civilians put to the sword
+const KARMA_TREATY      : i32 = 5;      // per treaty broken
+const KARMA_TRADE       : i32 = 1;      // per trade settled
+const KARMA_DARK        : i32 = 2;      // each turn under a demon or the undead
+const CHARITY_PER_KARMA : i32 = 10;     // percent of taxes given away per point
+
+// Change in a nation's karma for the turn.
+pub fn karma_delta(n : &Nation) -> i32 {
+    let d = &n.deeds;
+    let dark = if n.class == C_DEMON || n.class == C_SHADOW { KARMA_DARK } else { 0 };
+    let fade = -(n.karma as i32).signum();
+    fade
+        - KARMA_MASSACRE * d.massacres
+        - KARMA_TREATY * d.broken_treaties
+        + KARMA_TRADE * d.trades
+        + n.charity as i32 / CHARITY_PER_KARMA
+        - dark
+}
+
+// Per mille chance that an event with chance per mille happens given the
+// world's karma.  Good karma doubles blessings and stops disasters at the
+// top end, bad karma the other way around.
+pub fn karma_chance(chance : i32, good : bool, karma : i16) -> i32 {
+    let k = karma.clamp(-KARMA_MAX, KARMA_MAX) as i32;
+    let k = if good { k } else { -k };
+    chance * (KARMA_MAX as i32 + k) / KARMA_MAX as i32
+}
+
+impl World {
+    // The world's karma, from what its nations carry.
+    pub fn world_karma(&self) -> i16 {
+        let k : i32 = self.ntn.iter()
+            .filter(|n| n.race != Race::GOD)
+            .map(|n| n.karma as i32)
+            .sum();
+        k.clamp(-(KARMA_MAX as i32), KARMA_MAX as i32) as i16
+    }
+
+    // Weigh this turn's deeds.  Must come before update_politics() clears
+    // them.
+    pub(crate) fn update_karma(&mut self) {
+        for n in self.ntn.iter_mut() {
+            if n.race == Race::GOD {
+                continue;
+            }
+            n.karma = (n.karma as i32 + karma_delta(n)).clamp(-(KARMA_MAX as i32), KARMA_MAX as i32) as i16;
+        }
+        self.karma = self.world_karma();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::EVENTS;
+    use crate::PC;
+
+    #[test]
+    fn crimes_and_kindness_move_karma() {
+        let mut w = World::new(1, 1, 1);
+        w.ntn.push(Nation { race: Race::HUMAN, active: PC, charity: 30, ..Default::default() });
+        w.ntn.push(Nation { race: Race::ORC, active: PC, class: C_DEMON, ..Default::default() });
+        w.ntn[1].deeds.massacres = 1;
+        w.update_karma();
+        assert_eq!((w.ntn[0].karma, w.ntn[1].karma), (3, -12));
+        assert_eq!(w.karma, -9);
+    }
+
+    #[test]
+    fn karma_tilts_the_odds() {
+        for e in EVENTS.iter() {
+            assert_eq!(karma_chance(e.chance, e.good, 0), e.chance);
+            assert_eq!(karma_chance(e.chance, e.good, KARMA_MAX), if e.good { e.chance * 2 } else { 0 });
+            assert_eq!(karma_chance(e.chance, e.good, -KARMA_MAX), if e.good { 0 } else { e.chance * 2 });
+        }
+    }
+}
@@ -0,0 +1,283 @@
+//! rConq, a port of Conquer v4.10 to Rust.
+//!
+//! The whole game is a [`World`]: the map, every nation on it, and the
+//! turn.  Front ends load or make one, give orders through
+//! [`World::execute`], run turns with [`World::update`] and save it again.
+//! The command line, the line protocol server, the HTTP server and the
+//! Python bindings are all built on this and nothing else.
+//!
+//! The main types are here at the top, and grouped by topic in:
+//!
+//! * [`map`]: sectors and their terrain
+//! * [`nation`]: nations, races and diplomacy
+//! * [`military`]: armies and fleets
+//! * [`economy`]: budgets, taxes and upkeep
+//! * [`persistence`]: saving and loading games
+//! * [`render`]: pictures of the map
+//!
+//! Orders are [`command::Command`]s; mail, news and god's tools have
+//! modules of their own.  The rules themselves (combat, production,
+//! revolts and the rest) are private and run as part of the turn.
+
+#![allow(non_camel_case_types)]
+#![allow(clippy::upper_case_acronyms)]
+#![allow(clippy::inherent_to_string_shadow_display)]
+#![allow(clippy::derivable_impls)]
+
+pub mod auth;
+mod combat;
+pub mod command;
+pub mod economy;
+mod events;
+pub mod god;
+pub mod host;
+pub mod json;
+mod karma;
+pub mod mail;
+pub mod map;
+mod mercenary;
+pub mod military;
+mod monster;
+mod movement;
+pub mod nation;
+pub mod news;
+mod npc;
+pub mod persistence;
+mod politics;
+mod production;
+#[cfg(feature = "python")]
+mod python;
+pub mod render;
+mod revolt;
+mod rng;
+mod score;
+mod season;
+pub mod server;
+mod stats;
+mod trade;
+mod tradegood;
+mod units;
+mod update;
+mod vision;
+pub mod web;
+mod worldgen;
+
+use economy::Offer;
+use god::Audit;
+use mail::{Delivery, Mail};
+use military::Lair;
+use news::NewsItem;
+
+pub use map::{Designation, Elevation, Sector, Vegetation};
+pub use military::{Army, Navy};
+pub use nation::{Nation, Race};
+pub use rng::Rng;
+pub use score::Victory;
+pub use season::Season;
+pub use stats::{Drift, Rank, Stats};
+
+// The rest of the crate uses these as crate::NAME, as when they lived here.
+use map::distance;
+use military::*;
+use nation::*;
+
+/// The whole game: the map, the nations on it and everything a save holds.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct World {
+    pub mapx        : i16,          // size of world
+    pub mapy        : i16,          // size of world
+    pub othrntns    : i16,          // god, lizard...
+    pub turn        : i16,          // game turn
+    pub m_mil       : i32,          // number of mercs available
+    pub m_aplus     : i16,          // mercenary attack bonus
+    pub m_dplus     : i16,          // mercenary defense bonus
+    pub w_jewels    : i32,          // jewels in world
+    pub w_gold      : i32,          // gold talons in world
+    pub w_food      : i32,          // food in world
+    pub w_metal     : i32,          // metal in world
+    pub w_civ       : i32,          // world population
+    pub w_mil       : i32,          // world military
+    pub w_sctrs     : i32,          // owned sectors in world
+    pub score       : i32,          // world score total
+    pub karma       : i16,          // overall world alignment
+    pub ntn         : Vec<Nation>,  // not present in original
+    pub sct         : Vec<Vec<Sector>>, // sector map, indexed sct[x][y]
+    pub seed        : u64,          // random seed the world was made with
+    pub rng         : Rng,          // all game randomness comes from here
+    pub news        : Vec<NewsItem>,    // news of every turn so far
+    pub lairs       : Vec<Lair>,    // monster lairs on SPECIAL sectors
+    pub trades      : Vec<Offer>,   // open offers at the trading post
+    pub next_trade  : u32,          // id of the last offer posted
+    pub victory     : Victory,      // how the game can be won
+    pub winner      : Option<i32>,  // nation id, once the game is over
+    pub mail_delivery : Delivery,   // when player mail arrives
+    pub outbox      : Vec<Mail>,    // mail waiting for the end of the turn
+    pub next_mail   : u32,          // id of the last letter sent
+    pub audit       : Vec<Audit>,   // everything god has done
+}
+
+impl Default for World {
+    fn default() -> World {
+        World {
+            mapx        : 0,
+            mapy        : 0,
+            othrntns    : 0,
+            turn        : 0,
+            m_mil       : 0,
+            m_aplus     : 0,
+            m_dplus     : 0,
+            w_jewels    : 0,
+            w_gold      : 0,
+            w_food      : 0,
+            w_metal     : 0,
+            w_civ       : 0,
+            w_mil       : 0,
+            w_sctrs     : 0,
+            score       : 0,
+            karma       : 0,
+            ntn         : Vec::new(),
+            sct         : Vec::new(),
+            seed        : 0,
+            rng         : Rng::new(0),
+            news        : Vec::new(),
+            lairs       : Vec::new(),
+            trades      : Vec::new(),
+            next_trade  : 0,
+            victory     : Victory::default(),
+            winner      : None,
+            mail_delivery : Delivery::TURN,
+            outbox      : Vec::new(),
+            next_mail   : 0,
+            audit       : Vec::new(),
+        }
+    }
+}
+
+impl World {
+    /// Allocate an empty (all water) world of the given size.
+    pub fn new(mapx : i16, mapy : i16, seed : u64) -> World {
+        World {
+            mapx,
+            mapy,
+            seed,
+            rng         : Rng::new(seed),
+            sct         : vec![vec![Sector::default(); mapy as usize]; mapx as usize],
+            ..Default::default()
+        }
+    }
+
+    /// Position of the nation with the given id in ntn, if it still exists.
+    pub fn nation_index(&self, id : i32) -> Option<usize> {
+        self.ntn.iter().position(|n| n.id == id)
+    }
+
+    /// The nation with the given id, if it still exists.
+    pub fn nation(&self, id : i32) -> Option<&Nation> {
+        self.ntn.iter().find(|n| n.id == id)
+    }
+
+    /// A map mark nobody is using yet.
+    pub fn free_mark(&self) -> char {
+        ('A'..='Z').chain('a'..='z')
+            .find(|c| !self.ntn.iter().any(|n| n.mark == *c))
+            .unwrap_or('*')
+    }
+
+    /// The lowest nation id no nation or sector here is using, if any is
+    /// left that Sector::owner can hold.
+    pub fn free_id(&self) -> Option<i32> {
+        (1..=u8::MAX as i32).find(|&id| self.nation_index(id).is_none()
+            && !self.sct.iter().flatten().any(|s| s.owner as i32 == id))
+    }
+
+    /// Is (x, y) on the map?
+    pub fn on_map(&self, x : i32, y : i32) -> bool {
+        x >= 0 && y >= 0 && x < self.mapx as i32 && y < self.mapy as i32
+    }
+
+    /// The up to eight sectors around (x, y) that are on the map.
+    pub fn neighbours(&self, x : usize, y : usize) -> Vec<(usize, usize)> {
+        let mut v = Vec::new();
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                let (nx, ny) = (x as i32 + dx, y as i32 + dy);
+                if (dx != 0 || dy != 0) && self.on_map(nx, ny) {
+                    v.push((nx as usize, ny as usize));
+                }
+            }
+        }
+        v
+    }
+
+    /// A random entry of v, if there are any.
+    pub fn pick(&mut self, v : &[(usize, usize)]) -> Option<(usize, usize)> {
+        if v.is_empty() {
+            return None;
+        }
+        Some(v[self.rng.below(v.len() as u32) as usize])
+    }
+
+    /// Index of the nation that owns (x, y), or None if nobody (or god) does.
+    pub fn sector_owner(&self, x : usize, y : usize) -> Option<usize> {
+        self.nation_index(self.sct[x][y].owner as i32)
+            .filter(|&i| self.ntn[i].race != Race::GOD)
+    }
+
+    /// All sectors owned by nation id, as (x, y) pairs.
+    pub fn owned_sectors(&self, id : i32) -> Vec<(usize, usize)> {
+        let mut v = Vec::new();
+        for (x, col) in self.sct.iter().enumerate() {
+            for (y, s) in col.iter().enumerate() {
+                if s.owner as i32 == id && s.altitude != Elevation::WATER {
+                    v.push((x, y));
+                }
+            }
+        }
+        v
+    }
+}
+
+// Worlds for the tests all over the crate.
+#[cfg(test)]
+pub(crate) mod testing {
+    use super::*;
+
+    // width by height of clear, good land with nobody on it.
+    pub fn land(width : i16, height : i16) -> World {
+        let mut w = World::new(width, height, 1);
+        for col in w.sct.iter_mut() {
+            for s in col.iter_mut() {
+                s.altitude = Elevation::CLEAR;
+                s.vegetation = Vegetation::GOOD;
+            }
+        }
+        w
+    }
+
+    // Add n to w holding sectors, each with people in it.  Returns its id.
+    pub fn settle(w : &mut World, n : Nation, sectors : &[(usize, usize)], people : i32) -> i32 {
+        let id = n.id;
+        for &(x, y) in sectors {
+            let s = &mut w.sct[x][y];
+            s.owner = id as u8;
+            s.people = people;
+        }
+        w.ntn.push(n);
+        id
+    }
+
+    // 20x5 land with god (password "heaven"), Elfland ("mellon") at 1,1
+    // and Orcland ("grishnakh") at 18,3, out of sight of each other.
+    // Returns it and the three ids.
+    pub fn players() -> (World, i32, i32, i32) {
+        let mut w = land(20, 5);
+        let g = w.create_nation("god", Race::GOD, 0, None).unwrap();
+        let e = w.create_nation("Elfland", Race::ELF, PC, Some((1, 1))).unwrap();
+        let o = w.create_nation("Orcland", Race::ORC, PC, Some((18, 3))).unwrap();
+        w.reset_password(g, g, "heaven").unwrap();
+        w.reset_password(g, e, "mellon").unwrap();
+        w.reset_password(g, o, "grishnakh").unwrap();
+        (w, g, e, o)
+    }
+}
@@ -0,0 +1,205 @@
+//! Mail between nations.
+//!
+//! Every nation has a mailbox in Nation::mail.  Players write to each other
+//! and to god; the game itself writes too, with battle reports and word of
+//! trades, from SYSTEM.  Player mail either arrives at once or waits in
+//! World::outbox until the turn ends, depending on World::mail_delivery.
+//! System mail always arrives at once.  Mailboxes and the outbox are saved
+//! with the rest of the game.
+
+use std::fmt;
+
+use crate::combat::Battle;
+use crate::World;
+
+pub const SYSTEM        : i32 = 0;      // sender id of mail from the game itself
+const MAX_MAIL          : usize = 100;  // letters a mailbox holds before the oldest go
+
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Delivery {
+    IMMEDIATE,      // straight into the mailbox
+    TURN,           // when the turn ends, as in the original
+}
+
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Mail {
+    pub id          : u32,
+    pub from        : i32,      // nation id, or SYSTEM
+    pub to          : i32,      // nation id
+    pub turn        : i16,      // turn it was sent
+    pub subject     : String,
+    pub body        : String,
+    pub read        : bool,
+}
+
+impl fmt::Display for Mail {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Message {} sent turn {}", self.id, self.turn)?;
+        writeln!(f, "Subject: {}", self.subject)?;
+        write!(f, "{}", self.body)
+    }
+}
+
+impl World {
+    /// Nation from writes to nation to.  Returns the new letter's id.
+    pub fn send_mail(&mut self, from : i32, to : i32, subject : &str, body : &str) -> Result<u32, String> {
+        self.nation_index(from).ok_or("no such nation")?;
+        self.nation_index(to).ok_or("no one there to read it")?;
+        if subject.trim().is_empty() {
+            return Err("mail needs a subject".to_string());
+        }
+        let m = self.new_mail(from, to, subject, body);
+        let id = m.id;
+        match self.mail_delivery {
+            Delivery::IMMEDIATE => self.post_mail(m),
+            Delivery::TURN      => self.outbox.push(m),
+        }
+        Ok(id)
+    }
+
+    /// Mail from the game to nation to, delivered now.
+    pub fn system_mail(&mut self, to : i32, subject : &str, body : &str) {
+        if to != SYSTEM && self.nation_index(to).is_some() {
+            let m = self.new_mail(SYSTEM, to, subject, body);
+            self.post_mail(m);
+        }
+    }
+
+    fn new_mail(&mut self, from : i32, to : i32, subject : &str, body : &str) -> Mail {
+        self.next_mail += 1;
+        Mail {
+            id          : self.next_mail,
+            from,
+            to,
+            turn        : self.turn,
+            subject     : subject.to_string(),
+            body        : body.to_string(),
+            read        : false,
+        }
+    }
+
+    // Put m in its mailbox.  Mail to a nation that's gone is lost.
+    fn post_mail(&mut self, m : Mail) {
+        if let Some(idx) = self.nation_index(m.to) {
+            let mail = &mut self.ntn[idx].mail;
+            mail.push(m);
+            if mail.len() > MAX_MAIL {
+                mail.remove(0);
+            }
+        }
+    }
+
+    /// Hand out everything waiting in the outbox.
+    pub fn deliver_mail(&mut self) {
+        for m in std::mem::take(&mut self.outbox) {
+            self.post_mail(m);
+        }
+    }
+
+    /// Nation id's mailbox, oldest first.
+    pub fn inbox(&self, id : i32) -> Result<&[Mail], String> {
+        let idx = self.nation_index(id).ok_or("no such nation")?;
+        Ok(&self.ntn[idx].mail)
+    }
+
+    /// Read letter mail from nation id's mailbox, marking it read.
+    pub fn read_mail(&mut self, id : i32, mail : u32) -> Result<&Mail, String> {
+        let idx = self.nation_index(id).ok_or("no such nation")?;
+        let m = self.ntn[idx].mail.iter_mut().find(|m| m.id == mail).ok_or("no such letter")?;
+        m.read = true;
+        Ok(m)
+    }
+
+    pub fn delete_mail(&mut self, id : i32, mail : u32) -> Result<(), String> {
+        let idx = self.nation_index(id).ok_or("no such nation")?;
+        let box_ = &mut self.ntn[idx].mail;
+        let i = box_.iter().position(|m| m.id == mail).ok_or("no such letter")?;
+        box_.remove(i);
+        Ok(())
+    }
+
+    /// Tell both sides of a battle how it went: the attacker, and every
+    /// nation whose armies stood against it.
+    pub fn battle_mail(&mut self, b : &Battle) {
+        let name = |w : &World, id : i32| w.nation(id).map(|n| n.name.clone()).unwrap_or("nobody".to_string());
+        let att = name(self, b.attacker);
+        let def = if b.defenders.is_empty() {
+            name(self, b.defender)
+        } else {
+            b.defenders.iter().map(|&d| name(self, d)).collect::<Vec<_>>().join(" and ")
+        };
+        let subject = format!("Battle at {},{}", b.x, b.y);
+        self.system_mail(b.attacker, &subject, &format!(
+            "We attacked {} at {},{} and {}.\nWe lost {} men; they lost {}.",
+            def, b.x, b.y, if b.won { "won" } else { "were thrown back" }, b.att_loss, b.def_loss));
+        for &d in &b.defenders {
+            self.system_mail(d, &subject, &format!(
+                "{} attacked us at {},{} and {}.\nWe lost {} men; they lost {}.",
+                att, b.x, b.y, if b.won { "took the field" } else { "was beaten off" }, b.def_loss, b.att_loss));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Army, Nation, Race, PC};
+
+    fn world(delivery : Delivery) -> (World, i32, i32) {
+        let mut w = World::new(1, 1, 1);
+        w.mail_delivery = delivery;
+        let (a, b) = (Nation { race: Race::ELF, active: PC, ..Default::default() },
+            Nation { race: Race::DWARF, active: PC, ..Default::default() });
+        let ids = (a.id, b.id);
+        w.ntn.push(a);
+        w.ntn.push(b);
+        (w, ids.0, ids.1)
+    }
+
+    #[test]
+    fn mail_waits_for_the_turn() {
+        let (mut w, a, b) = world(Delivery::TURN);
+        let m = w.send_mail(a, b, "Peace", "Shall we?").unwrap();
+        assert!(w.inbox(b).unwrap().is_empty());
+        w.update();
+        let got = &w.inbox(b).unwrap()[0];
+        assert_eq!((got.id, got.from, got.read), (m, a, false));
+        assert!(w.read_mail(b, m).unwrap().read);
+        w.delete_mail(b, m).unwrap();
+        assert!(w.inbox(b).unwrap().is_empty());
+        assert!(w.delete_mail(b, m).is_err());
+    }
+
+    #[test]
+    fn everyone_who_fought_hears_of_it_and_the_owner_only_if_it_fought() {
+        let (mut w, a, b) = world(Delivery::IMMEDIATE);
+        let c = Nation { race: Race::HUMAN, active: PC, ..Default::default() };
+        let c = crate::testing::settle(&mut w, c, &[], 0);
+        w.sct[0][0].owner = b as u8;
+        w.ntn[2].arm.push(Army { sold: 100, ..Default::default() });
+        let battle = w.attack(a, 100, 0, 0);
+        assert_eq!((battle.defender, battle.defenders.clone()), (b, vec![c]));
+        assert_eq!(w.inbox(c).unwrap().len(), 1);
+        assert!(w.inbox(b).unwrap().is_empty());
+        let (owner, helper) = (&w.ntn[1].deeds, &w.ntn[2].deeds);
+        assert_eq!((owner.victories + owner.defeats, helper.victories + helper.defeats), (0, 1));
+    }
+
+    #[test]
+    fn immediate_mail_and_battle_reports() {
+        let (mut w, a, b) = world(Delivery::IMMEDIATE);
+        w.send_mail(b, a, "War", "Prepare yourselves").unwrap();
+        assert_eq!(w.inbox(a).unwrap().len(), 1);
+        assert!(w.send_mail(a, 999, "Hello", "").is_err());
+
+        w.battle_mail(&Battle { x: 0, y: 0, attacker: a, defender: b, defenders: vec![b], att_loss: 10, def_loss: 50, won: true });
+        assert_eq!(w.inbox(a).unwrap()[1].from, SYSTEM);
+        assert!(w.inbox(b).unwrap()[0].body.contains("took the field"));
+    }
+}
@@ -0,0 +1,379 @@
+//! The map: terrain, what has been built on it, and who holds it.
+//!
+//! The world is a grid of [`Sector`]s, `World::sct[x][y]`, from (0, 0) in
+//! the top left.  Each has an [`Elevation`], a [`Vegetation`] and, once
+//! somebody builds on it, a [`Designation`].  All three go to and from the
+//! single characters the original used on its maps and in its files.
+
+use std::fmt;
+
+pub use crate::vision::UNSEEN;
+pub use crate::worldgen::GenOptions;
+
+/// How high a sector is.  WATER is sea; everything else is land.
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "python", pyo3::pyclass(eq, eq_int))]
+pub enum Elevation {
+	WATER,
+	PEAK,
+	MOUNTAIN,
+	HILL,
+	CLEAR,
+	UNKNOWN,
+}
+
+impl Elevation {
+    /// To get the integer version, just cast it "as i32".
+    pub fn to_char(&self) -> char {
+        match self {
+            // char           *ele = "~#^%-0";
+            Elevation::WATER       => '~',
+            Elevation::PEAK        => '#',
+            Elevation::MOUNTAIN    => '^',
+            Elevation::HILL        => '%',
+            Elevation::CLEAR       => '-',
+            _                      => '?',
+        }
+    }
+    pub fn to_string(&self) -> String {
+        match self {
+            Elevation::WATER        => "WATER".to_string(),
+            Elevation::PEAK         => "PEAK".to_string(),
+            Elevation::MOUNTAIN     => "MOUNTAIN".to_string(),
+            Elevation::HILL         => "HILL".to_string(),
+            Elevation::CLEAR        => "CLEAR".to_string(),
+            _                       => "UNKNOWN".to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Elevation {
+    // error[E0277]: `Elevation` doesn't implement `std::fmt::Display`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_string())
+    }
+}
+
+impl From<char> for Elevation {
+    fn from(c: char) -> Self {
+        match c {
+            '~' => Elevation::WATER,
+            '#' => Elevation::PEAK,
+            '^' => Elevation::MOUNTAIN,
+            '%' => Elevation::HILL,
+            '-' => Elevation::CLEAR,
+            _   => Elevation::UNKNOWN,
+        }
+    }
+}
+
+impl From<Elevation> for char {
+    fn from(e: Elevation) -> Self {
+        e.to_char()
+    }
+}
+
+impl From<Elevation> for String {
+    fn from(e: Elevation) -> Self {
+        e.to_string()
+    }
+}
+
+impl From<Elevation> for i32 {
+    fn from(e: Elevation) -> Self {
+        e as i32
+    }
+}
+
+/// What a sector has been made into.  NONE until somebody builds on it.
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "python", pyo3::pyclass(eq, eq_int))]
+pub enum Designation {
+    TOWN,
+    CITY,
+    MINE,
+    FARM,
+    DEVASTATED,
+    GOLDMINE,
+    FORT,
+    RUIN,
+    STOCKADE,
+    CAPITOL,
+    SPECIAL,
+    LUMBERYARD,
+    BLACKSMITH,
+    ROAD,
+    MILL,
+    GRANARY,
+    CHURCH,
+    UNIVERSITY,
+    NONE,
+    BASECAMP,
+	UNKNOWN,
+}
+
+impl Designation {
+    /// To get the integer version, just cast it "as i32".
+    pub fn to_char(&self) -> char {
+        match self {
+            // char           *des = "tcmfx$!&sC?lb+*g=u-P0";
+            Designation::TOWN           => 't',
+            Designation::CITY           => 'c',
+            Designation::MINE           => 'm',
+            Designation::FARM           => 'f',
+            Designation::DEVASTATED     => 'x',
+            Designation::GOLDMINE       => '$',
+            Designation::FORT           => '!',
+            Designation::RUIN           => '&',
+            Designation::STOCKADE       => 's',
+            Designation::CAPITOL        => 'C',
+            Designation::SPECIAL        => '?',
+            Designation::LUMBERYARD     => 'l',
+            Designation::BLACKSMITH     => 'b',
+            Designation::ROAD           => '+',
+            Designation::MILL           => '*',
+            Designation::GRANARY        => 'g',
+            Designation::CHURCH         => '=',
+            Designation::UNIVERSITY     => 'u',
+            Designation::NONE           => '-',
+            Designation::BASECAMP       => 'P',
+            _                           => '0',
+        }
+    }
+    pub fn to_string(&self) -> String {
+        match self {
+            Designation::TOWN           => "TOWN".to_string(),
+            Designation::CITY           => "CITY".to_string(),
+            Designation::MINE           => "MINE".to_string(),
+            Designation::FARM           => "FARM".to_string(),
+            Designation::DEVASTATED     => "DEVASTATED".to_string(),
+            Designation::GOLDMINE       => "GOLDMINE".to_string(),
+            Designation::FORT           => "FORT".to_string(),
+            Designation::RUIN           => "RUIN".to_string(),
+            Designation::STOCKADE       => "STOCKADE".to_string(),
+            Designation::CAPITOL        => "CAPITOL".to_string(),
+            Designation::SPECIAL        => "SPECIAL".to_string(),
+            Designation::LUMBERYARD     => "LUMBERYARD".to_string(),
+            Designation::BLACKSMITH     => "BLACKSMITH".to_string(),
+            Designation::ROAD           => "ROAD".to_string(),
+            Designation::MILL           => "MILL".to_string(),
+            Designation::GRANARY        => "GRANARY".to_string(),
+            Designation::CHURCH         => "CHURCH".to_string(),
+            Designation::UNIVERSITY     => "UNIVERSITY".to_string(),
+            Designation::NONE           => "NONE".to_string(),
+            Designation::BASECAMP       => "BASECAMP".to_string(),
+            _                           => "UNKNOWN".to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Designation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_string())
+    }
+}
+
+impl From<char> for Designation {
+    fn from(c: char) -> Self {
+        match c {
+            't' => Designation::TOWN           ,
+            'c' => Designation::CITY           ,
+            'm' => Designation::MINE           ,
+            'f' => Designation::FARM           ,
+            'x' => Designation::DEVASTATED     ,
+            '$' => Designation::GOLDMINE       ,
+            '!' => Designation::FORT           ,
+            '&' => Designation::RUIN           ,
+            's' => Designation::STOCKADE       ,
+            'C' => Designation::CAPITOL        ,
+            '?' => Designation::SPECIAL        ,
+            'l' => Designation::LUMBERYARD     ,
+            'b' => Designation::BLACKSMITH     ,
+            '+' => Designation::ROAD           ,
+            '*' => Designation::MILL           ,
+            'g' => Designation::GRANARY        ,
+            '=' => Designation::CHURCH         ,
+            'u' => Designation::UNIVERSITY     ,
+            '-' => Designation::NONE           ,
+            'P' => Designation::BASECAMP       ,
+            _   => Designation::UNKNOWN        ,
+        }
+    }
+}
+
+impl From<Designation> for char {
+    fn from(d: Designation) -> Self {
+        d.to_char()
+    }
+}
+
+impl From<Designation> for String {
+    fn from(d: Designation) -> Self {
+        d.to_string()
+    }
+}
+
+impl From<Designation> for i32 {
+    fn from(d: Designation) -> Self {
+        d as i32
+    }
+}
+
+/// What grows on a sector, which decides how much food it can give.
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "python", pyo3::pyclass(eq, eq_int))]
+pub enum Vegetation {
+    VOLCANO,
+    DESERT,
+    TUNDRA,
+    BARREN,
+    LT_VEG,
+    GOOD,
+    WOOD,
+    FOREST,
+    JUNGLE,
+    SWAMP,
+    ICE,
+    NONE,
+	UNKNOWN,
+}
+
+impl Vegetation {
+    /// To get the integer version, just cast it "as i32".
+    pub fn to_char(&self) -> char {
+        match self {
+            // char           *veg = "vdtblgwfjsi~0";
+            Vegetation::VOLCANO     => 'v',
+            Vegetation::DESERT      => 'd',
+            Vegetation::TUNDRA      => 't',
+            Vegetation::BARREN      => 'b',
+            Vegetation::LT_VEG      => 'l',
+            Vegetation::GOOD        => 'g',
+            Vegetation::WOOD        => 'w',
+            Vegetation::FOREST      => 'f',
+            Vegetation::JUNGLE      => 'j',
+            Vegetation::SWAMP       => 's',
+            Vegetation::ICE         => 'i',
+            Vegetation::NONE        => '~',
+            _                       => '0',
+        }
+    }
+    pub fn to_string(&self) -> String {
+        match self {
+            Vegetation::VOLCANO     => "VOLCANO".to_string(),
+            Vegetation::DESERT      => "DESERT".to_string(),
+            Vegetation::TUNDRA      => "TUNDRA".to_string(),
+            Vegetation::BARREN      => "BARREN".to_string(),
+            Vegetation::LT_VEG      => "LT VEG".to_string(),
+            Vegetation::GOOD        => "GOOD".to_string(),
+            Vegetation::WOOD        => "WOOD".to_string(),
+            Vegetation::FOREST      => "FOREST".to_string(),
+            Vegetation::JUNGLE      => "JUNGLE".to_string(),
+            Vegetation::SWAMP       => "SWAMP".to_string(),
+            Vegetation::ICE         => "ICE".to_string(),
+            Vegetation::NONE        => "NONE".to_string(),
+            _                       => "UNKNOWN".to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Vegetation {
+    // error[E0277]: `Elevation` doesn't implement `std::fmt::Display`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_string())
+    }
+}
+
+impl From<char> for Vegetation {
+    fn from(c: char) -> Self {
+        match c {
+            'v' => Vegetation::VOLCANO    ,
+            'd' => Vegetation::DESERT     ,
+            't' => Vegetation::TUNDRA     ,
+            'b' => Vegetation::BARREN     ,
+            'l' => Vegetation::LT_VEG     ,
+            'g' => Vegetation::GOOD       ,
+            'w' => Vegetation::WOOD       ,
+            'f' => Vegetation::FOREST     ,
+            'j' => Vegetation::JUNGLE     ,
+            's' => Vegetation::SWAMP      ,
+            'i' => Vegetation::ICE        ,
+            '~' => Vegetation::NONE       ,
+            _   => Vegetation::UNKNOWN    ,
+        }
+    }
+}
+
+impl From<Vegetation> for char {
+    fn from(v: Vegetation) -> Self {
+        v.to_char()
+    }
+}
+
+impl From<Vegetation> for String {
+    fn from(v: Vegetation) -> Self {
+        v.to_string()
+    }
+}
+
+impl From<Vegetation> for i32 {
+    fn from(v: Vegetation) -> Self {
+        v as i32
+    }
+}
+
+/// One square of the map.
+#[derive(Debug)]
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Sector {
+    pub designation         : Designation,  // designation of sector
+    pub altitude            : Elevation,    // sector altitude
+    pub vegetation          : Vegetation,   // sector vegetation
+    pub owner               : u8,       // nation id of owner
+    pub people              : i32,      // civilians in sector
+    pub i_people            : i16,      // initial civilians in sector
+    pub jewels              : u8,       // jewel production ability
+    pub fortress            : u8,       // fortification level; 0 to 12
+    pub metal               : u8,       // metal produced by sector
+    pub tradegood           : u8,       // exotic trade goods in sector
+    pub region              : u8,       // unused, index of region
+    pub foodstore           : i32,      // food stored in sector
+    pub jewelstore          : i32,      // jewels stored in sector
+    pub metalstore          : i32,      // metal stored in sector
+}
+
+impl Default for Sector {
+    fn default() -> Sector {
+        Sector {
+            designation     : Designation::NONE,
+            altitude        : Elevation::WATER,
+            vegetation      : Vegetation::NONE,
+            owner           : 0,
+            people          : 0,
+            i_people        : 0,
+            jewels          : 0,
+            fortress        : 0,
+            metal           : 0,
+            tradegood       : 0,
+            region          : 0,
+            foodstore       : 0,
+            jewelstore      : 0,
+            metalstore      : 0,
+        }
+    }
+}
+
+/// Moves between two sectors, diagonals count as one like in the original.
+pub fn distance(x1 : i32, y1 : i32, x2 : i32, y2 : i32) -> i32 {
+    (x1 - x2).abs().max((y1 - y2).abs())
+}
@@ -0,0 +1,95 @@
+// The world mercenary market.
+//
+// Soldiers a nation lets go don't go home; they join the pool in
+// World::m_mil and sell their swords to whoever pays.  The pool fights with
+// its own bonuses, m_aplus and m_dplus, which are the average of what the
+// soldiers in it learned under their old flags.  Hired mercenaries carry
+// those bonuses with them and bring them back when they are let go again.
+
+use crate::units::MERCENARY;
+use crate::World;
+
+pub const MERC_COST     : i32 = 5;      // gold per mercenary hired
+const MERC_LEAVE        : i32 = 5;      // percent of the pool that wanders off each turn
+
+impl World {
+    // Add sold soldiers who fought with aplus and dplus to the pool.  The
+    // pool's bonuses move toward theirs in proportion to their numbers.
+    pub fn join_pool(&mut self, sold : i32, aplus : i16, dplus : i16) {
+        if sold <= 0 {
+            return;
+        }
+        let (old, new) = (self.m_mil.max(0) as i64, sold as i64);
+        let mix = |pool : i16, theirs : i16| ((pool as i64 * old + theirs as i64 * new) / (old + new)) as i16;
+        self.m_aplus = mix(self.m_aplus, aplus);
+        self.m_dplus = mix(self.m_dplus, dplus);
+        self.m_mil = (old + new).min(i32::MAX as i64) as i32;
+    }
+
+    // Bonuses the soldiers of army a in nation idx would bring to the pool.
+    pub fn pool_bonus(&self, idx : usize, a : usize) -> (i16, i16) {
+        let (n, army) = (&self.ntn[idx], &self.ntn[idx].arm[a]);
+        if army.unittyp == MERCENARY {
+            (army.aplus, army.dplus)
+        } else {
+            (n.aplus, n.dplus)
+        }
+    }
+
+    // Some of the pool gives up waiting for work.
+    pub(crate) fn update_mercenaries(&mut self) {
+        self.m_mil -= self.m_mil * MERC_LEAVE / 100;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::Command;
+    use crate::testing::{land, settle};
+    use crate::{Designation, Nation, Race, PC};
+
+    fn world() -> World {
+        let mut w = land(4, 4);
+        let n = Nation {
+            race        : Race::HUMAN,
+            active      : PC,
+            tgold       : 10000,
+            aplus       : 40,
+            dplus       : 20,
+            ..Default::default()
+        };
+        settle(&mut w, n, &[(1, 1)], 5000);
+        w.sct[1][1].designation = Designation::TOWN;
+        w
+    }
+
+    #[test]
+    fn pool_bonus_is_the_average_of_its_soldiers() {
+        let mut w = World::new(1, 1, 1);
+        w.join_pool(100, 40, 0);
+        assert_eq!((w.m_mil, w.m_aplus, w.m_dplus), (100, 40, 0));
+        w.join_pool(300, 0, 40);
+        assert_eq!((w.m_mil, w.m_aplus, w.m_dplus), (400, 10, 30));
+    }
+
+    #[test]
+    fn disbanded_soldiers_can_be_hired_back() {
+        let mut w = world();
+        let id = w.ntn[0].id;
+        w.execute(id, &Command::Draft { x: 1, y: 1, soldiers: 200 }).unwrap();
+        w.execute(id, &Command::Disband { army: 0 }).unwrap();
+        assert!(w.ntn[0].arm.is_empty());
+        assert_eq!((w.m_mil, w.m_aplus, w.m_dplus), (200, 40, 20));
+
+        w.ntn[0].aplus = 0;
+        let gold = w.ntn[0].tgold;
+        assert!(w.execute(id, &Command::Hire { x: 1, y: 1, soldiers: 201 }).is_err());
+        w.execute(id, &Command::Hire { x: 1, y: 1, soldiers: 150 }).unwrap();
+        assert_eq!(w.m_mil, 50);
+        assert_eq!(w.ntn[0].tgold, gold - 150 * MERC_COST);
+        let a = &w.ntn[0].arm[0];
+        assert_eq!((a.unittyp, a.sold, a.aplus, a.dplus), (MERCENARY, 150, 40, 20));
+        assert_eq!(a.force(0), 150 * 140 / 100);
+    }
+}
@@ -0,0 +1,78 @@
+//! Armies and fleets.
+//!
+//! A nation's units live in `Nation::arm` and `Nation::nvy`, and are known
+//! by their index there.  Orders for them go through `World::execute`.
+
+pub use crate::combat::Battle;
+pub use crate::units::{Lair, Monster};
+
+// Values for Army::stat, as in the original.
+pub const MARCH         : u8 = 1;
+pub const SCOUT         : u8 = 2;
+pub const GARRISON      : u8 = 3;
+pub const DEFEND        : u8 = 7;
+pub const ATTACK        : u8 = 9;
+
+/// An army of one unit type, somewhere on the map.
+#[derive(Debug)]
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Army {
+    pub unittyp         : u8,
+    pub xloc            : u8,
+    pub yloc            : u8,
+    pub smove           : u8,
+    pub sold            : i32,
+    pub stat            : u8,
+    pub aplus           : i16,          // mercenaries only, fight with these
+    pub dplus           : i16,          // and not their employer's
+}
+
+impl Default for Army {
+    fn default() -> Army {
+        Army {
+            unittyp    : 0,
+            xloc       : 0,
+            yloc       : 0,
+            smove      : 0,
+            sold       : 0,
+            stat       : 0,
+            aplus      : 0,
+            dplus      : 0,
+        }
+    }
+}
+
+/// A fleet of warships, merchant ships and galleys.
+#[derive(Debug)]
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Navy {
+    pub warships        : u16,
+    pub merchant        : u16,
+    pub galleys         : u16,
+    pub xloc            : u8,
+    pub yloc            : u8,
+    pub smove           : u8,               // movement ability of ship
+    pub crew            : u8,               // crew on ship
+    pub people          : u8,               // people carried
+    pub commodity       : u8,               // future commodities
+    pub armynum         : u8,               // army carried
+}
+
+impl Default for Navy {
+    fn default() -> Navy {
+        Navy {
+            warships        : 0,
+            merchant        : 0,
+            galleys         : 0,
+            xloc            : 0,
+            yloc            : 0,
+            smove           : 0,
+            crew            : 0,
+            people          : 0,
+            commodity       : 0,
+            armynum         : 0,
+        }
+    }
+}
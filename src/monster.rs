@@ -0,0 +1,334 @@
+// Monster nations: pirates, nomads and savages.
+//
+// These never really play, they just make life hard for everyone else, like
+// do_pirate(), do_nomad() and do_savage() in the original.  All their
+// choices come from the world's Rng, so a given seed always plays out the
+// same way.
+
+use crate::news::Category;
+use crate::{distance, Designation, Elevation, Race, World, ATTACK, DEFEND};
+
+const SAIL_MOVES        : i32 = 4;      // water sectors a pirate fleet covers in a turn
+const MARINES           : i32 = 50;     // raiders aboard each warship
+const NOMAD_MOVES       : i32 = 4;      // movement when the nation has no maxmove
+const SAVAGE_ATTACK     : i32 = 10;     // percent chance a savage army attacks a neighbour
+
+impl World {
+    // Let every monster nation have its turn.
+    pub(crate) fn update_monsters(&mut self) {
+        let monsters : Vec<(i32, Race)> = self.ntn.iter()
+            .map(|n| (n.id, n.race.clone()))
+            .collect();
+
+        for (id, race) in monsters {
+            match race {
+                Race::PIRATE    => self.do_pirate(id),
+                Race::NOMAD     => self.do_nomad(id),
+                Race::SAVAGE    => self.do_savage(id),
+                _               => {},
+            }
+        }
+    }
+
+    // Is (x, y) land belonging to an ordinary nation other than id?
+    fn prey(&self, x : usize, y : usize, id : i32) -> bool {
+        let s = &self.sct[x][y];
+        s.altitude != Elevation::WATER
+            && s.owner as i32 != id
+            && self.nation(s.owner as i32).is_some_and(|n| !n.race.is_special())
+    }
+
+    fn owner_name(&self, x : usize, y : usize) -> String {
+        self.nation(self.sct[x][y].owner as i32).map(|n| n.name.clone()).unwrap_or_default()
+    }
+
+    // Pirate fleets sail for the nearest inhabited coast and raid it.
+    fn do_pirate(&mut self, id : i32) {
+        let Some(idx) = self.nation_index(id) else { return };
+
+        for f in 0..self.ntn[idx].nvy.len() {
+            let fleet = &self.ntn[idx].nvy[f];
+            let (mut x, mut y) = (fleet.xloc as usize, fleet.yloc as usize);
+            let warships = fleet.warships as i32;
+            if warships == 0 || !self.on_map(x as i32, y as i32) {
+                continue;
+            }
+
+            let mut coast = Vec::new();
+            for (cx, col) in self.sct.iter().enumerate() {
+                for (cy, s) in col.iter().enumerate() {
+                    if s.people > 0 && self.prey(cx, cy, id)
+                        && self.neighbours(cx, cy).iter().any(|&(nx, ny)| self.sct[nx][ny].altitude == Elevation::WATER) {
+                        coast.push((cx, cy));
+                    }
+                }
+            }
+            let dist = |p : (usize, usize), q : (usize, usize)| distance(p.0 as i32, p.1 as i32, q.0 as i32, q.1 as i32);
+            let Some(target) = coast.into_iter().min_by_key(|&p| dist(p, (x, y))) else { continue };
+
+            // sail as close as the water allows
+            let mut moves = SAIL_MOVES;
+            while moves > 0 && dist((x, y), target) > 1 {
+                let next = self.neighbours(x, y).into_iter()
+                    .filter(|&(nx, ny)| self.sct[nx][ny].altitude == Elevation::WATER)
+                    .min_by_key(|&p| dist(p, target));
+                match next {
+                    Some(p) if dist(p, target) < dist((x, y), target) => (x, y) = p,
+                    _ => break,
+                }
+                moves -= 1;
+            }
+            self.ntn[idx].nvy[f].xloc = x as u8;
+            self.ntn[idx].nvy[f].yloc = y as u8;
+            if dist((x, y), target) > 1 {
+                continue;
+            }
+
+            let (tx, ty) = target;
+            let victim = self.owner_name(tx, ty);
+            let raiders = warships * MARINES;
+            let b = self.attack(id, raiders * (100 + self.ntn[idx].aplus as i32) / 100, tx, ty);
+            let sunk = (b.att_loss / MARINES).min(warships);
+            self.ntn[idx].nvy[f].warships -= sunk as u16;
+
+            if b.won {
+                let s = &mut self.sct[tx][ty];
+                s.people -= s.people / 10;
+                let wanted = s.people / 10;
+                let loot = match self.nation_index(b.defender) {
+                    Some(v) => {
+                        let loot = wanted.min(self.ntn[v].tgold.max(0));
+                        self.ntn[v].tgold -= loot;
+                        loot
+                    },
+                    None => 0,
+                };
+                self.ntn[idx].tgold += loot;
+                self.report(Category::MONSTERS, &[id, b.defender], Some((tx, ty)),
+                    format!("pirates raid {} at {},{} and carry off {} gold", victim, tx, ty, loot));
+            } else {
+                self.report(Category::MONSTERS, &[id, b.defender], Some((tx, ty)),
+                    format!("pirate raid on {} at {},{} beaten off; {} ships sunk", victim, tx, ty, sunk));
+            }
+        }
+        self.ntn[idx].nvy.retain(|f| f.warships > 0 || f.merchant > 0 || f.galleys > 0);
+    }
+
+    // Nomad armies wander at random and pillage whatever they stumble into.
+    fn do_nomad(&mut self, id : i32) {
+        let Some(idx) = self.nation_index(id) else { return };
+        let season = self.season();
+        let maxmove = match self.ntn[idx].maxmove {
+            0 => NOMAD_MOVES,
+            m => m as i32,
+        };
+
+        for a in 0..self.ntn[idx].arm.len() {
+            let army = &self.ntn[idx].arm[a];
+            let (mut x, mut y) = (army.xloc as usize, army.yloc as usize);
+            if army.sold <= 0 || !self.on_map(x as i32, y as i32) {
+                continue;
+            }
+
+            let mut moves = maxmove;
+            loop {
+                let options : Vec<((usize, usize), i32)> = self.neighbours(x, y).into_iter()
+                    .filter_map(|(nx, ny)| self.sct[nx][ny].move_cost(&season).map(|c| ((nx, ny), c)))
+                    .filter(|&(_, c)| c <= moves)
+                    .collect();
+                if options.is_empty() {
+                    break;
+                }
+                let ((nx, ny), cost) = options[self.rng.below(options.len() as u32) as usize];
+                (x, y) = (nx, ny);
+                moves -= cost;
+                if self.prey(x, y, id) {
+                    break;
+                }
+            }
+            self.ntn[idx].arm[a].xloc = x as u8;
+            self.ntn[idx].arm[a].yloc = y as u8;
+            self.ntn[idx].arm[a].stat = ATTACK;
+            if !self.prey(x, y, id) {
+                continue;
+            }
+
+            let victim = self.owner_name(x, y);
+            let force = self.ntn[idx].arm[a].force(self.ntn[idx].aplus);
+            let b = self.attack(id, force, x, y);
+            self.ntn[idx].arm[a].take_losses(force, b.att_loss);
+            if b.won {
+                let s = &mut self.sct[x][y];
+                s.people -= s.people / 5;
+                if s.designation == Designation::FARM {
+                    s.designation = Designation::DEVASTATED;
+                }
+                let wanted = s.people;
+                let food = match self.nation_index(b.defender) {
+                    Some(v) => {
+                        let food = wanted.min(self.ntn[v].tfood.max(0));
+                        self.ntn[v].tfood -= food;
+                        food
+                    },
+                    None => 0,
+                };
+                self.ntn[idx].tfood += food;
+                self.report(Category::MONSTERS, &[id, b.defender], Some((x, y)),
+                    format!("nomads pillage {} at {},{}", victim, x, y));
+            } else {
+                self.report(Category::MONSTERS, &[id, b.defender], Some((x, y)),
+                    format!("nomads driven off by {} at {},{}", victim, x, y));
+            }
+        }
+        self.ntn[idx].arm.retain(|a| a.sold > 0);
+    }
+
+    // Savages hold their ground, and now and then fall on a neighbour.
+    fn do_savage(&mut self, id : i32) {
+        let Some(idx) = self.nation_index(id) else { return };
+
+        for a in 0..self.ntn[idx].arm.len() {
+            self.ntn[idx].arm[a].stat = DEFEND;
+            let army = &self.ntn[idx].arm[a];
+            let (x, y) = (army.xloc as usize, army.yloc as usize);
+            if army.sold <= 0 || !self.on_map(x as i32, y as i32) || !self.rng.percent(SAVAGE_ATTACK) {
+                continue;
+            }
+
+            let targets : Vec<(usize, usize)> = self.neighbours(x, y).into_iter()
+                .filter(|&(nx, ny)| self.prey(nx, ny, id))
+                .collect();
+            let Some((tx, ty)) = self.pick(&targets) else { continue };
+
+            let victim = self.owner_name(tx, ty);
+            let force = self.ntn[idx].arm[a].force(self.ntn[idx].aplus);
+            let b = self.attack(id, force, tx, ty);
+            self.ntn[idx].arm[a].take_losses(force, b.att_loss);
+            if b.won && self.armies_at(tx, ty, id).is_empty() {
+                self.sct[tx][ty].owner = id as u8;
+                self.ntn[idx].arm[a].xloc = tx as u8;
+                self.ntn[idx].arm[a].yloc = ty as u8;
+                if let Some(v) = self.nation_index(b.defender) {
+                    self.ntn[v].deeds.lost += 1;
+                }
+                self.report(Category::MONSTERS, &[id, b.defender], Some((tx, ty)),
+                    format!("savages overrun {} at {},{}", victim, tx, ty));
+            } else {
+                self.report(Category::MONSTERS, &[id, b.defender], Some((tx, ty)),
+                    format!("savages attack {} at {},{} and are driven off", victim, tx, ty));
+            }
+        }
+        self.ntn[idx].arm.retain(|a| a.sold > 0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Army, Nation, Navy, Vegetation, PC};
+
+    // Farmland in columns 0-3, open sea from column 4 on.  Pirates are out
+    // at sea, nomads and savages already on the farmers' land.
+    fn world(seed : u64) -> World {
+        let mut w = World::new(10, 10, seed);
+        let farmers = Nation {
+            name        : String::from("Farmers"),
+            race        : Race::HUMAN,
+            active      : PC,
+            tgold       : 1000,
+            tfood       : 1000,
+            ..Default::default()
+        };
+        for x in 0..4 {
+            for y in 0..10 {
+                let s = &mut w.sct[x][y];
+                s.altitude = Elevation::CLEAR;
+                s.vegetation = Vegetation::GOOD;
+                s.designation = Designation::FARM;
+                s.owner = farmers.id as u8;
+                s.people = 500;
+            }
+        }
+        w.ntn.push(farmers);
+        w.ntn.push(Nation {
+            race        : Race::PIRATE,
+            nvy         : vec![Navy { warships: 10, xloc: 9, yloc: 5, ..Default::default() }],
+            ..Default::default()
+        });
+        w.ntn.push(Nation {
+            race        : Race::NOMAD,
+            arm         : vec![Army { sold: 500, xloc: 1, yloc: 1, ..Default::default() }],
+            ..Default::default()
+        });
+        w.ntn.push(Nation {
+            race        : Race::SAVAGE,
+            arm         : vec![Army { sold: 300, xloc: 0, yloc: 9, ..Default::default() }],
+            ..Default::default()
+        });
+        w
+    }
+
+    #[test]
+    fn same_seed_plays_the_same() {
+        let mut a = world(42);
+        let mut b = world(42);
+        for _ in 0..8 {
+            a.update_monsters();
+            b.update_monsters();
+        }
+        // nation ids differ between the two worlds, the story shouldn't
+        let text = |w : &World| w.news.iter().map(|n| n.text.clone()).collect::<Vec<_>>();
+        assert_eq!(text(&a), text(&b));
+        for (na, nb) in a.ntn.iter().zip(b.ntn.iter()) {
+            assert_eq!(format!("{:?}{:?}", na.arm, na.nvy), format!("{:?}{:?}", nb.arm, nb.nvy));
+            assert_eq!(na.tgold, nb.tgold);
+        }
+    }
+
+    #[test]
+    fn pirates_sail_in_and_raid() {
+        let mut w = world(7);
+        w.update_monsters();
+        assert_eq!(w.ntn[1].nvy[0].xloc, 5);
+        w.update_monsters();
+        assert!(w.news.iter().any(|n| n.text.starts_with("pirates raid Farmers")));
+        assert!(w.ntn[1].tgold > 0);
+    }
+
+    #[test]
+    fn savages_stand_their_ground() {
+        let mut w = world(3);
+        w.update_monsters();
+        assert!(w.ntn[3].arm.iter().all(|a| a.stat == DEFEND));
+    }
+
+    #[test]
+    fn nomads_wander_and_pillage() {
+        let mut w = world(5);
+        let nomads = w.ntn[2].id;
+        let mut been = Vec::new();
+        for _ in 0..4 {
+            w.do_nomad(nomads);
+            been.push((w.ntn[2].arm[0].xloc, w.ntn[2].arm[0].yloc));
+        }
+        assert!(been.iter().any(|&p| p != (1, 1)));
+        assert!(w.news.iter().any(|n| n.text.starts_with("nomads pillage Farmers")));
+        assert!(w.ntn[2].tfood > 0 && w.ntn[0].tfood < 1000);
+        assert!(w.sct.iter().flatten().any(|s| s.designation == Designation::DEVASTATED && s.people < 500));
+    }
+
+    #[test]
+    fn savages_fall_on_a_neighbour_and_keep_the_land() {
+        let mut w = world(11);
+        let savages = w.ntn[3].id;
+        for _ in 0..100 {
+            w.do_savage(savages);
+        }
+        assert!(w.news.iter().any(|n| n.text.starts_with("savages overrun Farmers")));
+        let a = &w.ntn[3].arm[0];
+        let (x, y) = (a.xloc as usize, a.yloc as usize);
+        assert_ne!((x, y), (0, 9));
+        assert_eq!(w.sct[x][y].owner as i32, savages);
+        assert!(w.ntn[0].deeds.lost > 0);
+    }
+}
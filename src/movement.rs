@@ -0,0 +1,70 @@
+// Movement costs.
+//
+// What it takes to enter a sector is its elevation cost plus its vegetation
+// cost, scaled by the season.  Water, volcanos and unknown land can't be
+// walked into at all.
+
+use crate::season::Season;
+use crate::{Elevation, Sector, Vegetation};
+
+fn ele_cost(ele : &Elevation) -> Option<i32> {
+    match ele {
+        Elevation::PEAK         => Some(6),
+        Elevation::MOUNTAIN     => Some(4),
+        Elevation::HILL         => Some(2),
+        Elevation::CLEAR        => Some(1),
+        _                       => None,
+    }
+}
+
+fn veg_cost(veg : &Vegetation) -> Option<i32> {
+    match veg {
+        Vegetation::VOLCANO     => None,
+        Vegetation::DESERT      => Some(2),
+        Vegetation::TUNDRA      => Some(1),
+        Vegetation::WOOD        => Some(1),
+        Vegetation::FOREST      => Some(2),
+        Vegetation::JUNGLE      => Some(3),
+        Vegetation::SWAMP       => Some(3),
+        Vegetation::ICE         => Some(2),
+        _                       => Some(0),
+    }
+}
+
+impl Sector {
+    // Movement points needed to enter this sector over land, if it can be
+    // entered at all.
+    pub fn move_cost(&self, season : &Season) -> Option<i32> {
+        let base = ele_cost(&self.altitude)? + veg_cost(&self.vegetation)?;
+        let pct = season.move_pct(&self.altitude, &self.vegetation);
+        Some((base * pct + 99) / 100)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sector(altitude : Elevation, vegetation : Vegetation) -> Sector {
+        Sector { altitude, vegetation, ..Default::default() }
+    }
+
+    #[test]
+    fn cost_is_elevation_plus_vegetation_by_season() {
+        let s = sector(Elevation::HILL, Vegetation::FOREST);
+        assert_eq!(s.move_cost(&Season::SUMMER), Some(4));
+        let s = sector(Elevation::MOUNTAIN, Vegetation::WOOD);
+        assert_eq!(s.move_cost(&Season::SUMMER), Some(5));
+        assert_eq!(s.move_cost(&Season::WINTER), Some(10));
+        // 3 * 125% rounds up
+        let s = sector(Elevation::CLEAR, Vegetation::ICE);
+        assert_eq!(s.move_cost(&Season::FALL), Some(4));
+    }
+
+    #[test]
+    fn water_and_volcanos_cant_be_walked_into() {
+        assert_eq!(sector(Elevation::WATER, Vegetation::GOOD).move_cost(&Season::SUMMER), None);
+        assert_eq!(sector(Elevation::CLEAR, Vegetation::VOLCANO).move_cost(&Season::SUMMER), None);
+        assert_eq!(sector(Elevation::UNKNOWN, Vegetation::GOOD).move_cost(&Season::SUMMER), None);
+    }
+}
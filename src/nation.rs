@@ -0,0 +1,328 @@
+//! Nations, their races, and how they stand with each other.
+//!
+//! Every nation, god included, has an id the world hands out when it is
+//! made: the lowest one from 1 to 255 nobody is using, see
+//! `World::free_id`.  The id is what goes in `Sector::owner`, in
+//! `Nation::dstatus` and in mail, so a nation keeps it for life, saves
+//! included.
+
+use std::fmt;
+use std::sync::atomic::AtomicI32;
+use std::sync::atomic::Ordering::SeqCst;
+
+use crate::mail::Mail;
+use crate::military::{Army, Navy};
+
+pub use crate::politics::Deeds;
+pub use crate::revolt::Revolt;
+
+/// The race of a nation.  GOD runs the game; PIRATE, SAVAGE and NOMAD are
+/// the monster nations.
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "python", pyo3::pyclass(eq, eq_int))]
+pub enum Race {
+    GOD,
+    ORC,
+    ELF,
+    DWARF,
+    LIZARD,
+    HUMAN,
+    PIRATE,
+    SAVAGE,
+    NOMAD,
+    UNKNOWN,
+}
+
+impl Race {
+    /// To get the integer version, just cast it "as i32".
+    pub fn to_char(&self) -> char {
+        match self {
+            Race::GOD           => '-',
+            Race::ORC           => 'O',
+            Race::ELF           => 'E',
+            Race::DWARF         => 'D',
+            Race::LIZARD        => 'L',
+            Race::HUMAN         => 'H',
+            Race::PIRATE        => 'P',
+            Race::SAVAGE        => 'S',
+            Race::NOMAD         => 'N',
+            _                   => '?',
+        }
+    }
+
+    pub fn to_string(&self) -> String {
+        match self {
+            Race::GOD           => "GOD".to_string(),
+            Race::ORC           => "ORC".to_string(),
+            Race::ELF           => "ELF".to_string(),
+            Race::DWARF         => "DWARF".to_string(),
+            Race::LIZARD        => "LIZARD".to_string(),
+            Race::HUMAN         => "HUMAN".to_string(),
+            Race::PIRATE        => "PIRATE".to_string(),
+            Race::SAVAGE        => "SAVAGE".to_string(),
+            Race::NOMAD         => "NOMAD".to_string(),
+            _                   => "UNKNOWN".to_string(),
+        }
+    }
+    /// God and the monster nations (pirates, savages, nomads) don't build,
+    /// tax or revolt like ordinary nations.
+    pub fn is_special(&self) -> bool {
+        matches!(self, Race::GOD | Race::PIRATE | Race::SAVAGE | Race::NOMAD)
+    }
+}
+
+impl fmt::Display for Race {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_string())
+    }
+}
+
+impl From<char> for Race {
+    fn from(c: char) -> Self {
+        match c {
+            '-' => Race::GOD,
+            'O' => Race::ORC,
+            'E' => Race::ELF,
+            'D' => Race::DWARF,
+            'L' => Race::LIZARD,
+            'H' => Race::HUMAN,
+            'P' => Race::PIRATE,
+            'S' => Race::SAVAGE,
+            'N' => Race::NOMAD,
+            _   => Race::UNKNOWN,
+        }
+    }
+}
+
+impl From<Race> for char {
+    fn from(r: Race) -> Self {
+        r.to_char()
+    }
+}
+
+impl From<Race> for String {
+    fn from(r: Race) -> Self {
+        r.to_string()
+    }
+}
+
+impl From<Race> for i32 {
+    fn from(r: Race) -> Self {
+        r as i32
+    }
+}
+
+// Values for Nation::class, as in the original.
+pub const C_NPC         : i16 = 0;
+pub const C_KING        : i16 = 1;
+pub const C_EMPEROR     : i16 = 2;
+pub const C_WIZARD      : i16 = 3;
+pub const C_PRIEST      : i16 = 4;
+pub const C_PIRATE      : i16 = 5;
+pub const C_TRADER      : i16 = 6;
+pub const C_WARLORD     : i16 = 7;
+pub const C_DEMON       : i16 = 8;
+pub const C_DRAGON      : i16 = 9;
+pub const C_SHADOW      : i16 = 10;     // lord of the undead
+
+// Values for Nation::active.  Anything from NPC_AGGRESSIVE up is run by the
+// computer, and the value picks its strategy.
+pub const INACTIVE          : u8 = 0;
+pub const PC                : u8 = 1;
+pub const NPC_AGGRESSIVE    : u8 = 2;
+pub const NPC_DEFENSIVE     : u8 = 3;
+pub const NPC_ISOLATIONIST  : u8 = 4;
+pub const NPC_TRADER        : u8 = 5;
+
+// Values for Nation::dstatus, as in the original.  Higher is less friendly.
+pub const UNMET         : i8 = 0;
+pub const TREATY        : i8 = 1;
+pub const ALLIED        : i8 = 2;
+pub const FRIENDLY      : i8 = 3;
+pub const NEUTRAL       : i8 = 4;
+pub const HOSTILE       : i8 = 5;
+pub const WAR           : i8 = 6;
+pub const JIHAD         : i8 = 7;
+
+/// A nation: its people, treasury, armies and fleets, and its standing
+/// with everybody else.
+#[derive(Debug)]
+//#[derive(Copy)]
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Nation {
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "keep_id"))]
+    pub id              : i32,          // unique naiton ID, established at allocation
+
+    pub name            : String,       // name, we do not want to deal with char arrays
+    pub passwd          : String,       // salted password hash, see auth.rs
+    pub leader          : String,       // leader title
+
+    pub race            : Race,         // national race
+    pub location        : i8,           // starting location quality, good, fair, random
+    pub mark            : char,         // unique symbol for nation display on map
+
+    pub capx            : u8,           // capitol x coordiante, is 16 bit in world
+    pub capy            : u8,           // capitol y coordinate, is 16 bit in world
+    pub active          : u8,           // nation type and strategy
+    pub maxmove         : u8,           // maximum movement of soldiers
+    pub repro           : i8,           // reproduction rate of nation
+
+    pub score           : i32,          // score
+    pub tgold           : i32,          // gold in treasury
+    pub jewels          : i32,          // raw amount of jewels in treasury
+    pub tmil            : i32,          // total military
+    pub tciv            : i32,          // total civilians
+    pub metals          : i32,          // total real metal in nation
+    pub tfood           : i32,          // total food in nation
+    pub powers          : i32,          // bit vector, should become boolean array
+
+    pub class           : i16,          // national class
+    pub aplus           : i16,          // attack plus of all soldiers
+    pub dplus           : i16,          // attack plus of all soldiers
+    pub spellpts        : i16,          // spell points
+    pub tsctrs          : i16,          // total number sectors
+    pub tships          : i16,          // number warships
+    pub inflation       : i16,          // inflation rate
+
+    pub charity         : u8,           // charity budget (% of Taxes)
+
+    pub arm             : Vec<Army>,    // array of army units
+    pub nvy             : Vec<Navy>,    // array of naval units
+    pub dstatus         : Vec<i8>,      // diplomatic status array
+
+    pub tax_rate        : u8,           // taxrate populace
+    pub prestige        : u8,           // nations prestige
+    pub popularity      : u8,           // governments popularity
+    pub power           : u8,           // nation power
+    pub communications  : u8,           // leader communication
+    pub wealth          : u8,           // per capita income
+    pub eatrate         : u8,           // food eaten / 10 people
+    pub spoilrate       : u8,           // food spoilage rate
+    pub knowledge       : u8,           // general knowledge
+    pub farm_ability    : u8,           // farming ability
+    pub mine_ability    : u8,           // mine ability
+    pub poverty         : u8,           // % poor people
+    pub terror          : u8,           // peoples terror of you
+    pub reputation      : u8,           // reputation of nation
+
+    pub deeds           : Deeds,        // what was done this turn, not present in original
+    pub karma           : i16,          // weight of the nation's deeds, not present in original
+    pub mail            : Vec<Mail>,    // mailbox, not present in original
+    pub failed_logins   : u8,           // wrong passwords in a row, not present in original
+    pub locked_until    : i16,          // turn a lockout ends, not present in original
+}
+
+static NATION_IDS : AtomicI32 = AtomicI32::new(1);
+
+// A nation read back from a snapshot keeps its id, and no new nation is
+// given it.
+#[cfg(feature = "serde")]
+fn keep_id<'de, D : serde::Deserializer<'de>>(d : D) -> Result<i32, D::Error> {
+    let id = <i32 as serde::Deserialize>::deserialize(d)?;
+    Nation::reserve_ids(id);
+    Ok(id)
+}
+
+impl Nation {
+    /// Our diplomatic status toward nation id.  dstatus is indexed by nation
+    /// id, the same number that goes in Sector::owner.
+    pub fn status(&self, id : i32) -> i8 {
+        self.dstatus.get(id as usize).copied().unwrap_or(UNMET)
+    }
+
+    pub fn set_status(&mut self, id : i32, status : i8) {
+        let i = id as usize;
+        if self.dstatus.len() <= i {
+            self.dstatus.resize(i + 1, UNMET);
+        }
+        self.dstatus[i] = status;
+    }
+
+    /// Ids start at 1; an owner of 0 means nobody holds the sector.
+    pub fn unique_id() -> i32 {
+        let id = NATION_IDS.fetch_add(1, SeqCst);
+        assert_ne!(id, i32::MAX, "ID counter has overflowed");
+        id
+    }
+
+    /// Make sure no id up to last is handed out again, for nations that come
+    /// back from a saved game.
+    pub fn reserve_ids(last : i32) {
+        NATION_IDS.fetch_max(last.saturating_add(1), SeqCst);
+    }
+
+    /// A blank nation with the given id, spending none from the counter.
+    pub fn with_id(id : i32) -> Nation {
+        Nation {
+            id,
+
+            name            : String::from("Bob"),
+            passwd          : String::from(""),
+            leader          : String::from(""),
+
+            race            : Race::UNKNOWN,
+            location        : 0,
+            mark            : ' ',
+
+            capx            : 0,
+            capy            : 0,
+            active          : 0,
+            maxmove         : 0,
+            repro           : 0,
+
+            score           : 0,
+            tgold           : 0,
+            jewels          : 0,
+            tmil            : 0,
+            tciv            : 0,
+            metals          : 0,
+            tfood           : 0,
+            powers          : 0,
+
+            class           : 0,
+            aplus           : 0,
+            dplus           : 0,
+            spellpts        : 0,
+            tsctrs          : 0,
+            tships          : 0,
+            inflation       : 0,
+
+            charity         : 0,
+
+            arm             : Vec::new(),
+            nvy             : Vec::new(),
+            dstatus         : Vec::new(),
+
+            tax_rate        : 0,
+            prestige        : 0,
+            popularity      : 0,
+            power           : 0,
+            communications  : 0,
+            wealth          : 0,
+            eatrate         : 0,
+            spoilrate       : 0,
+            knowledge       : 0,
+            farm_ability    : 0,
+            mine_ability    : 0,
+            poverty         : 0,
+            terror          : 0,
+            reputation      : 0,
+
+            deeds           : Default::default(),
+            karma           : 0,
+            mail            : Vec::new(),
+            failed_logins   : 0,
+            locked_until    : 0,
+        }
+    }
+}
+
+impl Default for Nation {
+    fn default() -> Nation {
+        Nation::with_id(Nation::unique_id())
+    }
+}
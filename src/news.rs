@@ -0,0 +1,152 @@
+//! The news.
+//!
+//! Everything worth telling goes into World::news as a NewsItem, stamped
+//! with the turn it happened in, the nations it concerns and where.  Nothing
+//! is thrown away, so any turn's paper can be printed again later.
+//! bulletin() sets a turn's items out as the newspaper the original
+//! published, headlines grouped by section.
+
+use std::fmt;
+
+use crate::season::{year, Season};
+use crate::World;
+
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Category {
+    WORLD,          // seasons, the end of the game
+    BATTLE,
+    CONQUEST,
+    DIPLOMACY,
+    REVOLT,
+    DISASTER,
+    BLESSING,
+    MONSTERS,
+    TRADE,
+}
+
+/// Sections of the bulletin, in the order they're printed.
+pub const SECTIONS : [Category; 9] = [
+    Category::WORLD,
+    Category::BATTLE,
+    Category::CONQUEST,
+    Category::DIPLOMACY,
+    Category::REVOLT,
+    Category::DISASTER,
+    Category::BLESSING,
+    Category::MONSTERS,
+    Category::TRADE,
+];
+
+impl Category {
+    fn to_string(&self) -> String {
+        match self {
+            Category::WORLD         => "WORLD".to_string(),
+            Category::BATTLE        => "BATTLE".to_string(),
+            Category::CONQUEST      => "CONQUEST".to_string(),
+            Category::DIPLOMACY     => "DIPLOMACY".to_string(),
+            Category::REVOLT        => "REVOLT".to_string(),
+            Category::DISASTER      => "DISASTER".to_string(),
+            Category::BLESSING      => "BLESSING".to_string(),
+            Category::MONSTERS      => "MONSTERS".to_string(),
+            Category::TRADE         => "TRADE".to_string(),
+        }
+    }
+
+    // Section heading in the bulletin.
+    fn heading(&self) -> &'static str {
+        match self {
+            Category::WORLD         => "THE WORLD",
+            Category::BATTLE        => "FROM THE FRONT",
+            Category::CONQUEST      => "CONQUESTS",
+            Category::DIPLOMACY     => "AFFAIRS OF STATE",
+            Category::REVOLT        => "UNREST",
+            Category::DISASTER      => "DISASTERS",
+            Category::BLESSING      => "GOOD FORTUNE",
+            Category::MONSTERS      => "MONSTERS AND MARAUDERS",
+            Category::TRADE         => "THE MARKETPLACE",
+        }
+    }
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_string())
+    }
+}
+
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NewsItem {
+    pub turn        : i16,
+    pub category    : Category,
+    pub nations     : Vec<i32>,     // ids of the nations involved
+    pub at          : Option<(usize, usize)>,   // where it happened
+    pub text        : String,
+}
+
+impl fmt::Display for NewsItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+impl World {
+    /// Report something that happened this turn.
+    pub fn report(&mut self, category : Category, nations : &[i32], at : Option<(usize, usize)>, text : String) {
+        let nations = nations.iter().copied().filter(|&id| id != 0).collect();
+        self.news.push(NewsItem { turn: self.turn, category, nations, at, text });
+    }
+
+    /// The news of a given turn.
+    pub fn news_of(&self, turn : i16) -> Vec<&NewsItem> {
+        self.news.iter().filter(|n| n.turn == turn).collect()
+    }
+
+    /// The newspaper for a turn.
+    pub fn bulletin(&self, turn : i16) -> String {
+        let items = self.news_of(turn);
+        let title = format!("THE CONQUER TIMES -- {} OF YEAR {}, TURN {}", Season::of(turn), year(turn), turn);
+        let mut b = format!("{}\n{}\n", title, "=".repeat(title.len()));
+        if items.is_empty() {
+            b += "\nA QUIET SEASON; NOTHING TO REPORT\n";
+        }
+        for section in SECTIONS.iter() {
+            let headlines : Vec<&&NewsItem> = items.iter().filter(|n| n.category == *section).collect();
+            if headlines.is_empty() {
+                continue;
+            }
+            b += &format!("\n{}\n", section.heading());
+            for n in headlines {
+                b += &format!("  * {}\n", n.text.to_uppercase());
+            }
+        }
+        b
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn old_news_is_kept_and_sorted_into_sections() {
+        let mut w = World::new(1, 1, 1);
+        w.report(Category::DISASTER, &[3], Some((0, 0)), "plague strikes Elves".to_string());
+        w.turn = 1;
+        w.report(Category::BATTLE, &[3, 4], None, "Elves attack Orcs".to_string());
+        w.report(Category::WORLD, &[], None, "summer of year 1 begins".to_string());
+
+        assert_eq!(w.news_of(0).len(), 1);
+        assert_eq!(w.news_of(1)[0].nations, vec![3, 4]);
+        let paper = w.bulletin(1);
+        assert!(paper.find("THE WORLD").unwrap() < paper.find("FROM THE FRONT").unwrap());
+        assert!(paper.contains("  * ELVES ATTACK ORCS\n"));
+        assert!(!paper.contains("PLAGUE"));
+        assert!(w.bulletin(7).contains("NOTHING TO REPORT"));
+    }
+}
@@ -0,0 +1,333 @@
+// Computer run kingdoms.
+//
+// An ordinary nation whose active is one of the NPC_* strategies plays
+// itself: it sets taxes, designates land, picks friends and enemies, drafts
+// and marches.  Everything goes through World::execute() as a Command, just
+// as a player's orders would.  The strategy only changes the numbers in its
+// Plan.
+
+use crate::command::{designate_cost, Command, ENLIST_COST};
+use crate::mercenary::MERC_COST;
+use crate::politics::{diplomacy_bias, recruit_limit};
+use crate::production::veg_food;
+use crate::tradegood::tradegood;
+use crate::{
+    distance, Designation, Elevation, World,
+    FRIENDLY, NEUTRAL, NPC_AGGRESSIVE, NPC_DEFENSIVE, NPC_ISOLATIONIST, NPC_TRADER, TREATY, WAR,
+};
+
+const RESERVE           : i32 = 100;    // gold an NPC won't spend below
+const MAX_DESIGNATE     : usize = 3;    // redesignations per turn
+const TOWN_PEOPLE       : i32 = 1000;   // people before a sector is worth a town
+
+struct Plan {
+    tax             : u8,       // preferred tax rate
+    charity         : u8,       // preferred charity
+    military        : i32,      // percent of civilians to keep under arms
+    expand_range    : i32,      // how far from home to look for empty land
+    warlike         : bool,     // starts wars
+    trader          : bool,     // seeks treaties
+}
+
+fn plan(active : u8) -> Option<Plan> {
+    match active {
+        NPC_AGGRESSIVE      => Some(Plan { tax: 15, charity: 0,  military: 20, expand_range: 8, warlike: true,  trader: false }),
+        NPC_DEFENSIVE       => Some(Plan { tax: 10, charity: 5,  military: 12, expand_range: 4, warlike: false, trader: false }),
+        NPC_ISOLATIONIST    => Some(Plan { tax: 8,  charity: 10, military: 8,  expand_range: 2, warlike: false, trader: false }),
+        NPC_TRADER          => Some(Plan { tax: 12, charity: 5,  military: 5,  expand_range: 6, warlike: false, trader: true  }),
+        _                   => None,
+    }
+}
+
+impl World {
+    // Let every computer run kingdom take its turn.
+    pub(crate) fn update_npcs(&mut self) {
+        let ids : Vec<i32> = self.ntn.iter()
+            .filter(|n| !n.race.is_special() && plan(n.active).is_some())
+            .map(|n| n.id)
+            .collect();
+
+        for id in ids {
+            self.npc_turn(id);
+        }
+    }
+
+    // Play one turn for nation id.  Returns the orders that were carried out.
+    pub fn npc_turn(&mut self, id : i32) -> Vec<Command> {
+        let mut done = Vec::new();
+        let Some(idx) = self.nation_index(id) else { return done };
+        let Some(plan) = plan(self.ntn[idx].active) else { return done };
+
+        // money
+        let n = &self.ntn[idx];
+        let mut tax = plan.tax as i32;
+        if n.popularity < 30 {
+            tax -= 3;
+        }
+        if n.tgold < RESERVE {
+            tax += 3;
+        }
+        let tax = tax.clamp(0, 20) as u8;
+        if tax != n.tax_rate {
+            self.order(id, Command::Tax { rate: tax }, &mut done);
+        }
+        let charity = plan.charity + if self.ntn[idx].poverty > 50 { 5 } else { 0 };
+        if charity != self.ntn[idx].charity {
+            self.order(id, Command::Charity { pct: charity }, &mut done);
+        }
+
+        // land
+        let hungry = self.ntn[idx].tfood < self.ntn[idx].tciv;
+        let mut designated = 0;
+        for (x, y) in self.owned_sectors(id) {
+            if designated >= MAX_DESIGNATE {
+                break;
+            }
+            let s = &self.sct[x][y];
+            if s.designation != Designation::NONE && s.designation != Designation::DEVASTATED {
+                continue;
+            }
+            let des = if let Some(g) = tradegood(s.tradegood).filter(|g| s.people >= g.people && designate_cost(&g.designation).is_some()) {
+                g.designation.clone()
+            } else if veg_food(&s.vegetation) >= 6 || (hungry && veg_food(&s.vegetation) > 0) {
+                Designation::FARM
+            } else if s.metal > 0 {
+                Designation::MINE
+            } else if s.jewels > 0 {
+                Designation::GOLDMINE
+            } else if s.people >= TOWN_PEOPLE {
+                Designation::TOWN
+            } else if veg_food(&s.vegetation) > 0 {
+                Designation::FARM
+            } else {
+                continue;
+            };
+            if self.ntn[idx].tgold - designate_cost(&des).unwrap_or(0) < RESERVE {
+                break;
+            }
+            self.order(id, Command::Designate { x, y, des }, &mut done);
+            designated += 1;
+        }
+
+        // neighbours
+        let me = self.ntn[idx].clone();
+        let mut at_war = self.ntn.iter().any(|o| me.status(o.id) >= WAR);
+        for o in self.neighbour_nations(id) {
+            let other = &self.ntn[o];
+            let (oid, bias, current) = (other.id, diplomacy_bias(&me, other), me.status(other.id));
+            if current >= WAR {
+                continue;
+            }
+            let wanted = if plan.warlike && !at_war && bias < 10 && other.power <= me.power {
+                at_war = true;
+                WAR
+            } else if plan.trader && bias > 10 {
+                TREATY
+            } else if !plan.warlike && bias > 0 {
+                FRIENDLY
+            } else {
+                NEUTRAL
+            };
+            if wanted != current {
+                self.order(id, Command::Diplomacy { nation: oid, status: wanted }, &mut done);
+            }
+        }
+
+        // draft
+        let tciv : i32 = self.owned_sectors(id).iter().map(|&(x, y)| self.sct[x][y].people).sum();
+        let tmil : i32 = self.ntn[idx].arm.iter().map(|a| a.sold).sum();
+        let mut want = tciv * plan.military / 100 - tmil;
+        let home = self.capitol(&self.ntn[idx])
+            .or_else(|| self.owned_sectors(id).into_iter().max_by_key(|&(x, y)| self.sct[x][y].people));
+        if let Some((x, y)) = home {
+            let afford = (self.ntn[idx].tgold - RESERVE) / ENLIST_COST;
+            let soldiers = want.min(afford).min(recruit_limit(&self.ntn[idx], self.sct[x][y].people));
+            if soldiers > 0 && self.order(id, Command::Draft { x, y, soldiers }, &mut done) {
+                want -= soldiers;
+            }
+
+            // make up the rest with mercenaries if there's a war on
+            let afford = (self.ntn[idx].tgold - RESERVE) / MERC_COST;
+            let soldiers = want.min(afford).min(self.m_mil);
+            if at_war && soldiers > 0 {
+                self.order(id, Command::Hire { x, y, soldiers }, &mut done);
+            }
+        }
+
+        // can't pay them and nobody to fight: let the smallest army go
+        if !at_war && self.ntn[idx].tgold < 0 && self.ntn[idx].arm.len() > 1 {
+            if let Some(a) = (0..self.ntn[idx].arm.len()).min_by_key(|&a| self.ntn[idx].arm[a].sold) {
+                self.order(id, Command::Disband { army: a }, &mut done);
+            }
+        }
+
+        // armies, last first so losses don't shuffle the ones still to go
+        let garrison = self.capitol(&self.ntn[idx]);
+        let owned = self.owned_sectors(id);
+        for a in (0..self.ntn[idx].arm.len()).rev() {
+            let Some(idx) = self.nation_index(id) else { break };
+            let Some(army) = self.ntn[idx].arm.get(a) else { continue };
+            let here = (army.xloc as usize, army.yloc as usize);
+
+            // someone stays home to mind the capitol
+            if Some(here) == garrison && self.ntn[idx].arm.iter().filter(|o| (o.xloc as usize, o.yloc as usize) == here).count() == 1 {
+                continue;
+            }
+
+            let enemy = self.nearest(here, |w, x, y| {
+                w.sector_owner(x, y).is_some_and(|o| w.ntn[idx].status(w.ntn[o].id) >= WAR)
+            });
+            let empty = self.nearest(here, |w, x, y| {
+                w.sector_owner(x, y).is_none() && w.sct[x][y].altitude != Elevation::WATER && w.lair_at(x, y).is_none()
+                    && owned.iter().any(|&(ox, oy)| distance(x as i32, y as i32, ox as i32, oy as i32) <= plan.expand_range)
+            });
+            let Some(target) = enemy.or(empty) else { continue };
+
+            let mut pos = here;
+            loop {
+                let (tx, ty) = target;
+                if enemy == Some(target) && distance(pos.0 as i32, pos.1 as i32, tx as i32, ty as i32) == 1 {
+                    self.order(id, Command::Attack { army: a, x: tx, y: ty }, &mut done);
+                    break;
+                }
+                let d = distance(pos.0 as i32, pos.1 as i32, tx as i32, ty as i32);
+                let step = self.neighbours(pos.0, pos.1).into_iter()
+                    .filter(|&(nx, ny)| distance(nx as i32, ny as i32, tx as i32, ty as i32) < d)
+                    .find(|&(nx, ny)| self.order(id, Command::Move { army: a, x: nx, y: ny }, &mut done));
+                match step {
+                    Some(p) if p != target => pos = p,
+                    _ => break,
+                }
+            }
+        }
+        done
+    }
+
+    // Execute c for nation id, noting it in done if it worked.
+    fn order(&mut self, id : i32, c : Command, done : &mut Vec<Command>) -> bool {
+        let ok = self.execute(id, &c).is_ok();
+        if ok {
+            done.push(c);
+        }
+        ok
+    }
+
+    // Ordinary nations that own land next to nation id's, as indexes.
+    fn neighbour_nations(&self, id : i32) -> Vec<usize> {
+        let mut v = Vec::new();
+        for (x, y) in self.owned_sectors(id) {
+            for (nx, ny) in self.neighbours(x, y) {
+                if let Some(o) = self.sector_owner(nx, ny) {
+                    if self.ntn[o].id != id && !self.ntn[o].race.is_special() && !v.contains(&o) {
+                        v.push(o);
+                    }
+                }
+            }
+        }
+        v.sort();
+        v
+    }
+
+    // The closest sector to from that matches, first in map order on ties.
+    fn nearest<F>(&self, from : (usize, usize), matches : F) -> Option<(usize, usize)>
+        where F : Fn(&World, usize, usize) -> bool {
+        let mut best = None;
+        let mut best_d = i32::MAX;
+        for x in 0..self.sct.len() {
+            for y in 0..self.sct[x].len() {
+                let d = distance(x as i32, y as i32, from.0 as i32, from.1 as i32);
+                if d < best_d && matches(self, x, y) {
+                    best = Some((x, y));
+                    best_d = d;
+                }
+            }
+        }
+        best
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{land, settle};
+    use crate::{Army, Nation, Race, PC};
+
+    // A computer kingdom of active holding the west three columns of 8x3
+    // land, 1000 people a sector and its capitol at 0,1.  Returns its id.
+    fn world(active : u8) -> (World, i32) {
+        let mut w = land(8, 3);
+        let n = Nation {
+            name        : String::from("Bots"),
+            race        : Race::HUMAN,
+            active,
+            tgold       : 5000,
+            popularity  : 50,
+            capx        : 0,
+            capy        : 1,
+            arm         : vec![Army { xloc: 0, yloc: 1, sold: 50, smove: 8, ..Default::default() }],
+            ..Default::default()
+        };
+        let owned : Vec<(usize, usize)> = (0..3).flat_map(|x| (0..3).map(move |y| (x, y))).collect();
+        let id = settle(&mut w, n, &owned, 1000);
+        w.sct[0][1].designation = Designation::CAPITOL;
+        (w, id)
+    }
+
+    #[test]
+    fn a_quiet_kingdom_farms_drafts_and_guards_home() {
+        let (mut w, id) = world(NPC_DEFENSIVE);
+        let done = w.npc_turn(id);
+        assert_eq!(done[0], Command::Tax { rate: 10 });
+        assert_eq!(done[1], Command::Charity { pct: 5 });
+        let farms = done.iter().filter(|c| matches!(c, Command::Designate { des: Designation::FARM, .. })).count();
+        assert_eq!(farms, MAX_DESIGNATE);
+        assert!(done.contains(&Command::Draft { x: 0, y: 1, soldiers: 100 }));
+        assert!(!done.iter().any(|c| matches!(c, Command::Attack { .. } | Command::Diplomacy { .. })));
+
+        // and every one of them was carried out, with one army left at
+        // home and the other off toward empty land
+        let n = &w.ntn[0];
+        assert_eq!((n.tax_rate, n.charity, n.arm.len()), (10, 5, 2));
+        assert!(n.arm.iter().any(|a| (a.xloc, a.yloc) == (0, 1)));
+        assert!(done.iter().any(|c| matches!(c, Command::Move { .. })));
+        assert!(n.arm.iter().any(|a| a.xloc >= 3));
+        assert_eq!(w.sct.iter().flatten().filter(|s| s.designation == Designation::FARM).count(), MAX_DESIGNATE);
+        assert!(n.tgold >= RESERVE);
+    }
+
+    #[test]
+    fn an_aggressive_kingdom_goes_to_war_with_a_weak_neighbour() {
+        let (mut w, id) = world(NPC_AGGRESSIVE);
+        w.ntn[0].arm.push(Army { xloc: 2, yloc: 1, sold: 300, smove: 8, ..Default::default() });
+        let orc = Nation { race: Race::ORC, active: PC, ..Default::default() };
+        let o = settle(&mut w, orc, &[(3, 0), (3, 1), (3, 2)], 100);
+
+        let done = w.npc_turn(id);
+        assert!(done.contains(&Command::Diplomacy { nation: o, status: WAR }));
+        assert!(done.iter().any(|c| matches!(c, Command::Attack { x: 3, .. })), "{:?}", done);
+        assert_eq!(w.ntn[1].status(id), WAR);
+    }
+
+    #[test]
+    fn a_broke_kingdom_at_peace_raises_taxes_and_lets_an_army_go() {
+        let (mut w, id) = world(NPC_DEFENSIVE);
+        w.ntn[0].tgold = -100;
+        w.ntn[0].arm.push(Army { xloc: 0, yloc: 1, sold: 20, ..Default::default() });
+        let done = w.npc_turn(id);
+        assert_eq!(done[0], Command::Tax { rate: 13 });
+        assert!(done.contains(&Command::Disband { army: 1 }));
+        assert!(!done.iter().any(|c| matches!(c, Command::Draft { .. } | Command::Designate { .. })));
+        assert_eq!(w.ntn[0].arm.len(), 1);
+    }
+
+    #[test]
+    fn players_and_monsters_play_themselves() {
+        let (mut w, id) = world(PC);
+        assert!(w.npc_turn(id).is_empty());
+        w.ntn[0].active = NPC_DEFENSIVE;
+        w.ntn[0].race = Race::SAVAGE;
+        let before = w.ntn[0].tax_rate;
+        w.update_npcs();
+        assert_eq!(w.ntn[0].tax_rate, before);
+    }
+}
@@ -0,0 +1,653 @@
+//! Saving and loading games.
+//!
+//! A save is plain text, one record to a line: a tag, then the fields in a
+//! fixed order separated by spaces.  Strings are written with a leading '
+//! and any space, tab, newline or % escaped as %XX, so every field is one
+//! word and an empty string still takes its place.  Numbers that may be
+//! missing are written as - when they are.  Passwords go out as the hash
+//! already kept in Nation::passwd.  Armies, fleets, deeds, logins and mail
+//! belong to the nation line before them, bids to the offer before them.
+//!
+//! The first line names the format and its version.  Anything that doesn't
+//! parse is an error giving the line, never a half loaded world.
+
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::str::{FromStr, SplitWhitespace};
+
+use crate::auth::is_hashed;
+use crate::god::Audit;
+use crate::mail::{Delivery, Mail};
+use crate::news::{NewsItem, SECTIONS};
+use crate::rng::Rng;
+use crate::score::Victory;
+use crate::trade::{Bid, Goods, Offer};
+use crate::units::Lair;
+use crate::{Army, Designation, Elevation, Nation, Navy, Race, Sector, Vegetation, World};
+
+pub const SAVE_MAGIC    : &str = "rconq-save";
+pub const SAVE_VERSION  : u32 = 1;
+
+fn esc(s : &str) -> String {
+    let mut e = String::from("'");
+    for c in s.chars() {
+        match c {
+            '%' | ' ' | '\t' | '\n' | '\r' => e += &format!("%{:02X}", c as u32),
+            _ => e.push(c),
+        }
+    }
+    e
+}
+
+fn unesc(s : &str) -> Result<String, String> {
+    let s = s.strip_prefix('\'').ok_or(format!("expected a string, got {}", s))?;
+    let mut out = String::new();
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c == '%' {
+            let hex : String = chars.by_ref().take(2).collect();
+            let c = u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32)
+                .ok_or(format!("bad escape %{}", hex))?;
+            out.push(c);
+        } else {
+            out.push(c);
+        }
+    }
+    Ok(out)
+}
+
+fn opt<T : fmt::Display>(v : &Option<T>) -> String {
+    match v {
+        Some(v) => v.to_string(),
+        None    => "-".to_string(),
+    }
+}
+
+fn goods(g : &Goods) -> String {
+    match g {
+        Goods::Food(n)      => format!("food {}", n),
+        Goods::Gold(n)      => format!("gold {}", n),
+        Goods::Metal(n)     => format!("metal {}", n),
+        Goods::Jewels(n)    => format!("jewels {}", n),
+        Goods::Land(x, y)   => format!("land {} {}", x, y),
+        Goods::Ships(x, y)  => format!("ships {} {}", x, y),
+    }
+}
+
+fn mail(tag : &str, m : &Mail) -> String {
+    format!("{} {} {} {} {} {} {} {}", tag, m.id, m.from, m.to, m.turn, m.read as u8, esc(&m.subject), esc(&m.body))
+}
+
+// The fields of one record.
+struct Fields<'a> {
+    words           : SplitWhitespace<'a>,
+}
+
+impl<'a> Fields<'a> {
+    fn word(&mut self) -> Result<&'a str, String> {
+        self.words.next().ok_or("too few fields".to_string())
+    }
+
+    fn num<T : FromStr>(&mut self) -> Result<T, String> {
+        let w = self.word()?;
+        w.parse().map_err(|_| format!("bad number {}", w))
+    }
+
+    fn opt<T : FromStr>(&mut self) -> Result<Option<T>, String> {
+        match self.word()? {
+            "-" => Ok(None),
+            w   => w.parse().map(Some).map_err(|_| format!("bad number {}", w)),
+        }
+    }
+
+    fn flag(&mut self) -> Result<bool, String> {
+        match self.word()? {
+            "0" => Ok(false),
+            "1" => Ok(true),
+            w   => Err(format!("expected 0 or 1, got {}", w)),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        unesc(self.word()?)
+    }
+
+    fn ch(&mut self) -> Result<char, String> {
+        let w = self.word()?;
+        let mut c = w.chars();
+        match (c.next(), c.next()) {
+            (Some(c), None) => Ok(c),
+            _               => Err(format!("expected a character, got {}", w)),
+        }
+    }
+
+    fn elevation(&mut self) -> Result<Elevation, String> {
+        let c = self.ch()?;
+        Some(Elevation::from(c)).filter(|e| e.to_char() == c).ok_or(format!("no elevation {}", c))
+    }
+
+    fn designation(&mut self) -> Result<Designation, String> {
+        let c = self.ch()?;
+        Some(Designation::from(c)).filter(|d| d.to_char() == c).ok_or(format!("no designation {}", c))
+    }
+
+    fn vegetation(&mut self) -> Result<Vegetation, String> {
+        let c = self.ch()?;
+        Some(Vegetation::from(c)).filter(|v| v.to_char() == c).ok_or(format!("no vegetation {}", c))
+    }
+
+    fn race(&mut self) -> Result<Race, String> {
+        let c = self.ch()?;
+        Some(Race::from(c)).filter(|r| r.to_char() == c).ok_or(format!("no race {}", c))
+    }
+
+    fn goods(&mut self) -> Result<Goods, String> {
+        Ok(match self.word()? {
+            "food"      => Goods::Food(self.num()?),
+            "gold"      => Goods::Gold(self.num()?),
+            "metal"     => Goods::Metal(self.num()?),
+            "jewels"    => Goods::Jewels(self.num()?),
+            "land"      => Goods::Land(self.num()?, self.num()?),
+            "ships"     => Goods::Ships(self.num()?, self.num()?),
+            w           => return Err(format!("no goods {}", w)),
+        })
+    }
+
+    fn mail(&mut self) -> Result<Mail, String> {
+        Ok(Mail {
+            id          : self.num()?,
+            from        : self.num()?,
+            to          : self.num()?,
+            turn        : self.num()?,
+            read        : self.flag()?,
+            subject     : self.string()?,
+            body        : self.string()?,
+        })
+    }
+
+    fn end(&mut self) -> Result<(), String> {
+        match self.words.next() {
+            None    => Ok(()),
+            Some(w) => Err(format!("unexpected {}", w)),
+        }
+    }
+}
+
+impl World {
+    /// Write the whole game to out.
+    pub fn save<W : Write>(&self, out : &mut W) -> io::Result<()> {
+        writeln!(out, "{} {}", SAVE_MAGIC, SAVE_VERSION)?;
+        writeln!(out, "world {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {}",
+            self.mapx, self.mapy, self.othrntns, self.turn, self.m_mil, self.m_aplus, self.m_dplus,
+            self.w_jewels, self.w_gold, self.w_food, self.w_metal, self.w_civ, self.w_mil, self.w_sctrs,
+            self.score, self.karma, self.seed, self.rng.state(), self.next_trade, self.next_mail,
+            opt(&self.winner), if self.mail_delivery == Delivery::IMMEDIATE { "now" } else { "turn" })?;
+        let v = &self.victory;
+        writeln!(out, "victory {} {} {} {}", opt(&v.score), opt(&v.turns), v.last_standing as u8, opt(&v.capitols))?;
+
+        for (x, col) in self.sct.iter().enumerate() {
+            for (y, s) in col.iter().enumerate() {
+                writeln!(out, "sector {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {}",
+                    x, y, s.designation.to_char(), s.altitude.to_char(), s.vegetation.to_char(), s.owner,
+                    s.people, s.i_people, s.jewels, s.fortress, s.metal, s.tradegood, s.region,
+                    s.foodstore, s.jewelstore, s.metalstore)?;
+            }
+        }
+
+        for n in &self.ntn {
+            let dstatus : Vec<String> = n.dstatus.iter().map(|d| d.to_string()).collect();
+            writeln!(out, "nation {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} \
+                {} {} {} {} {} {} {} {} {} {} {} {} {} {} {}",
+                n.id, esc(&n.name), esc(&n.passwd), esc(&n.leader), n.race.to_char(), n.location,
+                n.mark as u32, n.capx, n.capy, n.active, n.maxmove, n.repro, n.score, n.tgold, n.jewels,
+                n.tmil, n.tciv, n.metals, n.tfood, n.powers, n.class, n.aplus, n.dplus, n.spellpts,
+                n.tsctrs, n.tships, n.inflation, n.charity, n.tax_rate, n.prestige, n.popularity,
+                n.power, n.communications, n.wealth, n.eatrate, n.spoilrate, n.knowledge,
+                n.farm_ability, n.mine_ability, n.poverty, n.terror, n.reputation, n.karma,
+                if dstatus.is_empty() { "-".to_string() } else { dstatus.join(",") })?;
+            let d = &n.deeds;
+            writeln!(out, "deeds {} {} {} {} {} {} {}",
+                d.massacres, d.victories, d.defeats, d.captured, d.lost, d.broken_treaties, d.trades)?;
+            writeln!(out, "login {} {}", n.failed_logins, n.locked_until)?;
+            for a in &n.arm {
+                writeln!(out, "army {} {} {} {} {} {} {} {}",
+                    a.unittyp, a.xloc, a.yloc, a.smove, a.sold, a.stat, a.aplus, a.dplus)?;
+            }
+            for f in &n.nvy {
+                writeln!(out, "navy {} {} {} {} {} {} {} {} {} {}",
+                    f.warships, f.merchant, f.galleys, f.xloc, f.yloc, f.smove, f.crew, f.people,
+                    f.commodity, f.armynum)?;
+            }
+            for m in &n.mail {
+                writeln!(out, "{}", mail("mail", m))?;
+            }
+        }
+        for m in &self.outbox {
+            writeln!(out, "{}", mail("outbox", m))?;
+        }
+
+        for l in &self.lairs {
+            writeln!(out, "lair {} {} {} {} {} {}", l.x, l.y, l.unittyp, l.count, l.jewels, l.gold)?;
+        }
+        for o in &self.trades {
+            writeln!(out, "offer {} {} {} {} {}", o.id, o.seller, o.posted, opt(&o.accepted), goods(&o.goods))?;
+            for b in &o.bids {
+                writeln!(out, "bid {} {}", b.bidder, goods(&b.goods))?;
+            }
+        }
+        for a in &self.audit {
+            let (ok, outcome) = match &a.outcome {
+                Ok(done)    => (1, done),
+                Err(why)    => (0, why),
+            };
+            writeln!(out, "audit {} {} {} {} {}", a.turn, a.god, ok, esc(&a.action), esc(outcome))?;
+        }
+        for n in &self.news {
+            let nations : Vec<String> = n.nations.iter().map(|id| id.to_string()).collect();
+            let (x, y) = (n.at.map(|a| a.0), n.at.map(|a| a.1));
+            writeln!(out, "news {} {} {} {} {} {}", n.turn, n.category,
+                if nations.is_empty() { "-".to_string() } else { nations.join(",") },
+                opt(&x), opt(&y), esc(&n.text))?;
+        }
+        Ok(())
+    }
+
+    /// Read back a game written by save().
+    pub fn load<R : BufRead>(input : R) -> Result<World, String> {
+        let mut w = World::default();
+        let mut sized = false;
+        let mut lines = input.lines();
+
+        let header = lines.next().ok_or("empty save")?.map_err(|e| e.to_string())?;
+        if header.trim() != format!("{} {}", SAVE_MAGIC, SAVE_VERSION) {
+            return Err(format!("not a version {} save: {}", SAVE_VERSION, header.trim()));
+        }
+        for (i, line) in lines.enumerate() {
+            let line = line.map_err(|e| e.to_string())?;
+            let mut f = Fields { words: line.split_whitespace() };
+            let Some(tag) = f.words.next() else { continue };
+            w.load_record(tag, &mut f, &mut sized)
+                .and_then(|_| f.end())
+                .map_err(|e| format!("line {}: {}: {}", i + 2, tag, e))?;
+        }
+        if !sized {
+            return Err("no world record".to_string());
+        }
+        if let Some(last) = w.ntn.iter().map(|n| n.id).max() {
+            Nation::reserve_ids(last);
+        }
+        Ok(w)
+    }
+
+    fn load_record(&mut self, tag : &str, f : &mut Fields, sized : &mut bool) -> Result<(), String> {
+        if tag != "world" && !*sized {
+            return Err("comes before the world record".to_string());
+        }
+        match tag {
+            "world" => {
+                self.mapx = f.num()?;
+                self.mapy = f.num()?;
+                if self.mapx < 0 || self.mapy < 0 {
+                    return Err("bad map size".to_string());
+                }
+                self.othrntns = f.num()?;
+                self.turn = f.num()?;
+                self.m_mil = f.num()?;
+                self.m_aplus = f.num()?;
+                self.m_dplus = f.num()?;
+                self.w_jewels = f.num()?;
+                self.w_gold = f.num()?;
+                self.w_food = f.num()?;
+                self.w_metal = f.num()?;
+                self.w_civ = f.num()?;
+                self.w_mil = f.num()?;
+                self.w_sctrs = f.num()?;
+                self.score = f.num()?;
+                self.karma = f.num()?;
+                self.seed = f.num()?;
+                self.rng = Rng::from_state(f.num()?);
+                self.next_trade = f.num()?;
+                self.next_mail = f.num()?;
+                self.winner = f.opt()?;
+                self.mail_delivery = match f.word()? {
+                    "now"   => Delivery::IMMEDIATE,
+                    "turn"  => Delivery::TURN,
+                    w       => return Err(format!("no delivery {}", w)),
+                };
+                self.sct = vec![vec![Sector::default(); self.mapy as usize]; self.mapx as usize];
+                *sized = true;
+            },
+            "victory" => {
+                self.victory = Victory {
+                    score       : f.opt()?,
+                    turns       : f.opt()?,
+                    last_standing : f.flag()?,
+                    capitols    : f.opt()?,
+                };
+            },
+            "sector" => {
+                let (x, y) : (usize, usize) = (f.num()?, f.num()?);
+                if !self.on_map(x as i32, y as i32) {
+                    return Err(format!("{},{} is off the map", x, y));
+                }
+                self.sct[x][y] = Sector {
+                    designation     : f.designation()?,
+                    altitude        : f.elevation()?,
+                    vegetation      : f.vegetation()?,
+                    owner           : f.num()?,
+                    people          : f.num()?,
+                    i_people        : f.num()?,
+                    jewels          : f.num()?,
+                    fortress        : f.num()?,
+                    metal           : f.num()?,
+                    tradegood       : f.num()?,
+                    region          : f.num()?,
+                    foodstore       : f.num()?,
+                    jewelstore      : f.num()?,
+                    metalstore      : f.num()?,
+                };
+            },
+            "nation" => {
+                let mut n = Nation {
+                    id              : f.num()?,
+                    name            : f.string()?,
+                    passwd          : f.string()?,
+                    leader          : f.string()?,
+                    race            : f.race()?,
+                    location        : f.num()?,
+                    mark            : char::from_u32(f.num()?).ok_or("bad mark")?,
+                    capx            : f.num()?,
+                    capy            : f.num()?,
+                    active          : f.num()?,
+                    maxmove         : f.num()?,
+                    repro           : f.num()?,
+                    score           : f.num()?,
+                    tgold           : f.num()?,
+                    jewels          : f.num()?,
+                    tmil            : f.num()?,
+                    tciv            : f.num()?,
+                    metals          : f.num()?,
+                    tfood           : f.num()?,
+                    powers          : f.num()?,
+                    class           : f.num()?,
+                    aplus           : f.num()?,
+                    dplus           : f.num()?,
+                    spellpts        : f.num()?,
+                    tsctrs          : f.num()?,
+                    tships          : f.num()?,
+                    inflation       : f.num()?,
+                    charity         : f.num()?,
+                    tax_rate        : f.num()?,
+                    prestige        : f.num()?,
+                    popularity      : f.num()?,
+                    power           : f.num()?,
+                    communications  : f.num()?,
+                    wealth          : f.num()?,
+                    eatrate         : f.num()?,
+                    spoilrate       : f.num()?,
+                    knowledge       : f.num()?,
+                    farm_ability    : f.num()?,
+                    mine_ability    : f.num()?,
+                    poverty         : f.num()?,
+                    terror          : f.num()?,
+                    reputation      : f.num()?,
+                    karma           : f.num()?,
+                    ..Nation::with_id(0)
+                };
+                let dstatus = f.word()?;
+                if dstatus != "-" {
+                    for d in dstatus.split(',') {
+                        n.dstatus.push(d.parse().map_err(|_| format!("bad status {}", d))?);
+                    }
+                }
+                if self.nation_index(n.id).is_some() {
+                    return Err(format!("nation {} twice", n.id));
+                }
+                self.ntn.push(n);
+            },
+            "deeds" => {
+                let d = &mut self.last_nation()?.deeds;
+                d.massacres = f.num()?;
+                d.victories = f.num()?;
+                d.defeats = f.num()?;
+                d.captured = f.num()?;
+                d.lost = f.num()?;
+                d.broken_treaties = f.num()?;
+                d.trades = f.num()?;
+            },
+            "login" => {
+                let n = self.last_nation()?;
+                n.failed_logins = f.num()?;
+                n.locked_until = f.num()?;
+            },
+            "army" => {
+                let a = Army {
+                    unittyp         : f.num()?,
+                    xloc            : f.num()?,
+                    yloc            : f.num()?,
+                    smove           : f.num()?,
+                    sold            : f.num()?,
+                    stat            : f.num()?,
+                    aplus           : f.num()?,
+                    dplus           : f.num()?,
+                };
+                self.last_nation()?.arm.push(a);
+            },
+            "navy" => {
+                let n = Navy {
+                    warships        : f.num()?,
+                    merchant        : f.num()?,
+                    galleys         : f.num()?,
+                    xloc            : f.num()?,
+                    yloc            : f.num()?,
+                    smove           : f.num()?,
+                    crew            : f.num()?,
+                    people          : f.num()?,
+                    commodity       : f.num()?,
+                    armynum         : f.num()?,
+                };
+                self.last_nation()?.nvy.push(n);
+            },
+            "mail" => {
+                let m = f.mail()?;
+                self.last_nation()?.mail.push(m);
+            },
+            "outbox" => {
+                let m = f.mail()?;
+                self.outbox.push(m);
+            },
+            "lair" => {
+                self.lairs.push(Lair {
+                    x               : f.num()?,
+                    y               : f.num()?,
+                    unittyp         : f.num()?,
+                    count           : f.num()?,
+                    jewels          : f.num()?,
+                    gold            : f.num()?,
+                });
+            },
+            "offer" => {
+                self.trades.push(Offer {
+                    id              : f.num()?,
+                    seller          : f.num()?,
+                    posted          : f.num()?,
+                    accepted        : f.opt()?,
+                    goods           : f.goods()?,
+                    bids            : Vec::new(),
+                });
+            },
+            "bid" => {
+                let b = Bid { bidder: f.num()?, goods: f.goods()? };
+                self.trades.last_mut().ok_or("bid with no offer")?.bids.push(b);
+            },
+            "news" => {
+                let turn = f.num()?;
+                let cat = f.word()?;
+                let category = SECTIONS.iter().find(|c| c.to_string() == cat).ok_or(format!("no category {}", cat))?;
+                let mut nations = Vec::new();
+                let ids = f.word()?;
+                if ids != "-" {
+                    for id in ids.split(',') {
+                        nations.push(id.parse().map_err(|_| format!("bad nation {}", id))?);
+                    }
+                }
+                let (x, y) : (Option<usize>, Option<usize>) = (f.opt()?, f.opt()?);
+                self.news.push(NewsItem { turn, category: category.clone(), nations, at: x.zip(y), text: f.string()? });
+            },
+            "audit" => {
+                let (turn, god, ok) = (f.num()?, f.num()?, f.flag()?);
+                let (action, outcome) = (f.string()?, f.string()?);
+                let outcome = if ok { Ok(outcome) } else { Err(outcome) };
+                self.audit.push(Audit { turn, god, action, outcome });
+            },
+            _ => return Err("unknown record".to_string()),
+        }
+        Ok(())
+    }
+
+    /// Everything in a loaded world that doesn't hang together: land held by
+    /// nations that don't exist, units off the map, passwords kept in the
+    /// clear, and totals that have drifted.
+    pub fn problems(&self) -> Vec<String> {
+        let mut p = Vec::new();
+        for (x, col) in self.sct.iter().enumerate() {
+            for (y, s) in col.iter().enumerate() {
+                if s.owner != 0 && self.nation_index(s.owner as i32).is_none() {
+                    p.push(format!("sector {},{} belongs to missing nation {}", x, y, s.owner));
+                }
+            }
+        }
+        for n in &self.ntn {
+            if !n.passwd.is_empty() && !is_hashed(&n.passwd) {
+                p.push(format!("{} has a plain text password", n.name));
+            }
+            for (i, a) in n.arm.iter().enumerate() {
+                if !self.on_map(a.xloc as i32, a.yloc as i32) {
+                    p.push(format!("{} army {} is off the map at {},{}", n.name, i, a.xloc, a.yloc));
+                }
+            }
+            for (i, f) in n.nvy.iter().enumerate() {
+                if !self.on_map(f.xloc as i32, f.yloc as i32) {
+                    p.push(format!("{} navy {} is off the map at {},{}", n.name, i, f.xloc, f.yloc));
+                }
+            }
+        }
+        if let Some(w) = self.winner {
+            if self.nation_index(w).is_none() {
+                p.push(format!("the winner, nation {}, doesn't exist", w));
+            }
+        }
+        p.extend(self.check_stats().iter().map(|d| d.to_string()));
+        p
+    }
+
+    fn last_nation(&mut self) -> Result<&mut Nation, String> {
+        self.ntn.last_mut().ok_or("comes before any nation".to_string())
+    }
+
+    /// Save to path.  The game goes to a scratch file first and replaces the
+    /// old save only once it is all written, so a crash never leaves half a
+    /// world behind.
+    pub fn save_file(&self, path : &str) -> Result<(), String> {
+        let tmp = format!("{}.tmp", path);
+        let write = || -> io::Result<()> {
+            let mut out = BufWriter::new(File::create(&tmp)?);
+            self.save(&mut out)?;
+            out.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+            fs::rename(&tmp, path)
+        };
+        write().map_err(|e| {
+            let _ = fs::remove_file(&tmp);
+            format!("{}: {}", path, e)
+        })
+    }
+
+    pub fn load_file(path : &str) -> Result<World, String> {
+        let f = File::open(path).map_err(|e| format!("{}: {}", path, e))?;
+        World::load(BufReader::new(f)).map_err(|e| format!("{}: {}", path, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::god::GodCommand;
+    use crate::worldgen::GenOptions;
+    use crate::PC;
+
+    fn saved(w : &World) -> String {
+        let mut out = Vec::new();
+        w.save(&mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn a_saved_game_loads_back_the_same() {
+        let mut w = World::generate(12, 10, 5, &GenOptions::default());
+        w.ntn.push(Nation {
+            name        : "Odd name %1\nwith lines".to_string(),
+            passwd      : crate::auth::hash_password("plain text").unwrap(),
+            race        : Race::DWARF,
+            active      : PC,
+            arm         : vec![Army { sold: 10, xloc: 3, ..Default::default() }],
+            dstatus     : vec![0, 3, 6],
+            ..Default::default()
+        });
+        w.ntn.push(Nation { name: String::new(), race: Race::ELF, active: PC, ..Default::default() });
+        w.mail_delivery = Delivery::IMMEDIATE;
+        let (a, b) = (w.ntn[0].id, w.ntn[1].id);
+        w.send_mail(a, b, "Hello there", "").unwrap();
+        w.victory.turns = Some(40);
+        w.update();
+        assert!(w.god(a, &GodCommand::Update).is_err());
+
+        let text = saved(&w);
+        let back = World::load(text.as_bytes()).unwrap();
+        assert_eq!(saved(&back), text);
+        assert_eq!(back.ntn[0].name, w.ntn[0].name);
+        assert_eq!(back.ntn[1].mail, w.ntn[1].mail);
+        assert_eq!(back.audit, w.audit);
+        assert!(!text.contains("plain"));
+        assert_eq!(back.ntn[0].passwd, w.ntn[0].passwd);
+        assert_eq!(back.rng.state(), w.rng.state());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn snapshots_round_trip_through_serde() {
+        let mut w = World::generate(8, 6, 2, &GenOptions::default());
+        let id = w.create_nation("Khazad", Race::DWARF, PC, None).unwrap();
+        w.ntn[0].passwd = crate::auth::hash_password("plain text").unwrap();
+        w.update();
+
+        let json = serde_json::to_string(&w).unwrap();
+        assert!(json.contains("\"race\":\"DWARF\"") && json.contains("\"altitude\":\"WATER\""));
+        assert!(!json.contains("plain text"));
+        assert_eq!(serde_json::to_string(&w).unwrap(), json);
+        let back : World = serde_json::from_str(&json).unwrap();
+        assert!(crate::auth::check_password(&back.ntn[0].passwd, "plain text"));
+        assert_eq!(saved(&back), saved(&w));
+        assert!(Nation::default().id > id);
+    }
+
+    #[test]
+    fn loading_again_and_again_leaves_room_for_new_nations() {
+        let (w, _, _, _) = crate::testing::players();
+        let text = saved(&w);
+        let mut back = World::load(text.as_bytes()).unwrap();
+        for _ in 0..300 {
+            back = World::load(text.as_bytes()).unwrap();
+        }
+        assert_eq!(back.create_nation("Dwarfland", Race::DWARF, PC, Some((5, 2))), Ok(4));
+    }
+
+    #[test]
+    fn bad_saves_say_where() {
+        assert!(World::load("something else\n".as_bytes()).is_err());
+        let e = World::load("rconq-save 1\nsector 0 0\n".as_bytes()).unwrap_err();
+        assert!(e.starts_with("line 2: sector"), "{}", e);
+        let e = World::load("rconq-save 1\nworld 1 1 0 0 0 0 0 0 0 0 0 0 0 0 0 0 1 1 0 0 - turn\nsector 3 0 - ~ ~ 0 0 0 0 0 0 0 0 0 0 0\n".as_bytes()).unwrap_err();
+        assert!(e.contains("off the map"), "{}", e);
+    }
+}
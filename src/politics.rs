@@ -0,0 +1,241 @@
+// Popularity, terror, reputation, prestige, power and communications.
+//
+// Each turn these drift according to what the nation did (see Deeds) and
+// how it is run (taxes, charity, poverty).  The other half of this file is
+// the set of formulas that read them back: revolt chance, how many people
+// will answer a draft, and how an AI nation feels about another.
+//
+// All of the attributes live in 0..=100.
+
+use crate::{Nation, Race, World};
+
+const ATTR_MAX          : i32 = 100;
+const NORMAL_TAX        : i32 = 10;     // tax rate people grumble about but accept
+const BASE_RECRUIT      : i32 = 10;     // percent of people a neutral nation can draft
+
+// Things a nation did during the turn that the populace and the world will
+// remember.  Cleared once update_politics() has accounted for them.
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Deeds {
+    pub massacres       : i32,      // sectors of civilians put to the sword
+    pub victories       : i32,      // battles won
+    pub defeats         : i32,      // battles lost
+    pub captured        : i32,      // sectors taken from others
+    pub lost            : i32,      // sectors taken by others
+    pub broken_treaties : i32,      // treaties broken
+    pub trades          : i32,      // trades settled
+}
+
+fn clamp_attr(v : i32) -> u8 {
+    v.clamp(0, ATTR_MAX) as u8
+}
+
+// Change in popularity for the turn.  Taxes above the norm and poverty cost
+// support, charity and winning buy it back, fear breeds resentment.
+pub fn popularity_delta(n : &Nation) -> i32 {
+    let d = &n.deeds;
+    (NORMAL_TAX - n.tax_rate as i32) / 2
+        + n.charity as i32 / 10
+        - n.poverty as i32 / 20
+        - n.terror as i32 / 25
+        + 2 * d.victories
+        - 2 * d.defeats
+        - 3 * d.lost
+}
+
+// Change in terror for the turn.  Massacres raise it sharply, otherwise
+// memories fade a point a turn.
+pub fn terror_delta(n : &Nation) -> i32 {
+    let d = &n.deeds;
+    if d.massacres > 0 {
+        10 * d.massacres
+    } else {
+        -1
+    }
+}
+
+// Change in reputation for the turn.  Slaughter and treachery are remembered,
+// generosity less so.  With nothing happening it drifts back towards 50.
+pub fn reputation_delta(n : &Nation) -> i32 {
+    let d = &n.deeds;
+    let drift = (50 - n.reputation as i32).signum();
+    drift
+        - 5 * d.massacres
+        - 10 * d.broken_treaties
+        + n.charity as i32 / 20
+}
+
+// Change in prestige for the turn.  Won in battle, lost in battle, and slowly
+// forgotten otherwise.
+pub fn prestige_delta(n : &Nation) -> i32 {
+    let d = &n.deeds;
+    let change = 3 * d.victories + 2 * d.captured - 2 * d.defeats - 2 * d.lost;
+    if change == 0 && n.prestige > 0 {
+        -1
+    } else {
+        change
+    }
+}
+
+// Raw national power: how much the rest of the world ought to fear you.
+// Mostly army, then land, then money.
+pub fn power_level(n : &Nation) -> u8 {
+    clamp_attr(n.tmil / 500 + n.tsctrs as i32 / 2 + n.tgold / 10000 + n.prestige as i32 / 10)
+}
+
+// How well orders get out from the capitol.  Knowledge helps, sprawl hurts.
+pub fn communications_level(n : &Nation) -> u8 {
+    clamp_attr(50 + n.knowledge as i32 / 2 - n.tsctrs as i32 / 10)
+}
+
+// Per mille chance that a sector of this nation revolts in a turn, at
+// twice its loyal range from the capitol; revolt.rs scales it by distance.
+// Unpopular, poor nations revolt; terror keeps a lid on it for a while.
+pub fn revolt_chance(n : &Nation) -> i32 {
+    ((ATTR_MAX - n.popularity as i32) / 4
+        + n.poverty as i32 / 4
+        + (ATTR_MAX - n.communications as i32) / 10
+        - n.terror as i32 / 5)
+        .clamp(0, ATTR_MAX)
+}
+
+// How many of people will answer a draft.  Popular and prestigious
+// governments get volunteers, feared ones get conscripts.
+pub fn recruit_limit(n : &Nation, people : i32) -> i32 {
+    let pct = BASE_RECRUIT
+        + (n.popularity as i32 - 50) / 10
+        + n.prestige as i32 / 20
+        + n.terror as i32 / 25;
+    people.max(0) * pct.clamp(0, ATTR_MAX) / 100
+}
+
+// How kindly nation n should look on other when deciding diplomacy.
+// Positive means friendlier.  Good reputations are trusted, powerful
+// neighbours are appeased, feared ones are not.
+pub fn diplomacy_bias(n : &Nation, other : &Nation) -> i32 {
+    let mut bias = other.reputation as i32 - 50;
+    bias += (other.power as i32 - n.power as i32) / 4;
+    bias -= other.terror as i32 / 4;
+    if n.race == other.race {
+        bias += 10;
+    }
+    bias
+}
+
+impl World {
+    // Apply this turn's deeds and policies to every nation's attributes.
+    pub(crate) fn update_politics(&mut self) {
+        let goods : Vec<_> = self.ntn.iter().map(|n| self.goods_bonus(n.id)).collect();
+        for (n, goods) in self.ntn.iter_mut().zip(goods) {
+            if n.race == Race::GOD {
+                continue;
+            }
+            n.popularity = clamp_attr(n.popularity as i32 + popularity_delta(n) + goods.popularity);
+            n.terror = clamp_attr(n.terror as i32 + terror_delta(n));
+            n.reputation = clamp_attr(n.reputation as i32 + reputation_delta(n));
+            n.prestige = clamp_attr(n.prestige as i32 + prestige_delta(n));
+            n.power = power_level(n);
+            n.communications = clamp_attr(communications_level(n) as i32 + goods.communication);
+            n.deeds = Deeds::default();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nation() -> Nation {
+        Nation {
+            race        : Race::HUMAN,
+            tax_rate    : NORMAL_TAX as u8,
+            popularity  : 50,
+            reputation  : 50,
+            communications : 50,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn high_taxes_cost_popularity() {
+        let mut n = nation();
+        assert_eq!(popularity_delta(&n), 0);
+        n.tax_rate = 30;
+        assert!(popularity_delta(&n) < 0);
+        n.tax_rate = 2;
+        assert!(popularity_delta(&n) > 0);
+    }
+
+    #[test]
+    fn massacres_raise_terror_and_ruin_reputation() {
+        let mut n = nation();
+        assert_eq!(terror_delta(&n), -1);
+        assert_eq!(reputation_delta(&n), 0);
+        n.deeds.massacres = 2;
+        assert_eq!(terror_delta(&n), 20);
+        assert_eq!(reputation_delta(&n), -10);
+    }
+
+    #[test]
+    fn victories_raise_prestige() {
+        let mut n = nation();
+        assert_eq!(prestige_delta(&n), 0);
+        n.deeds.victories = 1;
+        assert_eq!(prestige_delta(&n), 3);
+        n.deeds = Deeds::default();
+        n.prestige = 10;
+        assert_eq!(prestige_delta(&n), -1);
+    }
+
+    #[test]
+    fn unpopular_poor_nations_revolt() {
+        let mut n = nation();
+        let base = revolt_chance(&n);
+        n.popularity = 0;
+        n.poverty = 80;
+        assert!(revolt_chance(&n) > base);
+        n.terror = 100;
+        assert!(revolt_chance(&n) < base + 45);
+        n.popularity = 100;
+        n.poverty = 0;
+        assert_eq!(revolt_chance(&n), 0);
+    }
+
+    #[test]
+    fn popularity_and_prestige_help_recruiting() {
+        let mut n = nation();
+        assert_eq!(recruit_limit(&n, 1000), 100);
+        n.popularity = 100;
+        n.prestige = 100;
+        assert_eq!(recruit_limit(&n, 1000), 200);
+        assert_eq!(recruit_limit(&n, -5), 0);
+    }
+
+    #[test]
+    fn reputation_drives_diplomacy() {
+        let n = nation();
+        let mut other = nation();
+        other.race = Race::ORC;
+        assert_eq!(diplomacy_bias(&n, &other), 0);
+        other.reputation = 90;
+        assert!(diplomacy_bias(&n, &other) > 0);
+        other.reputation = 10;
+        other.terror = 80;
+        assert!(diplomacy_bias(&n, &other) < 0);
+    }
+
+    #[test]
+    fn update_clamps_and_clears_deeds() {
+        let mut w = World::default();
+        let mut n = nation();
+        n.deeds.massacres = 20;
+        w.ntn.push(n);
+        w.update_politics();
+        assert_eq!(w.ntn[0].terror, 100);
+        assert_eq!(w.ntn[0].reputation, 0);
+        assert_eq!(w.ntn[0].deeds.massacres, 0);
+    }
+}
@@ -0,0 +1,147 @@
+// Food production, eating and spoilage.
+//
+// Farms grow food according to their vegetation, the nation's farming
+// ability and the season.  Everyone, civilian and soldier, eats from the
+// national store, and some of what is left rots.  Come up short and people
+// starve.  Working trade goods add their yield here too.
+
+use crate::news::Category;
+use crate::{Designation, Vegetation, World};
+
+// Food per 10 farmers in a normal season, by vegetation.
+pub fn veg_food(veg : &Vegetation) -> i32 {
+    match veg {
+        Vegetation::BARREN      => 4,
+        Vegetation::LT_VEG      => 6,
+        Vegetation::GOOD        => 9,
+        Vegetation::WOOD        => 7,
+        Vegetation::FOREST      => 4,
+        Vegetation::TUNDRA      => 1,
+        _                       => 0,
+    }
+}
+
+impl World {
+    // Food grown by the farm at (x, y) this turn, given its owner's ability.
+    pub fn farm_output(&self, x : usize, y : usize, farm_ability : u8) -> i32 {
+        let s = &self.sct[x][y];
+        if s.designation != Designation::FARM {
+            return 0;
+        }
+        let base = s.people.max(0) as i64 * veg_food(&s.vegetation) as i64 / 10;
+        let pct = self.season().farm_pct(&s.vegetation) as i64 * (100 + farm_ability as i64) / 100;
+        (base * pct / 100) as i32
+    }
+
+    // Grow, eat and spoil food for every nation.
+    pub(crate) fn update_production(&mut self) {
+        let spoil_pct = self.season().spoil_pct();
+
+        for idx in 0..self.ntn.len() {
+            if self.ntn[idx].race.is_special() {
+                continue;
+            }
+            let id = self.ntn[idx].id;
+            let owned = self.owned_sectors(id);
+            let ability = self.ntn[idx].farm_ability;
+            let grown : i32 = owned.iter().map(|&(x, y)| self.farm_output(x, y, ability)).sum();
+            let tciv : i32 = owned.iter().map(|&(x, y)| self.sct[x][y].people.max(0)).sum();
+            let tmil : i32 = self.ntn[idx].arm.iter().map(|a| a.sold.max(0)).sum();
+            let goods = self.goods_bonus(id);
+
+            let n = &mut self.ntn[idx];
+            n.tgold += goods.wealth;
+            n.jewels += goods.jewels;
+            n.metals += goods.metal;
+            n.knowledge = (n.knowledge as i32 + goods.knowledge).min(100) as u8;
+
+            let eatrate = n.eatrate as i64;
+            let eaten = ((tciv + tmil) as i64 * eatrate / 10) as i32;
+            n.tfood += grown + goods.food - eaten;
+
+            if n.tfood < 0 && eatrate > 0 && tciv > 0 {
+                // those who can't be fed don't survive the season
+                let starved = (-n.tfood as i64 * 10 / eatrate).min(tciv as i64) as i32;
+                n.tfood = 0;
+                let pct = starved as i64 * 100 / tciv as i64;
+                let (name, id) = (n.name.clone(), n.id);
+                for &(x, y) in &owned {
+                    let s = &mut self.sct[x][y];
+                    s.people -= (s.people as i64 * pct / 100) as i32;
+                }
+                self.report(Category::DISASTER, &[id], None, format!("famine in {}; {} people starve", name, starved));
+            } else {
+                n.tfood = n.tfood.max(0);
+                let spoil_pct = spoil_pct as i64 * (100 - goods.spoilage.min(100)) as i64 / 100;
+                n.tfood -= (n.tfood as i64 * n.spoilrate as i64 * spoil_pct / 10000) as i32;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{land, settle};
+    use crate::{Army, Nation, Race, PC};
+
+    // One farm of 1000 people, and a town of 1000 beside it.
+    fn world(turn : i16) -> World {
+        let mut w = land(2, 1);
+        w.turn = turn;
+        let n = Nation {
+            race            : Race::HUMAN,
+            active          : PC,
+            farm_ability    : 10,
+            eatrate         : 1,
+            spoilrate       : 10,
+            tfood           : 1000,
+            arm             : vec![Army { sold: 500, ..Default::default() }],
+            ..Default::default()
+        };
+        settle(&mut w, n, &[(0, 0), (1, 0)], 1000);
+        w.sct[0][0].designation = Designation::FARM;
+        w.sct[1][0].designation = Designation::TOWN;
+        w
+    }
+
+    #[test]
+    fn farms_grow_by_vegetation_ability_and_season() {
+        let w = world(0);
+        assert_eq!(w.farm_output(0, 0, 10), 1000 * 9 / 10 * 110 / 100);
+        assert_eq!(w.farm_output(0, 0, 0), 900);
+        assert_eq!(w.farm_output(1, 0, 10), 0, "not a farm");
+        assert_eq!(world(2).farm_output(0, 0, 0), 900 * 140 / 100);
+        assert_eq!(world(3).farm_output(0, 0, 0), 900 * 30 / 100);
+    }
+
+    #[test]
+    fn everyone_eats_and_the_rest_spoils_by_season() {
+        // spring: 990 grown, 250 eaten, 10% of the rest spoils
+        let mut w = world(0);
+        w.update_production();
+        assert_eq!(w.ntn[0].tfood, 1740 - 174);
+
+        // summer spoils half as much again
+        let mut w = world(1);
+        let grown = w.farm_output(0, 0, 10);
+        w.update_production();
+        let left = 1000 + grown - 250;
+        assert_eq!(w.ntn[0].tfood, left - left * 15 / 100);
+    }
+
+    #[test]
+    fn the_hungry_starve() {
+        // winter: what can't be fed at half rations starves
+        let mut w = world(3);
+        w.ntn[0].tfood = 0;
+        w.ntn[0].eatrate = 5;
+        let grown = w.farm_output(0, 0, 10);
+        w.update_production();
+        let starved = (1250 - grown) * 10 / 5;
+        assert_eq!(w.ntn[0].tfood, 0);
+        let lost = 1000 * (starved * 100 / 2000) / 100;
+        assert_eq!((w.sct[0][0].people, w.sct[1][0].people), (1000 - lost, 1000 - lost));
+        assert!(w.news.iter().any(|n| n.text.contains(&format!("{} people starve", starved))));
+    }
+}
@@ -0,0 +1,339 @@
+// Python bindings, built with --features python.
+//
+// This makes the library a Python extension module called rconq.  Build
+// it with maturin, or with --features extension-module and copy
+// target/release/librconq.so to rconq.so somewhere on the Python path:
+//
+//      import rconq
+//      w = rconq.World.load("game.save")
+//      elves = w.nation("Elfland")
+//      print("\n".join(w.map(elves.id)))
+//      w.execute(elves.id, "draft 10 4 100")
+//      w.update()
+//      w.save("game.save")
+//
+// Orders are the words of Command::from_str, the same as the line protocol
+// takes after "do".  A script holding a World is god as far as the game is
+// concerned: nothing asks it for a password, so don't hand one a live
+// game that players are using (use the servers for that).
+//
+// Nation, Sector, Army and Navy are copies taken when asked for; they
+// don't change when the world does.  Race, Elevation, Vegetation and
+// Designation are the game's own enums.
+
+// pyo3's macros convert every PyResult, which clippy takes for a mistake.
+#![allow(clippy::useless_conversion)]
+
+use pyo3::exceptions::{PyIndexError, PyValueError};
+use pyo3::prelude::*;
+
+use crate::command::Command;
+use crate::server::nation_id;
+use crate::worldgen::GenOptions;
+use crate::{Army, Designation, Elevation, Nation, Navy, Race, Sector, Vegetation};
+
+// An army seen: (nation, army, x, y, soldiers).
+type Sighting = (i32, usize, usize, usize, i32);
+
+fn err(e : String) -> PyErr {
+    PyValueError::new_err(e)
+}
+
+#[pyclass(name = "World", unsendable)]
+struct PyWorld {
+    w               : crate::World,
+}
+
+#[pyclass(name = "Nation")]
+struct PyNation(Nation);
+
+#[pyclass(name = "Sector")]
+struct PySector(Sector);
+
+#[pyclass(name = "Army")]
+struct PyArmy(Army);
+
+#[pyclass(name = "Navy")]
+struct PyNavy(Navy);
+
+impl PyWorld {
+    // A nation by id or name.
+    fn find(&self, which : &Bound<'_, PyAny>) -> PyResult<i32> {
+        nation_id(&self.w, &which.str()?.to_string()).map_err(err)
+    }
+}
+
+#[pymethods]
+impl PyWorld {
+    // An empty world, all water.
+    #[new]
+    fn new(width : i16, height : i16, seed : u64) -> PyWorld {
+        PyWorld { w: crate::World::new(width, height, seed) }
+    }
+
+    // A new world with land, as "rConq create" makes.
+    #[staticmethod]
+    fn generate(width : i16, height : i16, seed : u64) -> PyWorld {
+        PyWorld { w: crate::World::generate(width, height, seed, &GenOptions::default()) }
+    }
+
+    #[staticmethod]
+    fn load(path : &str) -> PyResult<PyWorld> {
+        crate::World::load_file(path).map(|w| PyWorld { w }).map_err(err)
+    }
+
+    fn save(&self, path : &str) -> PyResult<()> {
+        self.w.save_file(path).map_err(err)
+    }
+
+    #[getter]
+    fn turn(&self) -> i16 {
+        self.w.turn
+    }
+
+    #[getter]
+    fn width(&self) -> i16 {
+        self.w.mapx
+    }
+
+    #[getter]
+    fn height(&self) -> i16 {
+        self.w.mapy
+    }
+
+    #[getter]
+    fn season(&self) -> String {
+        self.w.season().to_string()
+    }
+
+    // Nation id of the winner, once there is one.
+    #[getter]
+    fn winner(&self) -> Option<i32> {
+        self.w.winner
+    }
+
+    fn nations(&self) -> Vec<PyNation> {
+        self.w.ntn.iter().map(|n| PyNation(n.clone())).collect()
+    }
+
+    // A nation by id or name.
+    fn nation(&self, which : &Bound<'_, PyAny>) -> PyResult<PyNation> {
+        let id = self.find(which)?;
+        Ok(PyNation(self.w.nation(id).unwrap().clone()))
+    }
+
+    fn sector(&self, x : i32, y : i32) -> PyResult<PySector> {
+        if !self.w.on_map(x, y) {
+            return Err(PyIndexError::new_err(format!("{},{} is off the map", x, y)));
+        }
+        Ok(PySector(self.w.sct[x as usize][y as usize].clone()))
+    }
+
+    // The map as rows of characters, as the given nation sees it or the
+    // whole of it.
+    #[pyo3(signature = (nation = None))]
+    fn map(&self, nation : Option<&Bound<'_, PyAny>>) -> PyResult<Vec<String>> {
+        if let Some(n) = nation {
+            return Ok(self.w.view_map(self.find(n)?));
+        }
+        Ok((0..self.w.mapy as usize).map(|y| {
+            (0..self.w.mapx as usize).map(|x| match self.w.sector_owner(x, y) {
+                Some(o) => self.w.ntn[o].mark,
+                None    => self.w.sct[x][y].altitude.to_char(),
+            }).collect()
+        }).collect())
+    }
+
+    // Which sectors a nation can see, indexed [x][y].
+    fn visible(&self, nation : &Bound<'_, PyAny>) -> PyResult<Vec<Vec<bool>>> {
+        Ok(self.w.visible(self.find(nation)?))
+    }
+
+    // Other nations' armies a nation can see.
+    fn enemies(&self, nation : &Bound<'_, PyAny>) -> PyResult<Vec<Sighting>> {
+        Ok(self.w.armies_in_view(self.find(nation)?))
+    }
+
+    // Give an order for a nation, e.g. "move 0 4 5".
+    fn execute(&mut self, nation : &Bound<'_, PyAny>, order : &str) -> PyResult<()> {
+        let id = self.find(nation)?;
+        let cmd : Command = order.parse().map_err(err)?;
+        self.w.execute(id, &cmd).map_err(err)
+    }
+
+    // Does password let this nation in?  Wrong ones count toward the
+    // lockout as they would anywhere else.
+    fn login(&mut self, nation : &Bound<'_, PyAny>, password : &str) -> PyResult<bool> {
+        let id = self.find(nation)?;
+        Ok(self.w.login(id, password).is_ok())
+    }
+
+    // Run the turn.
+    fn update(&mut self) {
+        self.w.update();
+    }
+
+    // The news of a turn, the last one by default.
+    #[pyo3(signature = (turn = None))]
+    fn news(&self, turn : Option<i16>) -> Vec<String> {
+        let turn = turn.unwrap_or((self.w.turn - 1).max(0));
+        self.w.news_of(turn).iter().map(|n| n.text.clone()).collect()
+    }
+
+    // Anything wrong with the world; see persistence.rs.
+    fn problems(&self) -> Vec<String> {
+        self.w.problems()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("<World {}x{} turn {}>", self.w.mapx, self.w.mapy, self.w.turn)
+    }
+}
+
+#[pymethods]
+impl PyNation {
+    #[getter] fn id(&self) -> i32 { self.0.id }
+    #[getter] fn name(&self) -> String { self.0.name.clone() }
+    #[getter] fn leader(&self) -> String { self.0.leader.clone() }
+    #[getter] fn race(&self) -> Race { self.0.race.clone() }
+    #[getter] fn mark(&self) -> char { self.0.mark }
+    #[getter] fn capitol(&self) -> (u8, u8) { (self.0.capx, self.0.capy) }
+    #[getter] fn score(&self) -> i32 { self.0.score }
+    #[getter] fn gold(&self) -> i32 { self.0.tgold }
+    #[getter] fn jewels(&self) -> i32 { self.0.jewels }
+    #[getter] fn metal(&self) -> i32 { self.0.metals }
+    #[getter] fn food(&self) -> i32 { self.0.tfood }
+    #[getter] fn military(&self) -> i32 { self.0.tmil }
+    #[getter] fn civilians(&self) -> i32 { self.0.tciv }
+    #[getter] fn sectors(&self) -> i16 { self.0.tsctrs }
+    #[getter] fn tax_rate(&self) -> u8 { self.0.tax_rate }
+    #[getter] fn popularity(&self) -> u8 { self.0.popularity }
+    #[getter] fn karma(&self) -> i16 { self.0.karma }
+    #[getter] fn armies(&self) -> Vec<PyArmy> { self.0.arm.iter().map(|a| PyArmy(a.clone())).collect() }
+    #[getter] fn navies(&self) -> Vec<PyNavy> { self.0.nvy.iter().map(|f| PyNavy(f.clone())).collect() }
+
+    fn __repr__(&self) -> String {
+        format!("<Nation {} {} ({})>", self.0.id, self.0.name, self.0.race)
+    }
+}
+
+#[pymethods]
+impl PySector {
+    #[getter] fn designation(&self) -> Designation { self.0.designation.clone() }
+    #[getter] fn altitude(&self) -> Elevation { self.0.altitude.clone() }
+    #[getter] fn vegetation(&self) -> Vegetation { self.0.vegetation.clone() }
+    #[getter] fn owner(&self) -> u8 { self.0.owner }
+    #[getter] fn people(&self) -> i32 { self.0.people }
+    #[getter] fn fortress(&self) -> u8 { self.0.fortress }
+    #[getter] fn jewels(&self) -> u8 { self.0.jewels }
+    #[getter] fn metal(&self) -> u8 { self.0.metal }
+    #[getter] fn tradegood(&self) -> u8 { self.0.tradegood }
+    #[getter] fn foodstore(&self) -> i32 { self.0.foodstore }
+
+    fn __repr__(&self) -> String {
+        format!("<Sector {} {} {} owner {}>", self.0.designation, self.0.altitude, self.0.vegetation, self.0.owner)
+    }
+}
+
+#[pymethods]
+impl PyArmy {
+    #[getter] fn x(&self) -> u8 { self.0.xloc }
+    #[getter] fn y(&self) -> u8 { self.0.yloc }
+    #[getter] fn soldiers(&self) -> i32 { self.0.sold }
+    #[getter] fn movement(&self) -> u8 { self.0.smove }
+    #[getter] fn status(&self) -> u8 { self.0.stat }
+    #[getter] fn unit_type(&self) -> u8 { self.0.unittyp }
+
+    fn __repr__(&self) -> String {
+        format!("<Army {} at {},{}>", self.0.sold, self.0.xloc, self.0.yloc)
+    }
+}
+
+#[pymethods]
+impl PyNavy {
+    #[getter] fn x(&self) -> u8 { self.0.xloc }
+    #[getter] fn y(&self) -> u8 { self.0.yloc }
+    #[getter] fn warships(&self) -> u16 { self.0.warships }
+    #[getter] fn merchant(&self) -> u16 { self.0.merchant }
+    #[getter] fn galleys(&self) -> u16 { self.0.galleys }
+    #[getter] fn movement(&self) -> u8 { self.0.smove }
+    #[getter] fn crew(&self) -> u8 { self.0.crew }
+    #[getter] fn people(&self) -> u8 { self.0.people }
+
+    fn __repr__(&self) -> String {
+        format!("<Navy {}/{}/{} at {},{}>", self.0.warships, self.0.merchant, self.0.galleys, self.0.xloc, self.0.yloc)
+    }
+}
+
+#[pymodule]
+fn rconq(m : &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyWorld>()?;
+    m.add_class::<PyNation>()?;
+    m.add_class::<PySector>()?;
+    m.add_class::<PyArmy>()?;
+    m.add_class::<PyNavy>()?;
+    m.add_class::<Race>()?;
+    m.add_class::<Elevation>()?;
+    m.add_class::<Vegetation>()?;
+    m.add_class::<Designation>()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::players;
+
+    // A world loaded the way a script would, from players() saved to a file.
+    fn world(name : &str) -> PyWorld {
+        let (w, ..) = players();
+        let path = std::env::temp_dir().join(format!("rconq-python-{}-{}.save", name, std::process::id()));
+        let path = path.to_str().unwrap();
+        w.save_file(path).unwrap();
+        let w = PyWorld::load(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+        w
+    }
+
+    #[test]
+    fn scripts_load_and_look_at_the_map() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let w = world("map");
+            assert_eq!((w.width(), w.height(), w.turn()), (20, 5, 0));
+            assert!(PyWorld::load("/nonexistent/rconq.save").is_err());
+
+            let elves = "Elfland".to_object(py).into_bound(py);
+            let id = w.nation(&elves).unwrap().id();
+            let by_id = id.to_object(py).into_bound(py);
+            assert_eq!(w.nation(&by_id).unwrap().name(), "Elfland");
+            assert!(w.nation(&"Mordor".to_object(py).into_bound(py)).is_err());
+
+            let all = w.map(None).unwrap();
+            let seen = w.map(Some(&elves)).unwrap();
+            assert_eq!((all.len(), seen.len()), (5, 5));
+            assert_eq!(all[3].chars().nth(18), Some(w.nation(&"Orcland".to_object(py).into_bound(py)).unwrap().mark()));
+            assert_eq!(seen[3].chars().nth(18), Some(' '));
+            assert!(w.sector(20, 0).is_err());
+        });
+    }
+
+    #[test]
+    fn scripts_give_orders_and_run_the_turn() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let mut w = world("orders");
+            let elves = "Elfland".to_object(py).into_bound(py);
+            w.execute(&elves, "draft 1 1 10").unwrap();
+            assert_eq!(w.nation(&elves).unwrap().armies().len(), 2);
+            assert!(w.execute(&elves, "draft 18 3 10").is_err());
+            assert!(w.execute(&elves, "fly 1 1").is_err());
+            assert!(!w.login(&elves, "friend").unwrap() && w.login(&elves, "mellon").unwrap());
+
+            w.update();
+            assert_eq!((w.turn(), w.season()), (1, "SUMMER".to_string()));
+            assert!(!w.news(None).is_empty());
+            assert!(w.problems().is_empty());
+        });
+    }
+}
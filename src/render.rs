@@ -0,0 +1,353 @@
+//! Pictures of the map, as PNG or SVG.
+//!
+//! Each sector is a square coloured for its terrain: water and peaks by
+//! their [`Elevation`], other land by its [`Vegetation`], darker the higher
+//! it is.  Land held by a nation gets a border in the nation's colour all
+//! round its edge, toward other nations, unowned land, water and the end
+//! of the map alike, and anything built on it gets a small icon.  Armies
+//! and fleets can be marked too.  There are twelve nation colours, picked
+//! by nation id, so nation 13 looks like nation 1.
+//!
+//! The picture is either god's view of everything or what one nation can
+//! see (see vision.rs), with the rest left dark and no borders drawn
+//! toward it.  Both formats are drawn from the same rectangles, so a PNG
+//! and an SVG of the same view match.
+
+use crate::{Designation, Elevation, Vegetation, World};
+
+type Colour = (u8, u8, u8);
+
+const MAX_PIXELS        : u64 = 1 << 26;    // largest picture drawn
+const UNSEEN            : Colour = (0x1a, 0x1a, 0x1a);
+const INK               : Colour = (0x10, 0x10, 0x10);  // icons and outlines
+
+// Nation colours, by place in World::ntn.
+const NATION_COLOURS : [Colour; 12] = [
+    (0xe6, 0x19, 0x4b), (0x43, 0x63, 0xd8), (0xff, 0xe1, 0x19), (0xf5, 0x82, 0x31),
+    (0x91, 0x1e, 0xb4), (0x46, 0xf0, 0xf0), (0xf0, 0x32, 0xe6), (0xbc, 0xf6, 0x0c),
+    (0x00, 0x80, 0x80), (0x9a, 0x63, 0x24), (0x80, 0x00, 0x00), (0xff, 0xff, 0xff),
+];
+
+/// How to draw the map.
+#[derive(Debug)]
+#[derive(Clone)]
+pub struct RenderOptions {
+    pub nation      : Option<i32>,  // draw what this nation can see; None for god's view
+    pub scale       : u32,          // pixels along each side of a sector
+    pub units       : bool,         // mark armies and fleets
+}
+
+impl Default for RenderOptions {
+    fn default() -> RenderOptions {
+        RenderOptions {
+            nation      : None,
+            scale       : 12,
+            units       : false,
+        }
+    }
+}
+
+fn shade((r, g, b) : Colour, pct : u32) -> Colour {
+    let f = |c : u8| (c as u32 * pct / 100) as u8;
+    (f(r), f(g), f(b))
+}
+
+fn terrain_colour(altitude : &Elevation, vegetation : &Vegetation) -> Colour {
+    let land = match vegetation {
+        Vegetation::VOLCANO     => (0x8b, 0x25, 0x00),
+        Vegetation::DESERT      => (0xe8, 0xd2, 0x8a),
+        Vegetation::TUNDRA      => (0xb8, 0xc4, 0xb0),
+        Vegetation::BARREN      => (0xb5, 0xa2, 0x7a),
+        Vegetation::LT_VEG      => (0xb7, 0xd9, 0x8b),
+        Vegetation::GOOD        => (0x7f, 0xbf, 0x5f),
+        Vegetation::WOOD        => (0x4f, 0x9a, 0x45),
+        Vegetation::FOREST      => (0x2f, 0x7a, 0x32),
+        Vegetation::JUNGLE      => (0x1f, 0x5f, 0x2a),
+        Vegetation::SWAMP       => (0x5e, 0x7a, 0x55),
+        Vegetation::ICE         => (0xee, 0xf4, 0xf8),
+        _                       => (0xc8, 0xb8, 0x9a),
+    };
+    match altitude {
+        Elevation::WATER        => (0x2b, 0x5f, 0xa8),
+        Elevation::PEAK         => (0xf2, 0xf2, 0xf2),
+        Elevation::MOUNTAIN     => shade(land, 65),
+        Elevation::HILL         => shade(land, 85),
+        _                       => land,
+    }
+}
+
+// A 5x5 picture of what is built on a sector, or None for nothing.
+fn icon(d : &Designation) -> Option<&'static str> {
+    Some(match d {
+        Designation::CAPITOL    => "..#..#####.###..#.#.#...#",
+        Designation::CITY       => "#.#.#######...##...######",
+        Designation::TOWN       => ".......#...###..#.#..###.",
+        Designation::MINE       => "#...#.#.#...#...#.#.#...#",
+        Designation::GOLDMINE   => "..#...###.#####.###...#..",
+        Designation::FARM       => "#.#.##.#.##.#.##.#.##.#.#",
+        Designation::FORT       => "#.#.#######.####.#.######",
+        Designation::STOCKADE   => "#.#.##.#.######.#.##.#.#.",
+        Designation::DEVASTATED => "#.#...#..##..#...#.#.#..#",
+        Designation::RUIN       => "#....#..#.#..#.##.#######",
+        Designation::SPECIAL    => ".###.#...#..##.......#...",
+        Designation::LUMBERYARD => "..#...###.#####..#....#..",
+        Designation::BLACKSMITH => "#####.###...#...###.#####",
+        Designation::ROAD       => "..........#####..........",
+        Designation::MILL       => "#.#.#.###.##.##.###.#.#.#",
+        Designation::GRANARY    => ".###.######...##...######",
+        Designation::CHURCH     => "..#...###...#....#....#..",
+        Designation::UNIVERSITY => ".###.#######.#.##.#.#####",
+        Designation::BASECAMP   => "..#...#.#.#...######.....",
+        _                       => return None,
+    })
+}
+
+// Something to draw on.  Everything is made of filled rectangles.
+trait Canvas {
+    fn rect(&mut self, x : u32, y : u32, w : u32, h : u32, c : Colour);
+}
+
+struct Svg {
+    out             : String,
+}
+
+impl Canvas for Svg {
+    fn rect(&mut self, x : u32, y : u32, w : u32, h : u32, (r, g, b) : Colour) {
+        self.out += &format!("<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"#{:02x}{:02x}{:02x}\"/>\n",
+            x, y, w, h, r, g, b);
+    }
+}
+
+struct Raster {
+    width           : u32,
+    height          : u32,
+    rgb             : Vec<u8>,
+}
+
+impl Canvas for Raster {
+    fn rect(&mut self, x : u32, y : u32, w : u32, h : u32, (r, g, b) : Colour) {
+        for py in y..(y + h).min(self.height) {
+            for px in x..(x + w).min(self.width) {
+                let i = 3 * (py as usize * self.width as usize + px as usize);
+                self.rgb[i..i + 3].copy_from_slice(&[r, g, b]);
+            }
+        }
+    }
+}
+
+impl World {
+    // Size of the picture in pixels, or why it can't be drawn.
+    fn picture_size(&self, opts : &RenderOptions) -> Result<(u32, u32), String> {
+        if let Some(id) = opts.nation {
+            self.nation_index(id).ok_or(format!("no nation {}", id))?;
+        }
+        if opts.scale == 0 {
+            return Err("the scale must be at least 1".to_string());
+        }
+        let (w, h) = (self.mapx as u64 * opts.scale as u64, self.mapy as u64 * opts.scale as u64);
+        if w * h > MAX_PIXELS {
+            return Err(format!("a {}x{} picture is too big; try a smaller scale", w, h));
+        }
+        Ok((w as u32, h as u32))
+    }
+
+    /// The colour nation id is drawn in, which it keeps as long as it
+    /// keeps the id; they repeat after twelve.
+    pub fn nation_colour(&self, id : i32) -> Colour {
+        NATION_COLOURS[(id - 1).max(0) as usize % NATION_COLOURS.len()]
+    }
+
+    fn draw(&self, opts : &RenderOptions, c : &mut dyn Canvas) {
+        let (mx, my) = (self.mapx as usize, self.mapy as usize);
+        let seen = match opts.nation {
+            Some(id)    => self.visible(id),
+            None        => vec![vec![true; my]; mx],
+        };
+        let k = opts.scale;
+        let line = (k / 8).max(1);
+        let dot = k / 7;
+        let owner = |x : usize, y : usize| self.sector_owner(x, y).map(|i| self.ntn[i].id);
+
+        for (x, col) in self.sct.iter().enumerate() {
+            for (y, s) in col.iter().enumerate() {
+                let (px, py) = (x as u32 * k, y as u32 * k);
+                if !seen[x][y] {
+                    c.rect(px, py, k, k, UNSEEN);
+                    continue;
+                }
+                c.rect(px, py, k, k, terrain_colour(&s.altitude, &s.vegetation));
+
+                if let Some(id) = owner(x, y) {
+                    // no border toward what the viewer can't see, or it
+                    // would give away who holds it
+                    let colour = self.nation_colour(id);
+                    let other = |dx : i32, dy : i32| {
+                        let (nx, ny) = (x as i32 + dx, y as i32 + dy);
+                        !self.on_map(nx, ny)
+                            || (seen[nx as usize][ny as usize] && owner(nx as usize, ny as usize) != Some(id))
+                    };
+                    if other(0, -1) { c.rect(px, py, k, line, colour) }
+                    if other(0, 1)  { c.rect(px, py + k - line, k, line, colour) }
+                    if other(-1, 0) { c.rect(px, py, line, k, colour) }
+                    if other(1, 0)  { c.rect(px + k - line, py, line, k, colour) }
+                }
+
+                if let (Some(pic), true) = (icon(&s.designation), dot > 0) {
+                    let at = (k - 5 * dot) / 2;
+                    for (i, _) in pic.chars().enumerate().filter(|(_, p)| *p == '#') {
+                        let (ix, iy) = (i as u32 % 5, i as u32 / 5);
+                        c.rect(px + at + ix * dot, py + at + iy * dot, dot, dot, INK);
+                    }
+                }
+            }
+        }
+
+        if !opts.units {
+            return;
+        }
+        // a square for an army in the top left of its sector, a flat bar for
+        // a fleet in the bottom right
+        let size = (k / 3).max(2);
+        let mut mark = |x : usize, y : usize, id : i32, fleet : bool| {
+            if !self.on_map(x as i32, y as i32) || !seen[x][y] {
+                return;
+            }
+            let (w, h) = if fleet { (size + size / 2, size / 2 + 1) } else { (size, size) };
+            let (px, py) = if fleet {
+                ((x as u32 + 1) * k - w - 1, (y as u32 + 1) * k - h - 1)
+            } else {
+                (x as u32 * k + 1, y as u32 * k + 1)
+            };
+            c.rect(px, py, w, h, INK);
+            if w > 2 && h > 2 {
+                c.rect(px + 1, py + 1, w - 2, h - 2, self.nation_colour(id));
+            }
+        };
+        for n in &self.ntn {
+            for a in n.arm.iter().filter(|a| a.sold > 0) {
+                mark(a.xloc as usize, a.yloc as usize, n.id, false);
+            }
+            for f in &n.nvy {
+                mark(f.xloc as usize, f.yloc as usize, n.id, true);
+            }
+        }
+    }
+
+    /// The map as an SVG document.
+    pub fn render_svg(&self, opts : &RenderOptions) -> Result<String, String> {
+        let (w, h) = self.picture_size(opts)?;
+        let mut svg = Svg { out: String::new() };
+        svg.out += &format!("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\" shape-rendering=\"crispEdges\">\n",
+            w, h, w, h);
+        self.draw(opts, &mut svg);
+        svg.out += "</svg>\n";
+        Ok(svg.out)
+    }
+
+    /// The map as a PNG image.
+    pub fn render_png(&self, opts : &RenderOptions) -> Result<Vec<u8>, String> {
+        let (width, height) = self.picture_size(opts)?;
+        let mut raster = Raster { width, height, rgb: vec![0; 3 * width as usize * height as usize] };
+        self.draw(opts, &mut raster);
+
+        let mut out = Vec::new();
+        let mut png = png::Encoder::new(&mut out, width, height);
+        png.set_color(png::ColorType::Rgb);
+        png.set_depth(png::BitDepth::Eight);
+        let mut writer = png.write_header().map_err(|e| e.to_string())?;
+        writer.write_image_data(&raster.rgb).map_err(|e| e.to_string())?;
+        writer.finish().map_err(|e| e.to_string())?;
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::land;
+    use crate::{Army, Race, PC};
+
+    fn world() -> (World, i32, i32) {
+        let mut w = land(12, 4);
+        let e = w.create_nation("Elfland", Race::ELF, PC, Some((1, 1))).unwrap();
+        let o = w.create_nation("Orcland", Race::ORC, PC, Some((10, 2))).unwrap();
+        w.sct[6][0].altitude = Elevation::WATER;
+        (w, e, o)
+    }
+
+    // The colour of pixel (x, y) of a PNG.
+    fn pixel(png : &[u8], x : usize, y : usize) -> Colour {
+        let mut reader = png::Decoder::new(png).read_info().unwrap();
+        let mut buf = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buf).unwrap();
+        let i = 3 * (y * info.width as usize + x);
+        (buf[i], buf[i + 1], buf[i + 2])
+    }
+
+    #[test]
+    fn god_sees_the_whole_map_drawn() {
+        let (mut w, e, o) = world();
+        w.ntn[0].arm.push(Army { xloc: 4, yloc: 3, sold: 20, ..Default::default() });
+        let opts = RenderOptions { scale: 10, units: true, ..Default::default() };
+
+        let png = w.render_png(&opts).unwrap();
+        assert_eq!(&png[1..4], b"PNG");
+        assert_eq!(pixel(&png, 65, 5), terrain_colour(&Elevation::WATER, &Vegetation::GOOD));
+        assert_eq!(pixel(&png, 10, 15), w.nation_colour(e));      // the capitol's west edge
+        assert_eq!(pixel(&png, 100, 25), w.nation_colour(o));
+        assert_eq!(pixel(&png, 12, 13), INK);                       // the capitol's icon
+        assert_eq!(pixel(&png, 42, 32), w.nation_colour(e));      // the army
+
+        let svg = w.render_svg(&opts).unwrap();
+        assert!(svg.starts_with("<svg") && svg.contains("width=\"120\" height=\"40\""));
+        let (r, g, b) = w.nation_colour(o);
+        assert!(svg.contains(&format!("#{:02x}{:02x}{:02x}", r, g, b)));
+    }
+
+    #[test]
+    fn a_nation_sees_only_its_corner() {
+        let (w, e, o) = world();
+        let opts = RenderOptions { nation: Some(e), scale: 8, units: true };
+        let png = w.render_png(&opts).unwrap();
+        assert_eq!(pixel(&png, 84, 20), UNSEEN);
+        assert_ne!(pixel(&png, 12, 12), UNSEEN);
+
+        let (r, g, b) = w.nation_colour(o);
+        assert!(!w.render_svg(&opts).unwrap().contains(&format!("#{:02x}{:02x}{:02x}", r, g, b)));
+        assert!(w.render_png(&RenderOptions { nation: Some(99), ..Default::default() }).is_err());
+        assert!(w.render_svg(&RenderOptions { scale: 0, ..Default::default() }).is_err());
+    }
+
+    #[test]
+    fn borders_stop_where_sight_does() {
+        // orcs on the edge of what the elves can see, with nobody beyond
+        let (mut w, e, o) = world();
+        let seen = w.visible(e);
+        let x = (0..12).rev().find(|&x| seen[x][1]).unwrap();
+        w.sct[x][1].owner = o as u8;
+        w.sct[x + 1][1].owner = 0;
+        let (edge, y) = (8 * x + 7, 8 + 4);
+
+        let god = w.render_png(&RenderOptions { scale: 8, ..Default::default() }).unwrap();
+        assert_eq!(pixel(&god, edge, y), w.nation_colour(o));
+        let own = w.render_png(&RenderOptions { nation: Some(e), scale: 8, units: false }).unwrap();
+        assert_eq!(pixel(&own, edge, y), terrain_colour(&Elevation::CLEAR, &Vegetation::GOOD));
+        assert_eq!(pixel(&own, edge + 1, y), UNSEEN);
+    }
+
+    #[test]
+    fn nations_keep_their_colours_and_the_first_twelve_are_all_different() {
+        let mut w = land(1, 1);
+        let ids : Vec<i32> = (0..13)
+            .map(|i| w.create_nation(&format!("Realm{}", i), Race::HUMAN, PC, None).unwrap())
+            .collect();
+        let colours : Vec<Colour> = ids.iter().map(|&id| w.nation_colour(id)).collect();
+        for i in 0..12 {
+            assert!(!colours[i + 1..12].contains(&colours[i]));
+        }
+        assert_eq!(colours[12], colours[0]);
+
+        w.destroy_nation(ids[0]).unwrap();
+        assert_eq!(w.nation_colour(ids[5]), colours[5]);
+    }
+}
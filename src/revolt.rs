@@ -0,0 +1,293 @@
+// Civil unrest.
+//
+// Sectors of an unpopular, poor or sprawling nation may rise up.  Whatever
+// rebels in a turn breaks away as a new NPC nation, taking neighbouring
+// disloyal sectors and any armies in the area that would rather not fight
+// their own people.
+
+use crate::politics::revolt_chance;
+use crate::{distance, Designation, Nation, World, INACTIVE, NPC_DEFENSIVE, WAR};
+
+const MIN_LOYAL_RANGE   : i32 = 2;      // sectors this close to the capitol won't spread a revolt
+const REBEL_POPULARITY  : u8 = 60;      // a fresh revolution is popular, for a while
+
+#[derive(Debug)]
+#[derive(Clone)]
+pub struct Revolt {
+    pub parent      : i32,                  // nation id revolted against
+    pub rebel       : i32,                  // nation id of the new rebels
+    pub sectors     : Vec<(usize, usize)>,  // sectors that went over
+    pub armies      : usize,                // armies that defected
+}
+
+// How far from the capitol the leader's word still carries.
+fn loyal_range(n : &Nation) -> i32 {
+    MIN_LOYAL_RANGE + n.communications as i32 / 10
+}
+
+impl World {
+    // Where nation n's capitol is, if it still holds one.
+    pub fn capitol(&self, n : &Nation) -> Option<(usize, usize)> {
+        let (x, y) = (n.capx as usize, n.capy as usize);
+        if !self.on_map(x as i32, y as i32) {
+            return None;
+        }
+        let s = &self.sct[x][y];
+        if s.owner as i32 == n.id && s.designation == Designation::CAPITOL {
+            Some((x, y))
+        } else {
+            None
+        }
+    }
+
+    // Roll for revolts in every ordinary nation.
+    pub(crate) fn update_revolts(&mut self) -> Vec<Revolt> {
+        let ids : Vec<i32> = self.ntn.iter()
+            .filter(|n| !n.race.is_special() && n.active != INACTIVE)
+            .map(|n| n.id)
+            .collect();
+
+        ids.into_iter().filter_map(|id| self.check_revolt(id)).collect()
+    }
+
+    fn check_revolt(&mut self, id : i32) -> Option<Revolt> {
+        let idx = self.nation_index(id)?;
+        let chance = revolt_chance(&self.ntn[idx]);
+        if chance == 0 {
+            return None;
+        }
+        let range = loyal_range(&self.ntn[idx]);
+        let cap = self.capitol(&self.ntn[idx]);
+        let far = self.mapx.max(self.mapy) as i32;
+        let dist = |x : usize, y : usize| match cap {
+            Some((cx, cy)) => distance(x as i32, y as i32, cx as i32, cy as i32),
+            None => far,
+        };
+
+        // the further out, the less anyone cares what the capitol says
+        let owned = self.owned_sectors(id);
+        let mut rebels = Vec::new();
+        for &(x, y) in &owned {
+            if Some((x, y)) == cap || self.sct[x][y].people <= 0 {
+                continue;
+            }
+            if self.rng.permille(chance * dist(x, y) / range / 2) {
+                rebels.push((x, y));
+            }
+        }
+        if rebels.is_empty() {
+            return None;
+        }
+
+        // neighbouring sectors out of reach of the capitol join in
+        let mut spread = Vec::new();
+        for &(x, y) in &rebels {
+            for (nx, ny) in self.neighbours(x, y) {
+                let p = (nx, ny);
+                if self.sct[nx][ny].owner as i32 == id && Some(p) != cap && dist(nx, ny) > range
+                    && !rebels.contains(&p) && !spread.contains(&p) {
+                    spread.push(p);
+                }
+            }
+        }
+        rebels.extend(spread);
+
+        self.split_nation(id, rebels)
+    }
+
+    // "Realm rebels", or if there are some already, "Realm rebels 2" and so on.
+    fn rebel_name(&self, parent : &str) -> String {
+        let taken = |name : &String| self.ntn.iter().any(|n| n.name == *name);
+        let mut name = format!("{} rebels", parent);
+        let mut i = 1;
+        while taken(&name) {
+            i += 1;
+            name = format!("{} rebels {}", parent, i);
+        }
+        name
+    }
+
+    // Break rebels away from nation id as a new NPC nation, taking nearby
+    // armies that won't stay loyal.  rebels must be non-empty and owned by
+    // id.  None if there is no id left for the rebels to hold land under.
+    pub fn split_nation(&mut self, id : i32, rebels : Vec<(usize, usize)>) -> Option<Revolt> {
+        let idx = self.nation_index(id).expect("split_nation on a missing nation");
+
+        // the most populous rebel sector becomes the new capitol
+        let &(capx, capy) = rebels.iter().max_by_key(|&&(x, y)| self.sct[x][y].people).unwrap();
+        let rebel_id = self.claim_id()?;
+        let parent = &self.ntn[idx];
+        let mut rebel = Nation {
+            name            : self.rebel_name(&parent.name),
+            leader          : String::from("rebel"),
+            race            : parent.race.clone(),
+            mark            : self.free_mark(),
+            capx            : capx as u8,
+            capy            : capy as u8,
+            active          : NPC_DEFENSIVE,
+            maxmove         : parent.maxmove,
+            repro           : parent.repro,
+            class           : parent.class,
+            aplus           : parent.aplus,
+            dplus           : parent.dplus,
+            tsctrs          : rebels.len() as i16,
+            tax_rate        : parent.tax_rate,
+            popularity      : REBEL_POPULARITY,
+            reputation      : parent.reputation,
+            eatrate         : parent.eatrate,
+            spoilrate       : parent.spoilrate,
+            knowledge       : parent.knowledge,
+            farm_ability    : parent.farm_ability,
+            mine_ability    : parent.mine_ability,
+            ..Nation::with_id(rebel_id)
+        };
+
+        // the rebels make off with their share of the stores
+        let people = |w : &World, v : &[(usize, usize)]| -> i64 {
+            v.iter().map(|&(x, y)| w.sct[x][y].people.max(0) as i64).sum()
+        };
+        let total = people(self, &self.owned_sectors(id)).max(1);
+        let share = people(self, &rebels);
+        let parent = &mut self.ntn[idx];
+        let food = (parent.tfood.max(0) as i64 * share / total) as i32;
+        let gold = (parent.tgold.max(0) as i64 * share / total) as i32;
+        parent.tfood -= food;
+        parent.tgold -= gold;
+        rebel.tfood = food;
+        rebel.tgold = gold;
+
+        for &(x, y) in &rebels {
+            self.sct[x][y].owner = rebel_id as u8;
+        }
+        self.sct[capx][capy].designation = Designation::CAPITOL;
+
+        // armies near the trouble may decide they are rebels too
+        let loyalty = self.ntn[idx].popularity as i32;
+        let mut defectors = Vec::new();
+        let mut i = 0;
+        while i < self.ntn[idx].arm.len() {
+            let a = &self.ntn[idx].arm[i];
+            let near = rebels.iter()
+                .any(|&(x, y)| distance(a.xloc as i32, a.yloc as i32, x as i32, y as i32) <= 1);
+            if near && a.sold > 0 && !self.rng.percent(loyalty) {
+                defectors.push(self.ntn[idx].arm.remove(i));
+            } else {
+                i += 1;
+            }
+        }
+        let armies = defectors.len();
+
+        let parent = &mut self.ntn[idx];
+        parent.tsctrs = (parent.tsctrs - rebels.len() as i16).max(0);
+        parent.deeds.lost += rebels.len() as i32;
+
+        // and there is no going back
+        self.ntn[idx].set_status(rebel_id, WAR);
+        rebel.set_status(id, WAR);
+
+        rebel.arm = defectors;
+        self.ntn.push(rebel);
+
+        Some(Revolt { parent: id, rebel: rebel_id, sectors: rebels, armies })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{land, settle};
+    use crate::{Army, Race, PC};
+
+    // A row of ten sectors of 100 people, the capitol at the west end.
+    fn world() -> (World, i32) {
+        let mut w = land(10, 1);
+        let n = Nation {
+            name            : String::from("Realm"),
+            race            : Race::HUMAN,
+            active          : PC,
+            popularity      : 60,
+            communications  : 0,
+            tgold           : 1200,
+            tfood           : 2400,
+            tsctrs          : 10,
+            ..Default::default()
+        };
+        let row : Vec<(usize, usize)> = (0..10).map(|x| (x, 0)).collect();
+        let id = settle(&mut w, n, &row, 100);
+        w.sct[0][0].designation = Designation::CAPITOL;
+        (w, id)
+    }
+
+    // Does nation id revolt within a hundred turns?
+    fn revolts(w : &mut World, id : i32) -> bool {
+        (0..100).any(|_| w.check_revolt(id).is_some())
+    }
+
+    #[test]
+    fn discontent_far_from_the_capitol_revolts() {
+        let (mut w, id) = world();
+        assert!(revolts(&mut w, id));
+        let kept = w.owned_sectors(id);
+        assert!(kept.len() < 10 && kept.contains(&(0, 0)));
+    }
+
+    #[test]
+    fn popular_or_feared_nations_stay_quiet() {
+        let (mut w, id) = world();
+        w.ntn[0].popularity = 100;
+        w.ntn[0].communications = 100;
+        assert!(!revolts(&mut w, id));
+
+        let (mut w, id) = world();
+        w.ntn[0].terror = 100;
+        assert!(!revolts(&mut w, id));
+        assert_eq!(w.owned_sectors(id).len(), 10);
+    }
+
+    #[test]
+    fn rebels_take_land_stores_capitol_and_armies() {
+        let (mut w, id) = world();
+        w.ntn[0].popularity = 0;
+        w.ntn[0].arm = vec![
+            Army { xloc: 0, yloc: 0, sold: 50, ..Default::default() },
+            Army { xloc: 6, yloc: 0, sold: 50, ..Default::default() },
+        ];
+        w.sct[7][0].people = 300;
+
+        let r = w.split_nation(id, vec![(6, 0), (7, 0)]).unwrap();
+        assert_eq!((r.parent, r.armies), (id, 1));
+        let rebel = w.nation(r.rebel).unwrap();
+        assert_eq!((rebel.capx, rebel.capy, rebel.tsctrs), (7, 0, 2));
+        assert_eq!(w.sct[7][0].designation, Designation::CAPITOL);
+        assert_eq!(w.owned_sectors(r.rebel), vec![(6, 0), (7, 0)]);
+
+        // a third of the people went, and a third of the gold and food
+        assert_eq!((rebel.tgold, rebel.tfood), (400, 800));
+        assert_eq!(rebel.arm[0].xloc, 6);
+        let parent = &w.ntn[0];
+        assert_eq!((parent.tgold, parent.tfood, parent.tsctrs), (800, 1600, 8));
+        assert_eq!(parent.arm.len(), 1);
+        assert_eq!(parent.arm[0].xloc, 0);
+    }
+
+    #[test]
+    fn rebels_take_an_id_nobody_here_is_using() {
+        let (mut w, id) = world();
+        let free = w.free_id().unwrap();
+        let r = w.split_nation(id, vec![(9, 0)]).unwrap();
+        assert!(r.rebel == free && r.rebel != id);
+        assert_eq!(w.sct[9][0].owner as i32, r.rebel);
+    }
+
+    #[test]
+    fn rebels_are_at_war_with_their_parent_under_a_name_of_their_own() {
+        let (mut w, id) = world();
+        let first = w.split_nation(id, vec![(9, 0)]).unwrap().rebel;
+        let second = w.split_nation(id, vec![(8, 0)]).unwrap().rebel;
+        for r in [first, second] {
+            assert_eq!((w.ntn[0].status(r), w.nation(r).unwrap().status(id)), (WAR, WAR));
+        }
+        assert_eq!(w.nation(first).unwrap().name, "Realm rebels");
+        assert_eq!(w.nation(second).unwrap().name, "Realm rebels 2");
+    }
+}
@@ -0,0 +1,65 @@
+// Small deterministic random number generator.
+//
+// The original leaned on rand()/srand() and friends.  We want the same
+// world seed to give the same game every time, so everything random goes
+// through the World's Rng instead.  xorshift64* is plenty for dice rolls.
+
+/// The game's dice, seeded from [`World::seed`](crate::World::seed) and
+/// saved with it.
+#[derive(Debug)]
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rng {
+    state           : u64,
+}
+
+impl Rng {
+    pub fn new(seed : u64) -> Rng {
+        // splitmix the seed so 0 and other small seeds still work
+        let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+        Rng { state: if z == 0 { 1 } else { z } }
+    }
+
+    /// Pick up exactly where a saved generator left off.
+    pub fn from_state(state : u64) -> Rng {
+        Rng { state: if state == 0 { 1 } else { state } }
+    }
+
+    pub fn state(&self) -> u64 {
+        self.state
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// 0..n, like rand()%n.  Returns 0 if n is 0.
+    pub fn below(&mut self, n : u32) -> u32 {
+        if n == 0 {
+            return 0;
+        }
+        (self.next_u64() % n as u64) as u32
+    }
+
+    /// True chance times in 100.
+    pub fn percent(&mut self, chance : i32) -> bool {
+        (self.below(100) as i32) < chance
+    }
+
+    /// True chance times in 1000.
+    pub fn permille(&mut self, chance : i32) -> bool {
+        (self.below(1000) as i32) < chance
+    }
+}
+
+impl Default for Rng {
+    fn default() -> Rng {
+        Rng::new(0)
+    }
+}
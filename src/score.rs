@@ -0,0 +1,151 @@
+// Scores and the end of the game.
+//
+// A nation's score is a point a sector plus points for its people, army,
+// treasury and magic, much as the original scored it.  World::score is the
+// sum over every ordinary nation.
+//
+// The original never ended; here the game is won when one of the enabled
+// Victory conditions is met.  After that update() and execute() refuse to
+// do anything, so the final position stays as it was.
+
+use crate::stats::military;
+use crate::{Designation, Nation, Race, World, INACTIVE};
+
+const SCORE_SECTOR      : i64 = 1;      // per sector owned
+const SCORE_CIV         : i64 = 1000;   // civilians per point
+const SCORE_MIL         : i64 = 1000;   // soldiers per point
+const SCORE_GOLD        : i64 = 10000;  // talons per point
+const SCORE_JEWELS      : i64 = 1000;   // jewels per point
+const SCORE_METAL       : i64 = 1000;   // metal per point
+const SCORE_POWER       : i64 = 5;      // per magic power
+
+// Ways to win.  Any that are set can end the game; none are by default.
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Victory {
+    pub score       : Option<i32>,      // first to this score wins
+    pub turns       : Option<i16>,      // highest score when this turn comes wins
+    pub last_standing : bool,           // the only nation left with land wins
+    pub capitols    : Option<usize>,    // first to hold this many capitols wins
+}
+
+// Score for a nation with the given totals.
+pub fn score(sectors : i32, civ : i32, mil : i32, n : &Nation) -> i32 {
+    let s = sectors.max(0) as i64 * SCORE_SECTOR
+        + civ.max(0) as i64 / SCORE_CIV
+        + mil.max(0) as i64 / SCORE_MIL
+        + n.tgold.max(0) as i64 / SCORE_GOLD
+        + n.jewels.max(0) as i64 / SCORE_JEWELS
+        + n.metals.max(0) as i64 / SCORE_METAL
+        + n.powers.count_ones() as i64 * SCORE_POWER;
+    s.min(i32::MAX as i64) as i32
+}
+
+// Nations that play for a score: not god, not monsters, not given up.
+fn playing(n : &Nation) -> bool {
+    n.race != Race::GOD && !n.race.is_special() && n.active != INACTIVE
+}
+
+impl World {
+    /// Recompute every nation's score and the world total.
+    pub(crate) fn update_score(&mut self) {
+        self.score = 0;
+        for idx in 0..self.ntn.len() {
+            let owned = self.owned_sectors(self.ntn[idx].id);
+            let civ : i32 = owned.iter().map(|&(x, y)| self.sct[x][y].people.max(0)).sum();
+            let n = &self.ntn[idx];
+            let s = score(owned.len() as i32, civ, military(n), n);
+
+            let n = &mut self.ntn[idx];
+            n.score = s;
+            if playing(n) {
+                self.score = self.score.saturating_add(s);
+            }
+        }
+    }
+
+    /// Has someone won?  Returns the winner's id and how.
+    pub fn check_victory(&self) -> Option<(i32, String)> {
+        let v = &self.victory;
+        let players : Vec<&Nation> = self.ntn.iter().filter(|n| playing(n)).collect();
+        let best = players.iter().max_by_key(|n| (n.score, -n.id));
+
+        if let Some(limit) = v.score {
+            if let Some(n) = best.filter(|n| n.score >= limit) {
+                return Some((n.id, format!("reaches a score of {}", n.score)));
+            }
+        }
+        if let Some(want) = v.capitols {
+            for n in &players {
+                let held = self.owned_sectors(n.id).iter()
+                    .filter(|&&(x, y)| self.sct[x][y].designation == Designation::CAPITOL)
+                    .count();
+                if held >= want {
+                    return Some((n.id, format!("holds {} capitols", held)));
+                }
+            }
+        }
+        if v.last_standing {
+            let alive : Vec<&&Nation> = players.iter().filter(|n| !self.owned_sectors(n.id).is_empty()).collect();
+            if alive.len() == 1 && players.len() > 1 {
+                return Some((alive[0].id, "is the last nation standing".to_string()));
+            }
+        }
+        if let Some(turns) = v.turns {
+            if self.turn >= turns {
+                if let Some(n) = best {
+                    return Some((n.id, format!("leads with {} points when time runs out", n.score)));
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::Command;
+    use crate::testing::{land, settle};
+    use crate::PC;
+
+    fn world() -> World {
+        let mut w = land(4, 1);
+        for x in 0..2 {
+            let n = Nation { race: Race::HUMAN, active: PC, ..Default::default() };
+            settle(&mut w, n, &[(x * 2, 0), (x * 2 + 1, 0)], 3000);
+            w.sct[x * 2][0].designation = Designation::CAPITOL;
+        }
+        w
+    }
+
+    #[test]
+    fn score_counts_land_people_and_treasure() {
+        let mut w = world();
+        w.ntn[0].tgold = 50000;
+        w.ntn[0].powers = 0b101;
+        w.update_score();
+        assert_eq!(w.ntn[0].score, 2 + 6 + 5 + 10);
+        assert_eq!(w.ntn[1].score, 2 + 6);
+        assert_eq!(w.score, 31);
+    }
+
+    #[test]
+    fn taking_the_last_capitol_wins_and_freezes() {
+        let mut w = world();
+        w.victory = Victory { capitols: Some(2), last_standing: true, ..Default::default() };
+        w.update();
+        assert_eq!(w.winner, None);
+
+        let (a, b) = (w.ntn[0].id, w.ntn[1].id);
+        w.sct[2][0].owner = a as u8;
+        w.update();
+        assert_eq!(w.winner, Some(a));
+        let turn = w.turn;
+        w.update();
+        assert_eq!(w.turn, turn);
+        assert!(w.execute(b, &Command::Tax { rate: 5 }).is_err());
+    }
+}
@@ -0,0 +1,146 @@
+// Seasons.
+//
+// As in the original, a turn is a season and four of them make a year,
+// starting in spring.  The season scales farm output, spoilage and how hard
+// it is to get through cold or high country.
+
+use std::fmt;
+
+use crate::{Elevation, Vegetation, World};
+
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Season {
+    SPRING,
+    SUMMER,
+    FALL,
+    WINTER,
+}
+
+impl Season {
+    // The season a given turn falls in.
+    pub fn of(turn : i16) -> Season {
+        match turn.rem_euclid(4) {
+            0 => Season::SPRING,
+            1 => Season::SUMMER,
+            2 => Season::FALL,
+            _ => Season::WINTER,
+        }
+    }
+
+    fn to_string(&self) -> String {
+        match self {
+            Season::SPRING      => "SPRING".to_string(),
+            Season::SUMMER      => "SUMMER".to_string(),
+            Season::FALL        => "FALL".to_string(),
+            Season::WINTER      => "WINTER".to_string(),
+        }
+    }
+
+    // Percent of normal food a farm on veg yields this season.  Each row
+    // averages out to about 100 over a year.
+    pub fn farm_pct(&self, veg : &Vegetation) -> i32 {
+        match veg {
+            // short summers, nothing grows under snow
+            Vegetation::TUNDRA | Vegetation::ICE => match self {
+                Season::SPRING  => 50,
+                Season::SUMMER  => 250,
+                Season::FALL    => 100,
+                Season::WINTER  => 0,
+            },
+            // no winter to speak of
+            Vegetation::JUNGLE | Vegetation::SWAMP => 100,
+            // too hot to grow much in summer
+            Vegetation::DESERT => match self {
+                Season::SUMMER  => 60,
+                Season::WINTER  => 120,
+                _               => 110,
+            },
+            _ => match self {
+                Season::SPRING  => 100,
+                Season::SUMMER  => 130,
+                Season::FALL    => 140,
+                Season::WINTER  => 30,
+            },
+        }
+    }
+
+    // Percent of normal movement cost through this kind of country.
+    pub fn move_pct(&self, ele : &Elevation, veg : &Vegetation) -> i32 {
+        let cold = matches!(veg, Vegetation::TUNDRA | Vegetation::ICE);
+        let high = matches!(ele, Elevation::MOUNTAIN | Elevation::PEAK);
+        match self {
+            Season::WINTER if cold || high  => 200,
+            Season::SPRING if high          => 150,     // snowmelt and mud
+            Season::SPRING if cold          => 125,
+            Season::FALL if cold            => 125,
+            _                               => 100,
+        }
+    }
+
+    // Percent of normal food spoilage.
+    pub fn spoil_pct(&self) -> i32 {
+        match self {
+            Season::SPRING      => 100,
+            Season::SUMMER      => 150,
+            Season::FALL        => 100,
+            Season::WINTER      => 50,
+        }
+    }
+}
+
+impl fmt::Display for Season {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_string())
+    }
+}
+
+// Year a given turn falls in, counting from 1.
+pub fn year(turn : i16) -> i16 {
+    turn.div_euclid(4) + 1
+}
+
+impl World {
+    // The season of the current turn.
+    pub fn season(&self) -> Season {
+        Season::of(self.turn)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn turns_walk_through_the_year() {
+        let seasons : Vec<Season> = (0..5).map(Season::of).collect();
+        assert_eq!(seasons, [Season::SPRING, Season::SUMMER, Season::FALL, Season::WINTER, Season::SPRING]);
+        assert_eq!((year(0), year(3), year(4)), (1, 1, 2));
+    }
+
+    #[test]
+    fn farms_follow_the_seasons_of_their_land() {
+        let good : Vec<i32> = (0..4).map(|t| Season::of(t).farm_pct(&Vegetation::GOOD)).collect();
+        assert_eq!(good, [100, 130, 140, 30]);
+        assert_eq!(Season::WINTER.farm_pct(&Vegetation::TUNDRA), 0);
+        assert_eq!(Season::SUMMER.farm_pct(&Vegetation::DESERT), 60);
+        assert_eq!(Season::WINTER.farm_pct(&Vegetation::JUNGLE), 100);
+        for veg in [Vegetation::GOOD, Vegetation::TUNDRA, Vegetation::DESERT, Vegetation::SWAMP] {
+            let year : i32 = (0..4).map(|t| Season::of(t).farm_pct(&veg)).sum();
+            assert!((360..=440).contains(&year), "{} grows {} a year", veg, year);
+        }
+    }
+
+    #[test]
+    fn winter_is_hard_going_and_summer_rots() {
+        assert_eq!(Season::WINTER.move_pct(&Elevation::MOUNTAIN, &Vegetation::GOOD), 200);
+        assert_eq!(Season::WINTER.move_pct(&Elevation::CLEAR, &Vegetation::ICE), 200);
+        assert_eq!(Season::WINTER.move_pct(&Elevation::CLEAR, &Vegetation::GOOD), 100);
+        assert_eq!(Season::SPRING.move_pct(&Elevation::PEAK, &Vegetation::GOOD), 150);
+        assert_eq!(Season::SUMMER.move_pct(&Elevation::MOUNTAIN, &Vegetation::TUNDRA), 100);
+        assert!(Season::SUMMER.spoil_pct() > Season::SPRING.spoil_pct());
+        assert!(Season::WINTER.spoil_pct() < Season::FALL.spoil_pct());
+    }
+}
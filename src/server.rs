@@ -0,0 +1,450 @@
+//! The multiplayer server.
+//!
+//! One World is hosted for everybody.  Players connect over TCP and talk a
+//! line protocol: each request is one line, a verb and its words, and each
+//! reply is either
+//!
+//! ```text
+//! ok N            followed by N lines of answer
+//! err MESSAGE     when the request can't be done
+//! ```
+//!
+//! A new connection is greeted with an ok reply of its own.  Each request is
+//! a job for the Host (see host.rs), so any number of sessions can talk at
+//! once without stepping on each other, and they see each other's changes
+//! in the order they arrived.
+//!
+//! Before logging in a session may only ask
+//!
+//! ```text
+//! login NATION PASSWORD   NATION is a name or id; see auth.rs for lockout
+//! nations                 id, race and name of every nation
+//! turn                    the turn, season and year
+//! help                    this list
+//! quit                    hang up
+//! ```
+//!
+//! and once logged in also
+//!
+//! ```text
+//! logout
+//! status                  the nation's own figures, "name value"
+//! map                     one line per y of what the nation can see;
+//!                         unseen sectors are blank (see vision.rs)
+//! sector X Y              a sector in sight, "name value"
+//! armies                  own armies, "index x y soldiers moves status"
+//! navies                  own fleets, "index x y warships merchant galleys"
+//! enemies                 other armies in sight, "nation index x y soldiers"
+//! news [TURN]             headlines, the last turn's by default
+//! mail                    the mailbox, "id from turn read subject"
+//! read ID                 a letter, which is then marked read
+//! send TO SUBJECT | BODY  a letter to nation TO
+//! delete ID
+//! do ORDER                any order, as Command reads them: do move 0 3 4
+//! update                  god only: run the turn now
+//! ```
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering::SeqCst};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::command::Command;
+use crate::god::GodCommand;
+use crate::host::Handle;
+use crate::season::year;
+use crate::tradegood::tradegood;
+use crate::World;
+
+const MAX_LINE          : u64 = 4096;   // longest request accepted
+const TIMEOUT           : Duration = Duration::from_secs(15 * 60);  // an idle session is dropped
+
+pub struct Server {
+    addr            : SocketAddr,
+    stopping        : Arc<AtomicBool>,
+    listener        : JoinHandle<()>,
+}
+
+// An ok reply with these lines.
+fn ok(lines : Vec<String>) -> Vec<String> {
+    let mut r = vec![format!("ok {}", lines.len())];
+    r.extend(lines);
+    r
+}
+
+/// A nation by id or name.
+pub fn nation_id(w : &World, which : &str) -> Result<i32, String> {
+    which.parse().ok().filter(|&id| w.nation_index(id).is_some())
+        .or_else(|| w.ntn.iter().find(|n| n.name == which).map(|n| n.id))
+        .ok_or(format!("no nation {}", which))
+}
+
+fn num<T : std::str::FromStr>(words : &[&str], i : usize) -> Result<T, String> {
+    let w = words.get(i).ok_or("not enough arguments")?;
+    w.parse().map_err(|_| format!("{} isn't a number", w))
+}
+
+/// Carry out one request for the session logged in as who.  Returns the
+/// answer lines and whether the game changed.
+pub fn request(w : &mut World, who : &mut Option<i32>, line : &str) -> Result<(Vec<String>, bool), String> {
+    let line = line.trim();
+    let (verb, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    let words : Vec<&str> = rest.split_whitespace().collect();
+
+    match verb {
+        "help"      => return Ok((HELP.lines().map(|l| l.to_string()).collect(), false)),
+        "quit"      => return Ok((vec!["goodbye".to_string()], false)),
+        "turn"      => return Ok((vec![format!("{} {} {}", w.turn, w.season(), year(w.turn))], false)),
+        "nations"   => {
+            let v = w.ntn.iter().map(|n| format!("{} {} {}", n.id, n.race.to_char(), n.name)).collect();
+            return Ok((v, false));
+        },
+        "login"     => {
+            let (which, passwd) = rest.trim().split_once(char::is_whitespace).ok_or("login NATION PASSWORD")?;
+            let id = nation_id(w, which)?;
+            if let Err(e) = w.login(id, passwd.trim()) {
+                *who = None;
+                return Err(e);
+            }
+            *who = Some(id);
+            let n = w.nation(id).unwrap();
+            return Ok((vec![format!("welcome {}, it is turn {}", n.name, w.turn)], true));
+        },
+        _           => {},
+    }
+
+    let id = who.ok_or("log in first")?;
+    let idx = w.nation_index(id).ok_or_else(|| { *who = None; "your nation is no more".to_string() })?;
+    let v = match verb {
+        "logout"    => {
+            *who = None;
+            vec!["goodbye".to_string()]
+        },
+        "status"    => {
+            let n = &w.ntn[idx];
+            vec![
+                format!("name {}", n.name),
+                format!("race {}", n.race),
+                format!("capitol {} {}", n.capx, n.capy),
+                format!("score {}", n.score),
+                format!("sectors {}", n.tsctrs),
+                format!("civilians {}", n.tciv),
+                format!("military {}", n.tmil),
+                format!("gold {}", n.tgold),
+                format!("jewels {}", n.jewels),
+                format!("metal {}", n.metals),
+                format!("food {}", n.tfood),
+                format!("tax_rate {}", n.tax_rate),
+                format!("popularity {}", n.popularity),
+                format!("karma {}", n.karma),
+                format!("mail {}", n.mail.iter().filter(|m| !m.read).count()),
+            ]
+        },
+        "map"       => w.view_map(id),
+        "sector"    => {
+            let (x, y) : (usize, usize) = (num(&words, 0)?, num(&words, 1)?);
+            if !w.on_map(x as i32, y as i32) || !w.visible(id)[x][y] {
+                return Err(format!("you can't see {},{}", x, y));
+            }
+            let s = &w.sct[x][y];
+            let mut v = vec![
+                format!("designation {}", s.designation.to_char()),
+                format!("altitude {}", s.altitude.to_char()),
+                format!("vegetation {}", s.vegetation.to_char()),
+                format!("owner {}", s.owner),
+                format!("people {}", s.people),
+            ];
+            // what's inside is only known to the owner
+            if s.owner as i32 == id {
+                v.extend([
+                    format!("fortress {}", s.fortress),
+                    format!("jewels {}", s.jewels),
+                    format!("metal {}", s.metal),
+                    format!("tradegood {}", tradegood(s.tradegood).map_or("none", |g| g.name)),
+                    format!("foodstore {}", s.foodstore),
+                ]);
+            }
+            v
+        },
+        "armies"    => w.ntn[idx].arm.iter().enumerate()
+            .map(|(i, a)| format!("{} {} {} {} {} {}", i, a.xloc, a.yloc, a.sold, a.smove, a.stat))
+            .collect(),
+        "navies"    => w.ntn[idx].nvy.iter().enumerate()
+            .map(|(i, f)| format!("{} {} {} {} {} {}", i, f.xloc, f.yloc, f.warships, f.merchant, f.galleys))
+            .collect(),
+        "enemies"   => w.armies_in_view(id).iter()
+            .map(|(n, i, x, y, sold)| format!("{} {} {} {} {}", n, i, x, y, sold))
+            .collect(),
+        "news"      => {
+            let turn = if words.is_empty() { (w.turn - 1).max(0) } else { num(&words, 0)? };
+            w.news_of(turn).iter().map(|n| n.text.clone()).collect()
+        },
+        "mail"      => w.inbox(id)?.iter()
+            .map(|m| format!("{} {} {} {} {}", m.id, m.from, m.turn, m.read as u8, m.subject))
+            .collect(),
+        "read"      => {
+            let m = w.read_mail(id, num(&words, 0)?)?;
+            let v = m.to_string().lines().map(|l| l.to_string()).collect();
+            return Ok((v, true));
+        },
+        "send"      => {
+            let to = nation_id(w, words.first().ok_or("send TO SUBJECT | BODY")?)?;
+            let text = rest.trim()[words[0].len()..].trim();
+            let (subject, body) = text.split_once('|').unwrap_or((text, ""));
+            let m = w.send_mail(id, to, subject.trim(), body.trim())?;
+            return Ok((vec![format!("sent {}", m)], true));
+        },
+        "delete"    => {
+            w.delete_mail(id, num(&words, 0)?)?;
+            return Ok((vec!["deleted".to_string()], true));
+        },
+        "do"        => {
+            let cmd : Command = rest.parse()?;
+            w.execute(id, &cmd)?;
+            return Ok((vec!["done".to_string()], true));
+        },
+        "update"    => {
+            // refused or not, it is in the audit log now; see session
+            return w.god(id, &GodCommand::Update).map(|d| (vec![d], true));
+        },
+        _           => return Err(format!("no request {:?}; try help", verb)),
+    };
+    Ok((v, false))
+}
+
+const HELP : &str = "login NATION PASSWORD
+logout
+nations
+turn
+status
+map
+sector X Y
+armies
+navies
+enemies
+news [TURN]
+mail
+read ID
+send TO SUBJECT | BODY
+delete ID
+do ORDER
+update
+quit";
+
+// How the nation a login line names stands toward a lockout, if there is
+// such a nation.
+fn tries(w : &World, line : &str) -> Option<(u8, i16)> {
+    let mut words = line.split_whitespace();
+    if words.next() != Some("login") {
+        return None;
+    }
+    let n = w.nation(nation_id(w, words.next()?).ok()?)?;
+    Some((n.failed_logins, n.locked_until))
+}
+
+fn session(stream : TcpStream, host : Handle) -> io::Result<()> {
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    let mut out = stream.try_clone()?;
+    let mut input = BufReader::new(stream);
+    let mut who = None;
+
+    writeln!(out, "ok 1\nrConq {} ready", env!("CARGO_PKG_VERSION"))?;
+    loop {
+        let mut line = String::new();
+        if (&mut input).take(MAX_LINE + 1).read_line(&mut line)? == 0 {
+            return Ok(());
+        }
+        if line.len() as u64 > MAX_LINE {
+            writeln!(out, "err request too long")?;
+            return Ok(());
+        }
+        if line.trim().is_empty() {
+            continue;
+        }
+        let quit = line.trim() == "quit";
+        // a refused update is audited, and a wrong password counts toward
+        // the lockout, so those failures have to be saved too
+        let update = line.trim() == "update";
+        let answer = host.call(move |w| {
+            let mut who = who;
+            let before = tries(w, &line);
+            match request(w, &mut who, &line) {
+                Ok((lines, changed))    => ((who, ok(lines)), changed),
+                Err(e)                  => {
+                    let changed = update || tries(w, &line) != before;
+                    ((who, vec![format!("err {}", e.replace('\n', " "))]), changed)
+                },
+            }
+        });
+        let Some((now, answer)) = answer else { return Ok(()) };
+        who = now;
+        for l in answer {
+            writeln!(out, "{}", l)?;
+        }
+        if quit {
+            return Ok(());
+        }
+    }
+}
+
+impl Server {
+    /// Serve the game host is running on addr ("127.0.0.1:0" picks a free
+    /// port).
+    pub fn start(host : Handle, addr : &str) -> io::Result<Server> {
+        let listener = TcpListener::bind(addr)?;
+        let addr = listener.local_addr()?;
+        let stopping = Arc::new(AtomicBool::new(false));
+
+        let listener = {
+            let stopping = stopping.clone();
+            thread::spawn(move || {
+                for stream in listener.incoming() {
+                    if stopping.load(SeqCst) {
+                        break;
+                    }
+                    let Ok(stream) = stream else { continue };
+                    let host = host.clone();
+                    thread::spawn(move || session(stream, host));
+                }
+            })
+        };
+        Ok(Server { addr, stopping, listener })
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Stop taking connections.  Sessions already open carry on until they
+    /// hang up or the host stops.
+    pub fn stop(self) {
+        self.stopping.store(true, SeqCst);
+        let _ = TcpStream::connect(self.addr);
+        let _ = self.listener.join();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::host::Host;
+    use crate::testing::players;
+
+    struct Client {
+        out             : TcpStream,
+        input           : BufReader<TcpStream>,
+    }
+
+    impl Client {
+        fn connect(addr : SocketAddr) -> Client {
+            let out = TcpStream::connect(addr).unwrap();
+            let mut c = Client { input: BufReader::new(out.try_clone().unwrap()), out };
+            c.answer().unwrap();
+            c
+        }
+
+        fn answer(&mut self) -> Result<Vec<String>, String> {
+            let mut head = String::new();
+            self.input.read_line(&mut head).unwrap();
+            let head = head.trim_end();
+            if let Some(e) = head.strip_prefix("err ") {
+                return Err(e.to_string());
+            }
+            let n : usize = head.strip_prefix("ok ").unwrap().parse().unwrap();
+            Ok((0..n).map(|_| {
+                let mut l = String::new();
+                self.input.read_line(&mut l).unwrap();
+                l.trim_end_matches('\n').to_string()
+            }).collect())
+        }
+
+        fn ask(&mut self, line : &str) -> Result<Vec<String>, String> {
+            writeln!(self.out, "{}", line).unwrap();
+            self.answer()
+        }
+    }
+
+    #[test]
+    fn players_log_in_and_see_only_their_corner() {
+        let (w, _, e, o) = players();
+        let host = Host::start(w, None);
+        let server = Server::start(host.handle(), "127.0.0.1:0").unwrap();
+        let mut elf = Client::connect(server.addr());
+        let mut orc = Client::connect(server.addr());
+
+        assert!(elf.ask("map").unwrap_err().contains("log in"));
+        assert_eq!(elf.ask("nations").unwrap().len(), 3);
+        assert!(elf.ask("login Elfland friend").is_err());
+        elf.ask("login Elfland mellon").unwrap();
+        orc.ask(&format!("login {} grishnakh", o)).unwrap();
+
+        let map = elf.ask("map").unwrap();
+        assert_eq!(map.len(), 5);
+        assert!(map[1].starts_with("-B-") && map[1].ends_with("  "));
+        assert!(elf.ask("sector 18 3").unwrap_err().contains("can't see"));
+        assert!(elf.ask("sector 1 1").unwrap().contains(&"fortress 0".to_string()));
+
+        elf.ask("do draft 1 1 10").unwrap();
+        assert_eq!(elf.ask("armies").unwrap().len(), 2);
+        assert!(elf.ask("do tax 99").is_err());
+        assert!(elf.ask("do fly 1 1").unwrap_err().contains("no order"));
+
+        orc.ask(&format!("send {} Greetings | We come in peace", e)).unwrap();
+        assert!(orc.ask("update").unwrap_err().contains("only god"));
+
+        server.stop();
+        let w = host.stop();
+        assert_eq!(w.nation(e).unwrap().arm.len(), 2);
+        assert_eq!(w.outbox.len(), 1);
+        assert_eq!(w.audit.len(), 1);
+    }
+
+    #[test]
+    fn sessions_are_served_one_request_at_a_time() {
+        let (w, g, e, o) = players();
+        let path = std::env::temp_dir().join(format!("rconq-server-{}.save", std::process::id()));
+        let save = path.to_str().unwrap().to_string();
+        let host = Host::start(w, Some(save.clone()));
+        let server = Server::start(host.handle(), "127.0.0.1:0").unwrap();
+        let addr = server.addr();
+
+        let drafts : Vec<_> = (0..4).map(|_| thread::spawn(move || {
+            let mut c = Client::connect(addr);
+            c.ask("login Elfland mellon").unwrap();
+            (0..5).filter(|_| c.ask("do draft 1 1 1").is_ok()).count()
+        })).collect();
+        let done : usize = drafts.into_iter().map(|t| t.join().unwrap()).sum();
+        assert_eq!(done, 20);
+
+        let mut god = Client::connect(addr);
+        god.ask(&format!("login {} heaven", g)).unwrap();
+        god.ask("update").unwrap();
+        assert_eq!(god.ask("turn").unwrap()[0].split(' ').next(), Some("1"));
+        assert!(god.ask("login Elfland friend").is_err());
+        assert!(god.ask("quit").is_ok());
+        let mut orc = Client::connect(addr);
+        orc.ask("login Orcland grishnakh").unwrap();
+        assert!(orc.ask("update").is_err());
+
+        server.stop();
+        let w = host.stop();
+        assert_eq!(w.nation(e).unwrap().arm.len(), 21);
+        let saved = World::load_file(&save).unwrap();
+        assert_eq!((saved.turn, saved.nation(e).unwrap().arm.len()), (1, 21));
+        assert_eq!(saved.nation(e).unwrap().failed_logins, 1);
+        assert_eq!(saved.audit.last().map(|a| (a.god, a.outcome.is_err())), Some((o, true)));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn only_logins_that_count_toward_a_lockout_need_saving() {
+        let (mut w, _, e, _) = players();
+        assert_eq!(tries(&w, "login Nowhere mellon"), None);
+        assert_eq!(tries(&w, "nations"), None);
+        assert_eq!(tries(&w, "login Elfland friend"), Some((0, 0)));
+        assert!(w.login(e, "friend").is_err());
+        assert_eq!(tries(&w, "login Elfland friend"), Some((1, 0)));
+    }
+}
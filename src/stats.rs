@@ -0,0 +1,247 @@
+// World statistics and the nation rankings.
+//
+// The w_* totals and karma in World, and tsctrs, tciv and tmil in each
+// Nation, are caches of what the nations and the map add up to.  Lots of
+// code changes the things they count without touching them, so once a turn
+// update_stats() adds everything up again.  check_stats() does the same
+// sums without fixing anything, to see how far the caches have wandered.
+
+use std::fmt;
+
+use crate::units::monster;
+use crate::{Elevation, Nation, Race, World};
+
+// Soldier equivalents under arms, monsters counted by strength.
+pub fn military(n : &Nation) -> i32 {
+    let mil : i64 = n.arm.iter()
+        .map(|a| a.sold.max(0) as i64 * monster(a.unittyp).map(|m| m.strength as i64).unwrap_or(1))
+        .sum();
+    mil.min(i32::MAX as i64) as i32
+}
+
+fn clamp32(v : i64) -> i32 {
+    v.clamp(i32::MIN as i64, i32::MAX as i64) as i32
+}
+
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(Default)]
+#[derive(PartialEq)]
+pub struct Stats {
+    pub jewels      : i32,
+    pub gold        : i32,
+    pub food        : i32,
+    pub metal       : i32,
+    pub civ         : i32,
+    pub mil         : i32,
+    pub sctrs       : i32,
+    pub karma       : i16,
+}
+
+// A cached number that doesn't match what it should count.
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(PartialEq)]
+pub struct Drift {
+    pub what        : String,
+    pub cached      : i64,
+    pub actual      : i64,
+}
+
+impl fmt::Display for Drift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} is {} but should be {} ({:+})", self.what, self.cached, self.actual, self.actual - self.cached)
+    }
+}
+
+// One line of the rankings.
+#[derive(Debug)]
+#[derive(Clone)]
+pub struct Rank {
+    pub nation      : i32,      // nation id
+    pub name        : String,
+    pub race        : Race,
+    pub score       : i32,
+    pub sctrs       : i32,
+    pub civ         : i32,
+    pub mil         : i32,
+    pub gold        : i32,
+    pub jewels      : i32,
+    pub metal       : i32,
+    pub food        : i32,
+}
+
+impl World {
+    // Add up the world from scratch.
+    pub fn world_stats(&self) -> Stats {
+        let (mut jewels, mut gold, mut food, mut metal) = (0i64, 0i64, 0i64, 0i64);
+        let (mut civ, mut mil, mut sctrs) = (0i64, 0i64, 0i64);
+
+        for n in &self.ntn {
+            jewels += n.jewels as i64;
+            gold += n.tgold as i64;
+            food += n.tfood as i64;
+            metal += n.metals as i64;
+            mil += military(n) as i64;
+        }
+        for (x, col) in self.sct.iter().enumerate() {
+            for (y, s) in col.iter().enumerate() {
+                jewels += s.jewelstore as i64;
+                food += s.foodstore as i64;
+                metal += s.metalstore as i64;
+                civ += s.people.max(0) as i64;
+                if s.altitude != Elevation::WATER && self.sector_owner(x, y).is_some() {
+                    sctrs += 1;
+                }
+            }
+        }
+
+        Stats {
+            jewels      : clamp32(jewels),
+            gold        : clamp32(gold),
+            food        : clamp32(food),
+            metal       : clamp32(metal),
+            civ         : clamp32(civ),
+            mil         : clamp32(mil),
+            sctrs       : clamp32(sctrs),
+            karma       : self.world_karma(),
+        }
+    }
+
+    // The stats as last cached in World.
+    pub fn cached_stats(&self) -> Stats {
+        Stats {
+            jewels      : self.w_jewels,
+            gold        : self.w_gold,
+            food        : self.w_food,
+            metal       : self.w_metal,
+            civ         : self.w_civ,
+            mil         : self.w_mil,
+            sctrs       : self.w_sctrs,
+            karma       : self.karma,
+        }
+    }
+
+    /// Every cached total, world and nation, that disagrees with the real
+    /// count.  Changes nothing.
+    pub fn check_stats(&self) -> Vec<Drift> {
+        let mut drift = Vec::new();
+        let mut check = |what : String, cached : i64, actual : i64| {
+            if cached != actual {
+                drift.push(Drift { what, cached, actual });
+            }
+        };
+
+        let (c, a) = (self.cached_stats(), self.world_stats());
+        check("world jewels".to_string(), c.jewels as i64, a.jewels as i64);
+        check("world gold".to_string(), c.gold as i64, a.gold as i64);
+        check("world food".to_string(), c.food as i64, a.food as i64);
+        check("world metal".to_string(), c.metal as i64, a.metal as i64);
+        check("world civilians".to_string(), c.civ as i64, a.civ as i64);
+        check("world military".to_string(), c.mil as i64, a.mil as i64);
+        check("world sectors".to_string(), c.sctrs as i64, a.sctrs as i64);
+        check("karma".to_string(), c.karma as i64, a.karma as i64);
+
+        for n in &self.ntn {
+            let owned = self.owned_sectors(n.id);
+            let civ : i64 = owned.iter().map(|&(x, y)| self.sct[x][y].people.max(0) as i64).sum();
+            check(format!("{} sectors", n.name), n.tsctrs as i64, owned.len() as i64);
+            check(format!("{} civilians", n.name), n.tciv as i64, civ);
+            check(format!("{} military", n.name), n.tmil as i64, military(n) as i64);
+        }
+        drift
+    }
+
+    /// Recompute and cache every total.  check_stats() first to see what
+    /// was wrong.
+    pub fn update_stats(&mut self) {
+        let s = self.world_stats();
+        self.w_jewels = s.jewels;
+        self.w_gold = s.gold;
+        self.w_food = s.food;
+        self.w_metal = s.metal;
+        self.w_civ = s.civ;
+        self.w_mil = s.mil;
+        self.w_sctrs = s.sctrs;
+        self.karma = s.karma;
+
+        for idx in 0..self.ntn.len() {
+            let owned = self.owned_sectors(self.ntn[idx].id);
+            let civ : i64 = owned.iter().map(|&(x, y)| self.sct[x][y].people.max(0) as i64).sum();
+            let n = &mut self.ntn[idx];
+            n.tsctrs = owned.len().min(i16::MAX as usize) as i16;
+            n.tciv = clamp32(civ);
+            n.tmil = military(n);
+        }
+    }
+
+    /// Ordinary nations, best score first.
+    pub fn rankings(&self) -> Vec<Rank> {
+        let mut r : Vec<Rank> = self.ntn.iter()
+            .filter(|n| n.race != Race::GOD && !n.race.is_special())
+            .map(|n| Rank {
+                nation      : n.id,
+                name        : n.name.clone(),
+                race        : n.race.clone(),
+                score       : n.score,
+                sctrs       : n.tsctrs as i32,
+                civ         : n.tciv,
+                mil         : n.tmil,
+                gold        : n.tgold,
+                jewels      : n.jewels,
+                metal       : n.metals,
+                food        : n.tfood,
+            })
+            .collect();
+        r.sort_by_key(|k| (-(k.score as i64), k.nation));
+        r
+    }
+
+    /// The rankings as a table, a line per nation under a heading.
+    pub fn ranking_table(&self) -> String {
+        let mut t = format!("{:>3} {:<16} {:<8} {:>7} {:>6} {:>9} {:>8} {:>9} {:>8} {:>8} {:>9}\n",
+            "#", "nation", "race", "score", "sctrs", "civilians", "military", "gold", "jewels", "metal", "food");
+        for (i, r) in self.rankings().iter().enumerate() {
+            t += &format!("{:>3} {:<16} {:<8} {:>7} {:>6} {:>9} {:>8} {:>9} {:>8} {:>8} {:>9}\n",
+                i + 1, r.name, r.race.to_string(), r.score, r.sctrs, r.civ, r.mil, r.gold, r.jewels, r.metal, r.food);
+        }
+        t
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Army, PC};
+
+    #[test]
+    fn stats_find_and_fix_drift() {
+        let mut w = World::new(3, 1, 1);
+        let n = Nation {
+            name        : "Lonely".to_string(),
+            race        : Race::HUMAN,
+            active      : PC,
+            tgold       : 500,
+            arm         : vec![Army { sold: 40, ..Default::default() }],
+            ..Default::default()
+        };
+        for x in 0..2 {
+            w.sct[x][0].altitude = Elevation::CLEAR;
+            w.sct[x][0].owner = n.id as u8;
+            w.sct[x][0].people = 100;
+        }
+        w.sct[2][0].altitude = Elevation::CLEAR;
+        w.sct[2][0].people = 7;
+        w.ntn.push(n);
+
+        let drift = w.check_stats();
+        w.update_stats();
+        assert!(drift.iter().any(|d| d.what == "Lonely sectors" && d.actual == 2));
+        assert_eq!(w.world_stats(), Stats { gold: 500, civ: 207, mil: 40, sctrs: 2, ..Default::default() });
+        assert_eq!(w.check_stats(), Vec::new());
+
+        w.ntn[0].tgold += 1;
+        assert_eq!(w.check_stats(), vec![Drift { what: "world gold".to_string(), cached: 500, actual: 501 }]);
+        assert_eq!(w.rankings()[0].name, "Lonely");
+    }
+}
@@ -0,0 +1,339 @@
+// The trading post.
+//
+// A nation posts something it has up for trade, other nations bid whatever
+// they like for it, and the seller accepts the bid it wants.  Nothing moves
+// until the turn update settles the accepted trades.  Both halves go or
+// neither does: if either side can no longer pay or deliver, everything is
+// put back the way it was and the trade falls through.
+//
+// Land goes only to a nation that owns a sector next to it.  Food, metal
+// and jewels have to be carried, by a merchant fleet or along a road that
+// reaches the other nation.  Gold travels as a letter of credit, and ships
+// sail themselves.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::news::Category;
+use crate::{Designation, Elevation, Sector, World};
+
+const MAX_OFFERS        : usize = 10;   // open offers per nation
+const OFFER_TURNS       : i16 = 10;     // turns before an unaccepted offer lapses
+
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Goods {
+    Food(i32),
+    Gold(i32),
+    Metal(i32),
+    Jewels(i32),
+    Land(usize, usize),     // the sector at x, y
+    Ships(usize, usize),    // the fleet at x, y
+}
+
+impl fmt::Display for Goods {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Goods::Food(n)      => write!(f, "{} food", n),
+            Goods::Gold(n)      => write!(f, "{} gold", n),
+            Goods::Metal(n)     => write!(f, "{} metal", n),
+            Goods::Jewels(n)    => write!(f, "{} jewels", n),
+            Goods::Land(x, y)   => write!(f, "the sector at {},{}", x, y),
+            Goods::Ships(x, y)  => write!(f, "the fleet at {},{}", x, y),
+        }
+    }
+}
+
+// Goods as players type them: "food 100", "land 3 4".
+impl FromStr for Goods {
+    type Err = String;
+
+    fn from_str(s : &str) -> Result<Goods, String> {
+        let w : Vec<&str> = s.split_whitespace().collect();
+        let n = |i : usize| -> Result<i32, String> {
+            w.get(i).and_then(|v| v.parse().ok()).ok_or(format!("{:?} isn't an amount", s))
+        };
+        let at = |i : usize| -> Result<usize, String> {
+            w.get(i).and_then(|v| v.parse().ok()).ok_or(format!("{:?} isn't a place", s))
+        };
+        let (g, len) = match w.first().copied() {
+            Some("food")    => (Goods::Food(n(1)?), 2),
+            Some("gold")    => (Goods::Gold(n(1)?), 2),
+            Some("metal")   => (Goods::Metal(n(1)?), 2),
+            Some("jewels")  => (Goods::Jewels(n(1)?), 2),
+            Some("land")    => (Goods::Land(at(1)?, at(2)?), 3),
+            Some("ships")   => (Goods::Ships(at(1)?, at(2)?), 3),
+            _               => return Err(format!("no goods {:?}", s)),
+        };
+        if w.len() != len {
+            return Err(format!("no goods {:?}", s));
+        }
+        Ok(g)
+    }
+}
+
+#[derive(Debug)]
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Bid {
+    pub bidder      : i32,      // nation id
+    pub goods       : Goods,
+}
+
+#[derive(Debug)]
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Offer {
+    pub id          : u32,
+    pub seller      : i32,      // nation id
+    pub goods       : Goods,
+    pub posted      : i16,      // turn
+    pub bids        : Vec<Bid>,
+    pub accepted    : Option<i32>,  // bidder whose bid the seller took
+}
+
+impl World {
+    // Post goods for trade on behalf of nation idx.  Returns the offer id.
+    pub fn post_offer(&mut self, idx : usize, goods : Goods) -> Result<u32, String> {
+        let seller = self.ntn[idx].id;
+        if self.trades.iter().filter(|o| o.seller == seller).count() >= MAX_OFFERS {
+            return Err(format!("no more than {} offers at a time", MAX_OFFERS));
+        }
+        self.check_goods(idx, &goods)?;
+        self.next_trade += 1;
+        self.report(Category::TRADE, &[seller], None, format!("{} offers {} for trade", self.ntn[idx].name, goods));
+        self.trades.push(Offer { id: self.next_trade, seller, goods, posted: self.turn, bids: Vec::new(), accepted: None });
+        Ok(self.next_trade)
+    }
+
+    // Position of offer id in trades.
+    pub fn offer_index(&self, id : u32) -> Option<usize> {
+        self.trades.iter().position(|o| o.id == id)
+    }
+
+    // Check that nation idx has goods to give.  Says nothing about getting
+    // them there.
+    pub fn check_goods(&self, idx : usize, goods : &Goods) -> Result<(), String> {
+        let n = &self.ntn[idx];
+        let (have, want) = match *goods {
+            Goods::Food(a)      => (n.tfood, a),
+            Goods::Gold(a)      => (n.tgold, a),
+            Goods::Metal(a)     => (n.metals, a),
+            Goods::Jewels(a)    => (n.jewels, a),
+            Goods::Land(x, y)   => {
+                if !self.on_map(x as i32, y as i32) || self.sct[x][y].owner as i32 != n.id {
+                    return Err("you don't own that sector".to_string());
+                }
+                if self.sct[x][y].designation == Designation::CAPITOL {
+                    return Err("the capitol is not for sale".to_string());
+                }
+                return Ok(());
+            },
+            Goods::Ships(x, y)  => {
+                return self.fleet_at(idx, x, y).map(|_| ()).ok_or("you have no fleet there".to_string());
+            },
+        };
+        if want <= 0 {
+            return Err("must trade at least one".to_string());
+        }
+        if have < want {
+            return Err(format!("you only have {}", have));
+        }
+        Ok(())
+    }
+
+    fn fleet_at(&self, idx : usize, x : usize, y : usize) -> Option<usize> {
+        self.ntn[idx].nvy.iter().position(|f| f.xloc as usize == x && f.yloc as usize == y)
+    }
+
+    // Can nation from get bulk goods to nation to?  It needs merchant ships,
+    // or a road of either nation's that touches the other's land.
+    fn linked(&self, from : usize, to : usize) -> bool {
+        if self.ntn[from].nvy.iter().any(|f| f.merchant > 0) {
+            return true;
+        }
+        let (a, b) = (self.ntn[from].id, self.ntn[to].id);
+        for (x, col) in self.sct.iter().enumerate() {
+            for (y, s) in col.iter().enumerate() {
+                if s.designation != Designation::ROAD || (s.owner as i32 != a && s.owner as i32 != b) {
+                    continue;
+                }
+                let other = if s.owner as i32 == a { b } else { a };
+                if self.neighbours(x, y).iter().any(|&(nx, ny)| self.sct[nx][ny].owner as i32 == other) {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    // Hand goods from nation from to nation to, or say why not.  Changes
+    // nothing on failure.
+    fn deliver(&mut self, from : usize, to : usize, goods : &Goods) -> Result<(), String> {
+        self.check_goods(from, goods)?;
+        let (giver, taker) = (self.ntn[from].name.clone(), self.ntn[to].name.clone());
+        match *goods {
+            Goods::Food(_) | Goods::Metal(_) | Goods::Jewels(_) if !self.linked(from, to) =>
+                return Err(format!("{} has no merchant fleet or road to {}", giver, taker)),
+            Goods::Food(a) => {
+                self.ntn[from].tfood -= a;
+                self.ntn[to].tfood += a;
+            },
+            Goods::Gold(a) => {
+                self.ntn[from].tgold -= a;
+                self.ntn[to].tgold += a;
+            },
+            Goods::Metal(a) => {
+                self.ntn[from].metals -= a;
+                self.ntn[to].metals += a;
+            },
+            Goods::Jewels(a) => {
+                self.ntn[from].jewels -= a;
+                self.ntn[to].jewels += a;
+            },
+            Goods::Land(x, y) => {
+                let id = self.ntn[to].id;
+                if !self.neighbours(x, y).iter().any(|&(nx, ny)| self.sct[nx][ny].owner as i32 == id
+                    && self.sct[nx][ny].altitude != Elevation::WATER) {
+                    return Err(format!("{} has no land next to {},{}", taker, x, y));
+                }
+                self.sct[x][y].owner = id as u8;
+                self.ntn[from].tsctrs = (self.ntn[from].tsctrs - 1).max(0);
+                self.ntn[to].tsctrs += 1;
+            },
+            Goods::Ships(x, y) => {
+                let f = self.fleet_at(from, x, y).ok_or("fleet is gone")?;
+                let fleet = self.ntn[from].nvy.remove(f);
+                self.ntn[to].nvy.push(fleet);
+            },
+        }
+        Ok(())
+    }
+
+    // Carry out the trade in offer, both ways, or not at all.
+    pub fn settle(&mut self, offer : &Offer) -> Result<(), String> {
+        let bidder = offer.accepted.ok_or("no bid accepted")?;
+        let bid = offer.bids.iter().find(|b| b.bidder == bidder).ok_or("bid was withdrawn")?;
+        let s = self.nation_index(offer.seller).ok_or("seller is gone")?;
+        let b = self.nation_index(bidder).ok_or("buyer is gone")?;
+
+        // everything either side of the trade can touch
+        let saved_ntn = (self.ntn[s].clone(), self.ntn[b].clone());
+        let saved_sct : Vec<(usize, usize, Sector)> = [&offer.goods, &bid.goods].iter()
+            .filter_map(|g| match **g {
+                Goods::Land(x, y) if self.on_map(x as i32, y as i32) => Some((x, y, self.sct[x][y].clone())),
+                _ => None,
+            })
+            .collect();
+
+        let done = self.deliver(s, b, &offer.goods).and_then(|_| self.deliver(b, s, &bid.goods));
+        if done.is_ok() {
+            self.ntn[s].deeds.trades += 1;
+            self.ntn[b].deeds.trades += 1;
+        } else {
+            self.ntn[s] = saved_ntn.0;
+            self.ntn[b] = saved_ntn.1;
+            for (x, y, sct) in saved_sct.into_iter().rev() {
+                self.sct[x][y] = sct;
+            }
+        }
+        done
+    }
+
+    // Settle every accepted trade and clear out stale offers.
+    pub(crate) fn update_trade(&mut self) {
+        let offers = std::mem::take(&mut self.trades);
+        for offer in offers {
+            if offer.accepted.is_none() {
+                if self.turn - offer.posted < OFFER_TURNS && self.nation(offer.seller).is_some() {
+                    self.trades.push(offer);
+                }
+                continue;
+            }
+            let names = |w : &World, id : i32| w.nation(id).map(|n| n.name.clone()).unwrap_or("nobody".to_string());
+            let bidder = offer.accepted.unwrap_or(0);
+            let (seller, buyer) = (names(self, offer.seller), names(self, bidder));
+            let text = match self.settle(&offer) {
+                Ok(()) => format!("{} trades {} to {}", seller, offer.goods, buyer),
+                Err(e) => format!("trade of {} between {} and {} falls through: {}", offer.goods, seller, buyer, e),
+            };
+            for id in [offer.seller, bidder] {
+                self.system_mail(id, &format!("Trade {}", offer.id), &text);
+            }
+            self.report(Category::TRADE, &[offer.seller, bidder], None, text);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::Command;
+    use crate::testing::{land, settle};
+    use crate::{Nation, Navy, Race, PC};
+
+    // Two nations side by side, west owning columns 0-1 and east 2-3.
+    fn world() -> World {
+        let mut w = land(4, 4);
+        for (name, cols) in [("West", 0..2), ("East", 2..4)] {
+            let n = Nation {
+                name        : name.to_string(),
+                race        : Race::HUMAN,
+                active      : PC,
+                tgold       : 1000,
+                tfood       : 1000,
+                metals      : 100,
+                ..Default::default()
+            };
+            let owned : Vec<(usize, usize)> = cols.flat_map(|x| (0..4).map(move |y| (x, y))).collect();
+            settle(&mut w, n, &owned, 0);
+        }
+        w
+    }
+
+    fn trade(w : &mut World, goods : Goods, price : Goods) -> u32 {
+        let (west, east) = (w.ntn[0].id, w.ntn[1].id);
+        w.execute(west, &Command::Post { goods }).unwrap();
+        let offer = w.next_trade;
+        w.execute(east, &Command::Bid { offer, goods: price }).unwrap();
+        w.execute(west, &Command::Accept { offer, bidder: east }).unwrap();
+        offer
+    }
+
+    #[test]
+    fn accepted_trade_settles_at_the_update() {
+        let mut w = world();
+        w.ntn[0].nvy.push(Navy { merchant: 1, ..Default::default() });
+        trade(&mut w, Goods::Food(500), Goods::Land(2, 1));
+        assert_eq!(w.ntn[0].tfood, 1000);
+
+        w.update_trade();
+        assert!(w.trades.is_empty());
+        assert_eq!((w.ntn[0].tfood, w.ntn[1].tfood), (500, 1500));
+        assert_eq!(w.sct[2][1].owner as i32, w.ntn[0].id);
+    }
+
+    #[test]
+    fn failed_trade_rolls_back() {
+        let mut w = world();
+        // west hands over land, but east has no way to ship the metal back
+        trade(&mut w, Goods::Land(1, 1), Goods::Metal(50));
+        w.update_trade();
+        assert!(w.trades.is_empty());
+        assert_eq!(w.sct[1][1].owner as i32, w.ntn[0].id);
+        assert_eq!((w.ntn[0].metals, w.ntn[1].metals), (100, 100));
+        assert!(w.news.iter().any(|n| n.text.contains("falls through")));
+    }
+
+    #[test]
+    fn roads_carry_goods() {
+        let mut w = world();
+        w.sct[2][0].designation = Designation::ROAD;
+        trade(&mut w, Goods::Gold(100), Goods::Metal(50));
+        w.update_trade();
+        assert_eq!((w.ntn[0].metals, w.ntn[1].metals), (150, 50));
+        assert_eq!((w.ntn[0].tgold, w.ntn[1].tgold), (900, 1100));
+    }
+}
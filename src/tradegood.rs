@@ -0,0 +1,265 @@
+// Exotic trade goods.
+//
+// Sector::tradegood is 0 for nothing special, otherwise one more than an
+// index into TRADEGOODS.  A good only does anything when its sector has the
+// right designation and enough people working it; then the owner gets its
+// bonus every turn it is kept that way.
+//
+// Goods that make something (food, gold, jewels, metal) yield value per 100
+// people in the sector.  The rest add value to an attribute or, for
+// spoilage and the military, take off or add on that many percent.
+
+use crate::{Designation, Elevation, Sector, World};
+
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(PartialEq)]
+pub enum Category {
+    POPULARITY,     // popularity each turn
+    COMMUNICATION,  // communications each turn
+    FOOD,           // food
+    SPOILAGE,       // percent less food rots
+    KNOWLEDGE,      // knowledge each turn
+    WEALTH,         // gold
+    JEWELS,         // jewels
+    METAL,          // metal
+    MILITARY,       // percent attack and defence bonus, see combat.rs
+}
+
+pub struct TradeGood {
+    pub name        : &'static str,
+    pub category    : Category,
+    pub designation : Designation,  // what the sector must be to use it
+    pub people      : i32,          // people it takes
+    pub value       : i32,
+}
+
+pub const TRADEGOODS : [TradeGood; 36] = [
+    TradeGood { name: "furs",        category: Category::POPULARITY,    designation: Designation::TOWN,       people: 500,  value: 1  },
+    TradeGood { name: "wool",        category: Category::POPULARITY,    designation: Designation::TOWN,       people: 500,  value: 1  },
+    TradeGood { name: "beer",        category: Category::POPULARITY,    designation: Designation::TOWN,       people: 500,  value: 2  },
+    TradeGood { name: "cloth",       category: Category::POPULARITY,    designation: Designation::TOWN,       people: 750,  value: 2  },
+    TradeGood { name: "wine",        category: Category::POPULARITY,    designation: Designation::TOWN,       people: 750,  value: 3  },
+    TradeGood { name: "mules",       category: Category::COMMUNICATION, designation: Designation::TOWN,       people: 100,  value: 2  },
+    TradeGood { name: "pigeons",     category: Category::COMMUNICATION, designation: Designation::TOWN,       people: 100,  value: 3  },
+    TradeGood { name: "griffons",    category: Category::COMMUNICATION, designation: Designation::TOWN,       people: 500,  value: 5  },
+    TradeGood { name: "corn",        category: Category::FOOD,          designation: Designation::FARM,       people: 100,  value: 2  },
+    TradeGood { name: "fish",        category: Category::FOOD,          designation: Designation::FARM,       people: 100,  value: 3  },
+    TradeGood { name: "sugar",       category: Category::FOOD,          designation: Designation::FARM,       people: 100,  value: 3  },
+    TradeGood { name: "honey",       category: Category::FOOD,          designation: Designation::FARM,       people: 100,  value: 4  },
+    TradeGood { name: "fruit",       category: Category::FOOD,          designation: Designation::FARM,       people: 100,  value: 4  },
+    TradeGood { name: "rice",        category: Category::FOOD,          designation: Designation::FARM,       people: 100,  value: 5  },
+    TradeGood { name: "wheat",       category: Category::FOOD,          designation: Designation::FARM,       people: 100,  value: 6  },
+    TradeGood { name: "salt",        category: Category::SPOILAGE,      designation: Designation::GRANARY,    people: 100,  value: 20 },
+    TradeGood { name: "pottery",     category: Category::SPOILAGE,      designation: Designation::GRANARY,    people: 100,  value: 15 },
+    TradeGood { name: "papyrus",     category: Category::KNOWLEDGE,     designation: Designation::UNIVERSITY, people: 500,  value: 1  },
+    TradeGood { name: "mathematics", category: Category::KNOWLEDGE,     designation: Designation::UNIVERSITY, people: 1000, value: 2  },
+    TradeGood { name: "library",     category: Category::KNOWLEDGE,     designation: Designation::UNIVERSITY, people: 1000, value: 3  },
+    TradeGood { name: "literature",  category: Category::KNOWLEDGE,     designation: Designation::UNIVERSITY, people: 1000, value: 3  },
+    TradeGood { name: "philosophy",  category: Category::KNOWLEDGE,     designation: Designation::UNIVERSITY, people: 2000, value: 5  },
+    TradeGood { name: "dye",         category: Category::WEALTH,        designation: Designation::TOWN,       people: 500,  value: 2  },
+    TradeGood { name: "spices",      category: Category::WEALTH,        designation: Designation::TOWN,       people: 500,  value: 4  },
+    TradeGood { name: "ivory",       category: Category::WEALTH,        designation: Designation::TOWN,       people: 750,  value: 5  },
+    TradeGood { name: "silk",        category: Category::WEALTH,        designation: Designation::TOWN,       people: 750,  value: 6  },
+    TradeGood { name: "pearls",      category: Category::WEALTH,        designation: Designation::TOWN,       people: 1000, value: 8  },
+    TradeGood { name: "gems",        category: Category::JEWELS,        designation: Designation::GOLDMINE,   people: 100,  value: 3  },
+    TradeGood { name: "rubies",      category: Category::JEWELS,        designation: Designation::GOLDMINE,   people: 100,  value: 5  },
+    TradeGood { name: "diamonds",    category: Category::JEWELS,        designation: Designation::GOLDMINE,   people: 200,  value: 8  },
+    TradeGood { name: "copper",      category: Category::METAL,         designation: Designation::MINE,       people: 100,  value: 2  },
+    TradeGood { name: "iron",        category: Category::METAL,         designation: Designation::MINE,       people: 100,  value: 4  },
+    TradeGood { name: "mithril",     category: Category::METAL,         designation: Designation::MINE,       people: 200,  value: 10 },
+    TradeGood { name: "adamantine",  category: Category::METAL,         designation: Designation::MINE,       people: 300,  value: 15 },
+    TradeGood { name: "horses",      category: Category::MILITARY,      designation: Designation::FARM,       people: 500,  value: 10 },
+    TradeGood { name: "elephants",   category: Category::MILITARY,      designation: Designation::FARM,       people: 1000, value: 15 },
+];
+
+// The good a Sector::tradegood value stands for, if any.
+pub fn tradegood(tg : u8) -> Option<&'static TradeGood> {
+    TRADEGOODS.get((tg as usize).checked_sub(1)?)
+}
+
+// Does a sector of designation des put a good needing need to use?  Cities
+// and capitols do whatever a town does.
+fn works(des : &Designation, need : &Designation) -> bool {
+    des == need || (*need == Designation::TOWN && matches!(des, Designation::CITY | Designation::CAPITOL))
+}
+
+// The good in s, if it is being used.
+pub fn working(s : &Sector) -> Option<&'static TradeGood> {
+    tradegood(s.tradegood).filter(|g| works(&s.designation, &g.designation) && s.people >= g.people)
+}
+
+// Everything a nation gets from its trade goods this turn.
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(Default)]
+pub struct Bonuses {
+    pub popularity      : i32,
+    pub communication   : i32,
+    pub food            : i32,
+    pub spoilage        : i32,
+    pub knowledge       : i32,
+    pub wealth          : i32,
+    pub jewels          : i32,
+    pub metal           : i32,
+    pub military        : i32,
+}
+
+impl World {
+    // Add up what nation id's working trade goods give it.
+    pub(crate) fn goods_bonus(&self, id : i32) -> Bonuses {
+        let mut b = Bonuses::default();
+        for (x, y) in self.owned_sectors(id) {
+            let s = &self.sct[x][y];
+            let Some(g) = working(s) else { continue };
+            let made = s.people.max(0) * g.value / 100;
+            match g.category {
+                Category::POPULARITY    => b.popularity += g.value,
+                Category::COMMUNICATION => b.communication += g.value,
+                Category::FOOD          => b.food += made,
+                Category::SPOILAGE      => b.spoilage += g.value,
+                Category::KNOWLEDGE     => b.knowledge += g.value,
+                Category::WEALTH        => b.wealth += made,
+                Category::JEWELS        => b.jewels += made,
+                Category::METAL         => b.metal += made,
+                Category::MILITARY      => b.military += g.value,
+            }
+        }
+        b
+    }
+
+    // Scatter trade goods over pct percent of the land.  Metal and jewels
+    // go where the ground has them, the rest anywhere.
+    pub fn place_tradegoods(&mut self, pct : i32) {
+        let (metal, jewels) = (category(&Category::METAL), category(&Category::JEWELS));
+        let other : Vec<usize> = (0..TRADEGOODS.len())
+            .filter(|i| !metal.contains(i) && !jewels.contains(i))
+            .collect();
+
+        for x in 0..self.sct.len() {
+            for y in 0..self.sct[x].len() {
+                let s = &self.sct[x][y];
+                if s.altitude == Elevation::WATER || s.designation == Designation::SPECIAL || !self.rng.percent(pct) {
+                    continue;
+                }
+                let from = if s.metal > 0 {
+                    &metal
+                } else if s.jewels > 0 {
+                    &jewels
+                } else {
+                    &other
+                };
+                let i = from[self.rng.below(from.len() as u32) as usize];
+                self.sct[x][y].tradegood = i as u8 + 1;
+            }
+        }
+    }
+}
+
+// Indexes into TRADEGOODS of every good of category cat.
+fn category(cat : &Category) -> Vec<usize> {
+    (0..TRADEGOODS.len()).filter(|&i| TRADEGOODS[i].category == *cat).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{land, settle};
+    use crate::{Army, Nation, Race, PC};
+
+    fn index(name : &str) -> u8 {
+        TRADEGOODS.iter().position(|g| g.name == name).unwrap() as u8 + 1
+    }
+
+    #[test]
+    fn goods_need_the_right_designation_and_people() {
+        let mut w = land(2, 1);
+        let n = Nation { race: Race::HUMAN, active: PC, ..Default::default() };
+        let id = settle(&mut w, n, &[(0, 0), (1, 0)], 1000);
+        w.sct[0][0].tradegood = index("silk");
+        w.sct[0][0].designation = Designation::CITY;
+        w.sct[1][0].tradegood = index("horses");
+        w.sct[1][0].designation = Designation::MINE;
+
+        let b = w.goods_bonus(id);
+        assert_eq!((b.wealth, b.military), (60, 0));
+
+        w.sct[1][0].designation = Designation::FARM;
+        w.sct[0][0].people = 100;
+        let b = w.goods_bonus(id);
+        assert_eq!((b.wealth, b.military), (0, 10));
+    }
+
+    #[test]
+    fn goods_go_where_the_ground_suits_them() {
+        let mut w = land(4, 4);
+        w.place_tradegoods(0);
+        assert!(w.sct.iter().flatten().all(|s| s.tradegood == 0));
+
+        w.sct[0][0].altitude = Elevation::WATER;
+        w.sct[1][0].designation = Designation::SPECIAL;
+        w.sct[2][0].metal = 5;
+        w.sct[3][0].jewels = 5;
+        w.place_tradegoods(100);
+        assert_eq!((w.sct[0][0].tradegood, w.sct[1][0].tradegood), (0, 0));
+        assert_eq!(tradegood(w.sct[2][0].tradegood).unwrap().category, Category::METAL);
+        assert_eq!(tradegood(w.sct[3][0].tradegood).unwrap().category, Category::JEWELS);
+        for s in w.sct.iter().flatten().filter(|s| s.tradegood != 0 && s.metal == 0 && s.jewels == 0) {
+            let g = tradegood(s.tradegood).unwrap();
+            assert!(g.category != Category::METAL && g.category != Category::JEWELS);
+        }
+    }
+
+    #[test]
+    fn working_goods_pay_out_every_turn() {
+        let mut w = land(5, 1);
+        let n = Nation { race: Race::HUMAN, active: PC, ..Default::default() };
+        settle(&mut w, n, &[(0, 0), (1, 0), (2, 0), (3, 0), (4, 0)], 1000);
+        for (x, good, des) in [(0, "silk", Designation::TOWN), (1, "rubies", Designation::GOLDMINE),
+                               (2, "iron", Designation::MINE), (3, "papyrus", Designation::UNIVERSITY),
+                               (4, "wheat", Designation::FARM)] {
+            w.sct[x][0].tradegood = index(good);
+            w.sct[x][0].designation = des;
+        }
+        let grown = w.farm_output(4, 0, 0);
+
+        w.update_production();
+        let n = &w.ntn[0];
+        assert_eq!((n.tgold, n.jewels, n.metals, n.knowledge), (60, 50, 40, 1));
+        assert_eq!(n.tfood, grown + 60);
+    }
+
+    #[test]
+    fn military_goods_fight_on_both_sides() {
+        let mut w = land(8, 3);
+        let a = settle(&mut w, Nation { race: Race::HUMAN, active: PC, ..Default::default() },
+            &(0..8).map(|x| (x, 0)).collect::<Vec<_>>(), 1000);
+        let d = settle(&mut w, Nation { race: Race::HUMAN, active: PC, ..Default::default() },
+            &(0..8).map(|x| (x, 1)).chain([(0, 2)]).collect::<Vec<_>>(), 1000);
+        w.ntn[1].arm.push(Army { xloc: 0, yloc: 2, sold: 100, ..Default::default() });
+        for x in 0..8 {
+            w.sct[x][0].tradegood = index("elephants");
+            w.sct[x][1].tradegood = index("elephants");
+        }
+
+        // a thousand even fights, then with the attacker's elephants
+        // working, then with the defender's too
+        let wins = |w : &mut World, armed : &[usize]| {
+            for x in 0..8 {
+                for y in 0..2 {
+                    w.sct[x][y].designation = if armed.contains(&y) { Designation::FARM } else { Designation::NONE };
+                }
+            }
+            (0..1000).filter(|_| {
+                w.ntn[1].arm[0].sold = 100;
+                w.attack(a, 100, 0, 2).won
+            }).count() as i32
+        };
+        let even = wins(&mut w, &[]);
+        let attacking = wins(&mut w, &[0]);
+        let both = wins(&mut w, &[0, 1]);
+        assert_eq!(w.goods_bonus(d).military, 120);
+        assert!(attacking > even + 100, "{} {} {}", even, attacking, both);
+        assert!(both < attacking - 100, "{} {} {}", even, attacking, both);
+    }
+}
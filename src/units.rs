@@ -0,0 +1,227 @@
+// Monster units and their lairs.
+//
+// Army::unittyp below MINMONSTER is ordinary troops, where sold counts
+// soldiers; MERCENARY troops bring their own bonuses.  From MINMONSTER up
+// it indexes MONSTERS and sold counts monsters, each worth strength
+// soldiers in a fight.  Monsters are summoned with spell points and cost
+// jewels and spell points every turn to keep; a nation that can't pay
+// watches them wander off.
+//
+// Lairs are SPECIAL sectors where monsters sit on a hoard.  Beat the
+// guardians and the hoard is yours.
+
+use crate::news::Category;
+use crate::{Army, Designation, World};
+
+pub const MERCENARY     : u8 = 1;       // hired soldiers, see mercenary.rs
+pub const MINMONSTER    : u8 = 100;     // unittyp of the first monster
+
+const FEAR_BONUS        : i64 = 25;     // percent, the enemy breaks and runs
+
+pub struct Monster {
+    pub name        : &'static str,
+    pub strength    : i32,      // soldiers each is worth
+    pub attack      : i16,      // attack bonus, percent
+    pub defense     : i16,      // defense bonus, percent
+    pub moves       : u8,       // movement per turn
+    pub summon      : i16,      // spell points to summon one
+    pub jewels      : i32,      // jewels per turn to keep one
+    pub spells      : i16,      // spell points per turn to keep one
+    pub flight      : bool,     // flies over any land for one move a sector
+    pub fear        : bool,     // enemies fight at a disadvantage
+}
+
+pub const MONSTERS : [Monster; 16] = [
+    Monster { name: "spirit",     strength: 50,   attack: 0,   defense: 0,   moves: 10, summon: 2,  jewels: 100,  spells: 1, flight: true,  fear: false },
+    Monster { name: "assassin",   strength: 50,   attack: 50,  defense: 0,   moves: 10, summon: 3,  jewels: 200,  spells: 1, flight: false, fear: false },
+    Monster { name: "djinni",     strength: 50,   attack: 50,  defense: 50,  moves: 10, summon: 4,  jewels: 200,  spells: 1, flight: true,  fear: false },
+    Monster { name: "gargoyle",   strength: 75,   attack: 20,  defense: 20,  moves: 10, summon: 4,  jewels: 150,  spells: 1, flight: true,  fear: false },
+    Monster { name: "wraith",     strength: 75,   attack: 10,  defense: 10,  moves: 10, summon: 5,  jewels: 200,  spells: 1, flight: false, fear: true  },
+    Monster { name: "hero",       strength: 100,  attack: 25,  defense: 25,  moves: 8,  summon: 5,  jewels: 250,  spells: 1, flight: false, fear: false },
+    Monster { name: "centaur",    strength: 100,  attack: 10,  defense: 10,  moves: 15, summon: 5,  jewels: 200,  spells: 1, flight: false, fear: false },
+    Monster { name: "giant",      strength: 150,  attack: 0,   defense: 0,   moves: 8,  summon: 6,  jewels: 300,  spells: 1, flight: false, fear: true  },
+    Monster { name: "superhero",  strength: 150,  attack: 50,  defense: 50,  moves: 8,  summon: 8,  jewels: 500,  spells: 2, flight: false, fear: false },
+    Monster { name: "mummy",      strength: 150,  attack: 0,   defense: 50,  moves: 6,  summon: 7,  jewels: 300,  spells: 1, flight: false, fear: true  },
+    Monster { name: "elemental",  strength: 175,  attack: 25,  defense: 25,  moves: 8,  summon: 8,  jewels: 400,  spells: 2, flight: false, fear: false },
+    Monster { name: "minotaur",   strength: 150,  attack: 50,  defense: 25,  moves: 8,  summon: 8,  jewels: 400,  spells: 2, flight: false, fear: true  },
+    Monster { name: "wyvern",     strength: 200,  attack: 25,  defense: 0,   moves: 12, summon: 9,  jewels: 500,  spells: 2, flight: true,  fear: true  },
+    Monster { name: "demon",      strength: 500,  attack: 50,  defense: 50,  moves: 8,  summon: 15, jewels: 1000, spells: 3, flight: false, fear: true  },
+    Monster { name: "balrog",     strength: 500,  attack: 75,  defense: 75,  moves: 10, summon: 18, jewels: 1200, spells: 4, flight: true,  fear: true  },
+    Monster { name: "dragon",     strength: 1000, attack: 100, defense: 100, moves: 12, summon: 25, jewels: 2000, spells: 5, flight: true,  fear: true  },
+];
+
+// The monster a unittyp stands for, if it is one.
+pub fn monster(unittyp : u8) -> Option<&'static Monster> {
+    MONSTERS.get(unittyp.checked_sub(MINMONSTER)? as usize)
+}
+
+#[derive(Debug)]
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Lair {
+    pub x           : usize,
+    pub y           : usize,
+    pub unittyp     : u8,       // what guards it
+    pub count       : i32,      // how many of them
+    pub jewels      : i32,      // hoard
+    pub gold        : i32,      // hoard
+}
+
+impl Lair {
+    // Guardian strength in soldiers.
+    pub fn guard(&self) -> i32 {
+        match monster(self.unittyp) {
+            Some(m) => self.count * m.strength * (100 + m.defense as i32) / 100,
+            None => self.count,
+        }
+    }
+}
+
+impl Army {
+    // Soldier equivalents this army throws into an attack, with the nation's
+    // attack bonus.
+    pub fn force(&self, aplus : i16) -> i32 {
+        let aplus = if self.unittyp == MERCENARY { self.aplus } else { aplus };
+        let (each, bonus, fear) = match monster(self.unittyp) {
+            Some(m) => (m.strength as i64, m.attack as i64, m.fear),
+            None => (1, 0, false),
+        };
+        let mut f = self.sold.max(0) as i64 * each * (100 + aplus as i64 + bonus) / 100;
+        if fear {
+            f = f * (100 + FEAR_BONUS) / 100;
+        }
+        f as i32
+    }
+
+    // Soldier equivalents this army holds a sector with, with the nation's
+    // defense bonus plus any extra for digging in.
+    pub fn guard(&self, dplus : i16, dug_in : i16) -> i32 {
+        let dplus = if self.unittyp == MERCENARY { self.dplus } else { dplus };
+        let (each, bonus, fear) = match monster(self.unittyp) {
+            Some(m) => (m.strength as i64, m.defense as i64, m.fear),
+            None => (1, 0, false),
+        };
+        let mut g = self.sold.max(0) as i64 * each * (100 + dplus as i64 + dug_in as i64 + bonus) / 100;
+        if fear {
+            g = g * (100 + FEAR_BONUS) / 100;
+        }
+        g as i32
+    }
+
+    // Take loss soldier equivalents out of an army that fought with
+    // strength total.  Monsters die whole, when at least half of one is lost.
+    pub fn take_losses(&mut self, strength : i32, loss : i32) -> i32 {
+        if strength <= 0 {
+            return 0;
+        }
+        let lost = ((self.sold as i64 * loss as i64 * 2 + strength as i64) / (2 * strength as i64)) as i32;
+        let lost = lost.min(self.sold);
+        self.sold -= lost;
+        lost
+    }
+
+    pub fn flies(&self) -> bool {
+        monster(self.unittyp).is_some_and(|m| m.flight)
+    }
+}
+
+impl World {
+    // The lair at (x, y), if there is one.
+    pub fn lair_at(&self, x : usize, y : usize) -> Option<usize> {
+        self.lairs.iter().position(|l| l.x == x && l.y == y)
+    }
+
+    // Hand the hoard of lair l to nation idx and clear it out.
+    pub fn loot_lair(&mut self, l : usize, idx : usize) {
+        let lair = self.lairs.remove(l);
+        let n = &mut self.ntn[idx];
+        n.jewels += lair.jewels;
+        n.tgold += lair.gold;
+        let text = format!("{} clears the lair at {},{} and takes {} jewels and {} gold",
+            n.name, lair.x, lair.y, lair.jewels, lair.gold);
+        let id = n.id;
+        self.sct[lair.x][lair.y].designation = Designation::NONE;
+        self.report(Category::MONSTERS, &[id], Some((lair.x, lair.y)), text);
+    }
+
+    // Pay the jewels and spell points every monster costs.  Those that go
+    // unpaid leave.
+    pub(crate) fn update_monster_units(&mut self) {
+        let mut news = Vec::new();
+        for n in self.ntn.iter_mut() {
+            let mut deserted = 0;
+            for a in n.arm.iter_mut() {
+                let Some(m) = monster(a.unittyp) else { continue };
+                while a.sold > 0 && (n.jewels < a.sold * m.jewels || (n.spellpts as i32) < a.sold * m.spells as i32) {
+                    a.sold -= 1;
+                    deserted += 1;
+                }
+                n.jewels -= a.sold * m.jewels;
+                n.spellpts -= (a.sold * m.spells as i32) as i16;
+            }
+            n.arm.retain(|a| a.sold > 0);
+            if deserted > 0 {
+                news.push((n.id, format!("{} monsters desert {} for want of jewels or magic", deserted, n.name)));
+            }
+        }
+        for (id, text) in news {
+            self.report(Category::MONSTERS, &[id], None, text);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::worldgen::GenOptions;
+    use crate::{Elevation, Nation, Race, PC};
+
+    // unittyp of the monster with the given name.
+    fn monster_type(name : &str) -> Option<u8> {
+        MONSTERS.iter().position(|m| m.name == name).map(|i| MINMONSTER + i as u8)
+    }
+
+    #[test]
+    fn monsters_fight_harder_than_their_numbers() {
+        let wyvern = Army { unittyp: monster_type("wyvern").unwrap(), sold: 2, ..Default::default() };
+        let men = Army { sold: 400, ..Default::default() };
+        assert!(wyvern.force(0) > men.force(0));
+        assert!(wyvern.flies() && !men.flies());
+    }
+
+    #[test]
+    fn beating_the_guardians_pays_the_hoard() {
+        let mut w = World::generate(16, 16, 7, &GenOptions { pwater: 0, lairs: 1, tradegoods: 0 });
+        let (x, y) = (w.lairs[0].x, w.lairs[0].y);
+        let (jewels, gold) = (w.lairs[0].jewels, w.lairs[0].gold);
+        assert_eq!(w.sct[x][y].designation, Designation::SPECIAL);
+        assert_ne!(w.sct[x][y].altitude, Elevation::WATER);
+
+        let hero = Nation { race: Race::HUMAN, active: PC, ..Default::default() };
+        let id = hero.id;
+        w.ntn.push(hero);
+        let b = w.attack(id, 1_000_000, x, y);
+        assert!(b.won);
+        assert!(w.lairs.is_empty());
+        assert_eq!(w.sct[x][y].designation, Designation::NONE);
+        assert_eq!(w.ntn[0].jewels, jewels);
+        assert_eq!(w.ntn[0].tgold, gold);
+    }
+
+    #[test]
+    fn monsters_leave_when_jewels_or_spell_points_run_short() {
+        let mut w = World::new(1, 1, 1);
+        let giant = monster_type("giant").unwrap();
+        let m = monster(giant).unwrap();
+        let mut n = Nation { race: Race::HUMAN, active: PC, jewels: 10 * m.jewels, spellpts: 3 * m.spells, ..Default::default() };
+        n.arm.push(Army { unittyp: giant, sold: 5, ..Default::default() });
+        w.ntn.push(n);
+
+        w.update_monster_units();
+        assert_eq!(w.ntn[0].arm[0].sold, 3);
+        assert_eq!((w.ntn[0].jewels, w.ntn[0].spellpts), (7 * m.jewels, 0));
+        w.update_monster_units();
+        assert!(w.ntn[0].arm.is_empty());
+        assert_eq!(w.ntn[0].jewels, 7 * m.jewels);
+    }
+}
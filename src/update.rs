@@ -0,0 +1,100 @@
+// The turn update, what conqrun -x did in the original.
+//
+// Phases run in a fixed order so that a given seed always plays out the
+// same way.  Each phase lives in its own module; this just sequences them.
+
+use crate::economy::Budget;
+use crate::news::Category;
+use crate::season::year;
+use crate::units::monster;
+use crate::World;
+
+impl World {
+    /// Run one turn for the whole world.  Returns each nation's budget; the
+    /// rest of what happened is in the news for the turn.  Once the game is
+    /// won nothing moves.
+    pub fn update(&mut self) -> Vec<Budget> {
+        if self.winner.is_some() {
+            return Vec::new();
+        }
+        self.deliver_mail();
+
+        self.update_monsters();
+        self.update_npcs();
+        self.update_trade();
+        self.update_events();
+        self.update_production();
+        let budgets = self.update_economy();
+        self.update_monster_units();
+        self.update_mercenaries();
+        self.update_karma();
+        self.update_politics();
+
+        for r in self.update_revolts() {
+            let parent = self.nation(r.parent).map(|n| n.name.clone()).unwrap_or_default();
+            self.report(Category::REVOLT, &[r.parent, r.rebel], None, format!(
+                "civil war in {}; {} sectors and {} armies break away", parent, r.sectors.len(), r.armies));
+        }
+
+        // armies get their legs back for the coming turn
+        for n in self.ntn.iter_mut() {
+            for a in n.arm.iter_mut() {
+                a.smove = monster(a.unittyp).map(|m| m.moves).unwrap_or(n.maxmove);
+            }
+        }
+
+        self.turn += 1;
+        self.update_stats();
+        self.update_score();
+        if let Some((id, how)) = self.check_victory() {
+            let name = self.nation(id).map(|n| n.name.clone()).unwrap_or_default();
+            self.report(Category::WORLD, &[id], None, format!("{} {} and wins the game", name, how));
+            self.winner = Some(id);
+            return budgets;
+        }
+        self.report(Category::WORLD, &[], None, format!("{} of year {} begins", self.season(), year(self.turn)));
+        budgets
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::karma::KARMA_MAX;
+    use crate::testing::{land, settle};
+    use crate::{Army, Designation, Nation, Race, Season, PC};
+
+    #[test]
+    fn a_turn_is_played_in_its_season_then_moves_on() {
+        let mut w = land(2, 1);
+        w.turn = 3;
+        w.karma = -KARMA_MAX;   // no blessings to muddle the harvest
+        let n = Nation {
+            race            : Race::HUMAN,
+            active          : PC,
+            popularity      : 100,
+            communications  : 100,
+            maxmove         : 8,
+            arm             : vec![Army { sold: 10, smove: 0, ..Default::default() }],
+            ..Default::default()
+        };
+        let id = settle(&mut w, n, &[(0, 0), (1, 0)], 1000);
+        w.sct[0][0].designation = Designation::CAPITOL;
+        w.sct[1][0].designation = Designation::FARM;
+        let pen = settle(&mut w, Nation { race: Race::HUMAN, active: PC, ..Default::default() }, &[], 0);
+        w.send_mail(pen, id, "hello", "").unwrap();
+        let winter = w.farm_output(1, 0, 0);
+        assert!(winter > 0 && winter < w.sct[1][0].people * 9 / 10);
+
+        w.update();
+        // mail is out first, the harvest is the winter's, and only then
+        // does the turn move on to a spring with armies rested and scores
+        // counted
+        assert_eq!(w.inbox(id).unwrap().len(), 1);
+        assert_eq!(w.ntn[0].tfood, winter);
+        assert_eq!((w.turn, w.season()), (4, Season::SPRING));
+        assert_eq!(w.ntn[0].arm[0].smove, 8);
+        assert_eq!(w.ntn[0].score, 2 + 2);
+        let last = w.news.last().unwrap();
+        assert_eq!((last.turn, last.text.as_str()), (4, "SPRING of year 2 begins"));
+    }
+}
@@ -0,0 +1,113 @@
+// What a nation can see.
+//
+// The original showed a player only the land near their own: sectors they
+// held, and the country around their armies and fleets.  Everything else
+// on their map was blank.  Front ends get their map through here so nobody
+// learns more than their scouts could tell them.  God sees everything.
+
+use crate::{Race, World};
+
+const VIEW_LAND         : i32 = 2;      // sectors seen around owned land
+const VIEW_ARMY         : i32 = 2;      // around an army
+const VIEW_NAVY         : i32 = 3;      // around a fleet, from the masthead
+
+pub const UNSEEN        : char = ' ';   // on a nation's map
+
+impl World {
+    /// Which sectors nation id can see, indexed `[x][y]` like sct.  Nobody
+    /// sees anything if the nation doesn't exist.
+    pub fn visible(&self, id : i32) -> Vec<Vec<bool>> {
+        let (mx, my) = (self.mapx as usize, self.mapy as usize);
+        let Some(n) = self.nation(id) else { return vec![vec![false; my]; mx] };
+        if n.race == Race::GOD {
+            return vec![vec![true; my]; mx];
+        }
+
+        let mut seen = vec![vec![false; my]; mx];
+        let mut look = |x : i32, y : i32, range : i32| {
+            for sx in (x - range).max(0)..=(x + range).min(mx as i32 - 1) {
+                for sy in (y - range).max(0)..=(y + range).min(my as i32 - 1) {
+                    seen[sx as usize][sy as usize] = true;
+                }
+            }
+        };
+        for (x, y) in self.owned_sectors(id) {
+            look(x as i32, y as i32, VIEW_LAND);
+        }
+        for a in n.arm.iter().filter(|a| a.sold > 0) {
+            look(a.xloc as i32, a.yloc as i32, VIEW_ARMY);
+        }
+        for f in &n.nvy {
+            look(f.xloc as i32, f.yloc as i32, VIEW_NAVY);
+        }
+        seen
+    }
+
+    /// Nation id's map, a row of characters for each y.  Land held by a
+    /// nation shows its mark, other sectors their terrain, and what can't be
+    /// seen is UNSEEN.
+    pub fn view_map(&self, id : i32) -> Vec<String> {
+        let seen = self.visible(id);
+        (0..self.mapy as usize).map(|y| {
+            (0..self.mapx as usize).map(|x| {
+                let s = &self.sct[x][y];
+                match (seen[x][y], self.sector_owner(x, y)) {
+                    (false, _)      => UNSEEN,
+                    (true, Some(o)) => self.ntn[o].mark,
+                    (true, None)    => s.altitude.to_char(),
+                }
+            }).collect()
+        }).collect()
+    }
+
+    /// Every army nation id can see that isn't its own: (nation, army
+    /// index, x, y, soldiers).
+    pub fn armies_in_view(&self, id : i32) -> Vec<(i32, usize, usize, usize, i32)> {
+        let seen = self.visible(id);
+        let mut v = Vec::new();
+        for n in self.ntn.iter().filter(|n| n.id != id) {
+            for (i, a) in n.arm.iter().enumerate() {
+                let (x, y) = (a.xloc as usize, a.yloc as usize);
+                if a.sold > 0 && self.on_map(x as i32, y as i32) && seen[x][y] {
+                    v.push((n.id, i, x, y, a.sold));
+                }
+            }
+        }
+        v
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::land;
+    use crate::{Army, Nation, PC};
+
+    #[test]
+    fn nations_see_only_near_their_land_and_armies() {
+        let mut w = land(12, 3);
+        let elf = Nation { race: Race::ELF, active: PC, mark: 'E', ..Default::default() };
+        let orc = Nation {
+            race        : Race::ORC,
+            active      : PC,
+            mark        : 'O',
+            arm         : vec![Army { xloc: 6, yloc: 1, sold: 50, ..Default::default() }],
+            ..Default::default()
+        };
+        let god = Nation { race: Race::GOD, ..Default::default() };
+        let (e, o, g) = (elf.id, orc.id, god.id);
+        w.ntn.extend([elf, orc, god]);
+        w.sct[0][0].owner = e as u8;
+        w.sct[11][2].owner = o as u8;
+
+        let map = w.view_map(e);
+        assert_eq!(map[0], "E-- ".to_string() + &" ".repeat(8));
+        assert!(w.armies_in_view(e).is_empty());
+
+        w.ntn[0].arm.push(Army { xloc: 4, yloc: 1, sold: 10, ..Default::default() });
+        assert_eq!(w.armies_in_view(e), vec![(o, 0, 6, 1, 50)]);
+        assert!(!w.visible(e)[11][2]);
+        assert!(!w.visible(o)[0][0] && w.visible(g)[0][0]);
+        assert_eq!(w.view_map(g)[2].chars().last(), Some('O'));
+    }
+}
@@ -0,0 +1,604 @@
+//! The game over HTTP, for web pages and other graphical clients.
+//!
+//! A small HTTP/1.1 server that answers in JSON, one request to a
+//! connection.  Like the line protocol in server.rs it runs every request
+//! as a job for the Host, and shows a nation only what it could see:
+//!
+//! ```text
+//! POST    /api/login          nation, password: {"token", "nation"}
+//! POST    /api/logout
+//! GET     /api/nation         status of the nation logged in
+//! GET     /api/map            its map, rows as from view_map
+//! GET     /api/tiles          sectors in view; x, y, w, h for a window
+//! GET     /api/armies         its armies, and others in view
+//! GET     /api/navies         its fleets, and others in view
+//! GET     /api/news           news of turn, the last one by default
+//! GET     /api/mail           the mailbox
+//! GET     /api/mail/ID        a letter, which is then marked read
+//! POST    /api/mail           to, subject, body
+//! DELETE  /api/mail/ID
+//! POST    /api/orders         order, as for "do" in server.rs
+//! POST    /api/update         run the turn; god only
+//! GET     /api/events         a WebSocket of events as they happen
+//! ```
+//!
+//! Fields are urlencoded, in the query or the body.  Everything but login
+//! needs "Authorization: Bearer TOKEN", or token=TOKEN in the query since
+//! that is all a browser can do when opening a WebSocket.  Errors are
+//! {"error": MSG} with a 4xx status.
+//!
+//! The events socket sends {"event": "turn", "turn": N} to everyone when
+//! an update has run, and {"event": "mail", "id", "from", "subject"} to a
+//! nation when a letter reaches its mailbox.  Tokens last until logout or
+//! the server stops.
+
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering::SeqCst};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use tungstenite::handshake::server::{ErrorResponse, Request as WsRequest, Response};
+use tungstenite::http::StatusCode;
+use tungstenite::Message;
+
+use crate::auth::new_token;
+use crate::command::Command;
+use crate::god::GodCommand;
+use crate::host::{Event, Handle};
+use crate::json;
+use crate::server::nation_id;
+use crate::World;
+
+const MAX_HEAD          : u64 = 8192;   // request line and headers
+const MAX_BODY          : usize = 65536;
+const TIMEOUT           : Duration = Duration::from_secs(30);
+const EVENTS            : &[u8] = b"GET /api/events";
+
+// Session tokens and the nation each is logged in as.
+type Tokens = Arc<Mutex<HashMap<String, i32>>>;
+
+// An HTTP status and a JSON body.
+type Reply = (u16, String);
+
+#[derive(Clone)]
+struct Request {
+    method          : String,
+    path            : String,
+    fields          : HashMap<String, String>,  // from the query and the body
+    token           : Option<String>,
+}
+
+pub struct Web {
+    addr            : SocketAddr,
+    stopping        : Arc<AtomicBool>,
+    listener        : JoinHandle<()>,
+}
+
+fn error(status : u16, msg : &str) -> Reply {
+    (status, json::object(&[("error", json::string(msg))]))
+}
+
+fn reason(status : u16) -> &'static str {
+    match status {
+        200     => "OK",
+        400     => "Bad Request",
+        401     => "Unauthorized",
+        403     => "Forbidden",
+        404     => "Not Found",
+        413     => "Payload Too Large",
+        _       => "Service Unavailable",
+    }
+}
+
+// Undo urlencoding.
+fn decode(s : &str) -> String {
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < s.len() {
+        let b = s.as_bytes()[i];
+        match s.get(i + 1..i + 3).and_then(|h| u8::from_str_radix(h, 16).ok()) {
+            Some(c) if b == b'%'    => { out.push(c); i += 2 },
+            _ if b == b'+'          => out.push(b' '),
+            _                       => out.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+fn parse_fields(s : &str, fields : &mut HashMap<String, String>) {
+    for pair in s.split('&').filter(|p| !p.is_empty()) {
+        let (k, v) = pair.split_once('=').unwrap_or((pair, ""));
+        fields.insert(decode(k), decode(v));
+    }
+}
+
+fn read_request(stream : &TcpStream) -> Result<Request, Reply> {
+    let bad = |_| error(400, "bad request");
+    let mut input = BufReader::new(stream.take(MAX_HEAD));
+    let mut line = String::new();
+    input.read_line(&mut line).map_err(bad)?;
+    let mut words = line.split_whitespace();
+    let (Some(method), Some(target)) = (words.next(), words.next()) else { return Err(error(400, "bad request")) };
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let mut r = Request { method: method.to_string(), path: path.to_string(), fields: HashMap::new(), token: None };
+    parse_fields(query, &mut r.fields);
+
+    let mut length = 0;
+    loop {
+        line.clear();
+        if input.read_line(&mut line).map_err(bad)? == 0 || !line.ends_with('\n') {
+            return Err(error(413, "headers too long"));
+        }
+        let Some((name, value)) = line.split_once(':') else { break };
+        let value = value.trim();
+        match name.to_ascii_lowercase().as_str() {
+            "content-length"    => length = value.parse().map_err(|_| error(400, "bad content-length"))?,
+            "authorization"     => r.token = value.strip_prefix("Bearer ").map(|t| t.trim().to_string()),
+            _                   => {},
+        }
+    }
+    if length > MAX_BODY {
+        return Err(error(413, "request too large"));
+    }
+
+    // some of the body may already be in input's buffer
+    input.get_mut().set_limit(length as u64);
+    let mut body = vec![0; length];
+    input.read_exact(&mut body).map_err(bad)?;
+    parse_fields(&String::from_utf8_lossy(&body), &mut r.fields);
+    if r.token.is_none() {
+        r.token = r.fields.get("token").cloned();
+    }
+    Ok(r)
+}
+
+fn write_reply(mut stream : &TcpStream, (status, body) : Reply) -> io::Result<()> {
+    write!(stream, "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status, reason(status), body.len(), body)?;
+    stream.flush()
+}
+
+fn field<'a>(r : &'a Request, name : &str) -> Result<&'a str, Reply> {
+    r.fields.get(name).map(|s| s.as_str()).ok_or_else(|| error(400, &format!("{} is missing", name)))
+}
+
+// A number field, or default if it isn't given.
+fn number<T : FromStr>(r : &Request, name : &str, default : T) -> Result<T, Reply> {
+    match r.fields.get(name) {
+        Some(v) => v.parse().map_err(|_| error(400, &format!("{} isn't a number", name))),
+        None    => Ok(default),
+    }
+}
+
+fn bad(e : String) -> Reply {
+    error(400, &e)
+}
+
+fn nation_json(w : &World, idx : usize) -> String {
+    let n = &w.ntn[idx];
+    json::object(&[
+        ("id",          n.id.to_string()),
+        ("name",        json::string(&n.name)),
+        ("race",        json::string(&n.race.to_string())),
+        ("mark",        json::string(&n.mark.to_string())),
+        ("capitol",     format!("[{}, {}]", n.capx, n.capy)),
+        ("turn",        w.turn.to_string()),
+        ("score",       n.score.to_string()),
+        ("sectors",     n.tsctrs.to_string()),
+        ("civilians",   n.tciv.to_string()),
+        ("military",    n.tmil.to_string()),
+        ("gold",        n.tgold.to_string()),
+        ("jewels",      n.jewels.to_string()),
+        ("metal",       n.metals.to_string()),
+        ("food",        n.tfood.to_string()),
+        ("tax_rate",    n.tax_rate.to_string()),
+        ("popularity",  n.popularity.to_string()),
+        ("karma",       n.karma.to_string()),
+        ("unread",      n.mail.iter().filter(|m| !m.read).count().to_string()),
+    ])
+}
+
+// The sectors nation id can see in a window of the map.
+fn tiles(w : &World, id : i32, r : &Request) -> Result<String, Reply> {
+    let x0 : usize = number(r, "x", 0)?;
+    let y0 : usize = number(r, "y", 0)?;
+    let x1 = x0.saturating_add(number(r, "w", w.mapx as usize)?).min(w.mapx as usize);
+    let y1 = y0.saturating_add(number(r, "h", w.mapy as usize)?).min(w.mapy as usize);
+    let seen = w.visible(id);
+    let mut v = Vec::new();
+    for (x, col) in seen.iter().enumerate().take(x1).skip(x0) {
+        for y in (y0..y1).filter(|&y| col[y]) {
+            let s = &w.sct[x][y];
+            let mut f = vec![
+                ("x",           x.to_string()),
+                ("y",           y.to_string()),
+                ("designation", json::string(&s.designation.to_char().to_string())),
+                ("altitude",    json::string(&s.altitude.to_char().to_string())),
+                ("vegetation",  json::string(&s.vegetation.to_char().to_string())),
+                ("owner",       s.owner.to_string()),
+                ("people",      s.people.to_string()),
+            ];
+            // what's inside is only known to the owner
+            if s.owner as i32 == id {
+                f.extend([
+                    ("fortress",    s.fortress.to_string()),
+                    ("jewels",      s.jewels.to_string()),
+                    ("metal",       s.metal.to_string()),
+                    ("tradegood",   s.tradegood.to_string()),
+                    ("foodstore",   s.foodstore.to_string()),
+                ]);
+            }
+            v.push(json::object(&f));
+        }
+    }
+    Ok(json::list(&v))
+}
+
+fn armies(w : &World, id : i32, idx : usize) -> String {
+    let own : Vec<_> = w.ntn[idx].arm.iter().enumerate().map(|(i, a)| json::object(&[
+        ("army",        i.to_string()),
+        ("x",           a.xloc.to_string()),
+        ("y",           a.yloc.to_string()),
+        ("soldiers",    a.sold.to_string()),
+        ("move",        a.smove.to_string()),
+        ("status",      a.stat.to_string()),
+    ])).collect();
+    let seen : Vec<_> = w.armies_in_view(id).iter().map(|(n, i, x, y, sold)| json::object(&[
+        ("nation",      n.to_string()),
+        ("army",        i.to_string()),
+        ("x",           x.to_string()),
+        ("y",           y.to_string()),
+        ("soldiers",    sold.to_string()),
+    ])).collect();
+    json::object(&[("own", json::list(&own)), ("seen", json::list(&seen))])
+}
+
+fn navies(w : &World, id : i32, idx : usize) -> String {
+    let fleet = |f : &crate::Navy, i : usize| vec![
+        ("navy",        i.to_string()),
+        ("x",           f.xloc.to_string()),
+        ("y",           f.yloc.to_string()),
+        ("warships",    f.warships.to_string()),
+        ("merchant",    f.merchant.to_string()),
+        ("galleys",     f.galleys.to_string()),
+    ];
+    let own : Vec<_> = w.ntn[idx].nvy.iter().enumerate().map(|(i, f)| json::object(&fleet(f, i))).collect();
+    let visible = w.visible(id);
+    let mut seen = Vec::new();
+    for n in w.ntn.iter().filter(|n| n.id != id) {
+        for (i, f) in n.nvy.iter().enumerate() {
+            let (x, y) = (f.xloc as usize, f.yloc as usize);
+            if w.on_map(x as i32, y as i32) && visible[x][y] {
+                let mut o = vec![("nation", n.id.to_string())];
+                o.extend(fleet(f, i));
+                seen.push(json::object(&o));
+            }
+        }
+    }
+    json::object(&[("own", json::list(&own)), ("seen", json::list(&seen))])
+}
+
+// Carry out a request from nation id.  Returns the answer and whether
+// the game changed.
+fn api(w : &mut World, id : i32, r : &Request) -> Result<(String, bool), Reply> {
+    let idx = w.nation_index(id).ok_or_else(|| error(401, "your nation is no more"))?;
+    let letter = match r.path.strip_prefix("/api/mail/") {
+        Some(m) => Some(m.parse::<u32>().map_err(|_| error(404, "no such letter"))?),
+        None    => None,
+    };
+    let done = json::object(&[("ok", "true".to_string())]);
+
+    let v = match (r.method.as_str(), r.path.as_str()) {
+        ("GET", "/api/nation")  => nation_json(w, idx),
+        ("GET", "/api/map")     => json::object(&[
+            ("width",       w.mapx.to_string()),
+            ("height",      w.mapy.to_string()),
+            ("rows",        json::list(&w.view_map(id).iter().map(|r| json::string(r)).collect::<Vec<_>>())),
+        ]),
+        ("GET", "/api/tiles")   => tiles(w, id, r)?,
+        ("GET", "/api/armies")  => armies(w, id, idx),
+        ("GET", "/api/navies")  => navies(w, id, idx),
+        ("GET", "/api/news")    => {
+            let turn = number(r, "turn", (w.turn - 1).max(0))?;
+            let news : Vec<_> = w.news_of(turn).iter().map(|n| json::object(&[
+                ("category",    json::string(&n.category.to_string())),
+                ("nations",     json::list(&n.nations.iter().map(|id| id.to_string()).collect::<Vec<_>>())),
+                ("text",        json::string(&n.text)),
+            ])).collect();
+            json::object(&[("turn", turn.to_string()), ("news", json::list(&news))])
+        },
+        ("GET", "/api/mail")    => {
+            let mail : Vec<_> = w.inbox(id).map_err(bad)?.iter().map(|m| json::object(&[
+                ("id",          m.id.to_string()),
+                ("from",        m.from.to_string()),
+                ("turn",        m.turn.to_string()),
+                ("read",        m.read.to_string()),
+                ("subject",     json::string(&m.subject)),
+            ])).collect();
+            json::list(&mail)
+        },
+        ("POST", "/api/mail")   => {
+            let to = nation_id(w, field(r, "to")?).map_err(bad)?;
+            let body = r.fields.get("body").map(|b| b.as_str()).unwrap_or("");
+            let m = w.send_mail(id, to, field(r, "subject")?, body).map_err(bad)?;
+            return Ok((json::object(&[("id", m.to_string())]), true));
+        },
+        ("GET", _) if letter.is_some()      => {
+            let m = w.read_mail(id, letter.unwrap()).map_err(|e| error(404, &e))?;
+            let v = json::object(&[
+                ("id",          m.id.to_string()),
+                ("from",        m.from.to_string()),
+                ("turn",        m.turn.to_string()),
+                ("subject",     json::string(&m.subject)),
+                ("body",        json::string(&m.body)),
+            ]);
+            return Ok((v, true));
+        },
+        ("DELETE", _) if letter.is_some()   => {
+            w.delete_mail(id, letter.unwrap()).map_err(|e| error(404, &e))?;
+            return Ok((done, true));
+        },
+        ("POST", "/api/orders") => {
+            let cmd : Command = field(r, "order")?.parse().map_err(bad)?;
+            w.execute(id, &cmd).map_err(bad)?;
+            return Ok((done, true));
+        },
+        ("POST", "/api/update") => {
+            let d = w.god(id, &GodCommand::Update).map_err(|e| error(403, &e))?;
+            return Ok((json::object(&[("done", json::string(&d)), ("turn", w.turn.to_string())]), true));
+        },
+        _                       => return Err(error(404, &format!("no {} {}", r.method, r.path))),
+    };
+    Ok((v, false))
+}
+
+fn answer(host : &Handle, tokens : &Tokens, r : Request) -> Reply {
+    let stopped = || error(503, "the game has stopped");
+    match (r.method.as_str(), r.path.as_str()) {
+        ("POST", "/api/login")  => {
+            let (which, passwd) = match (field(&r, "nation"), field(&r, "password")) {
+                (Ok(n), Ok(p))  => (n.to_string(), p.to_string()),
+                (Err(e), _) | (_, Err(e)) => return e,
+            };
+            let logged = host.call(move |w| match nation_id(w, &which) {
+                Ok(id)  => (w.login(id, &passwd).map(|_| id), true),
+                Err(e)  => (Err(e), false),
+            });
+            match logged {
+                Some(Ok(id))    => {
+                    let token = new_token();
+                    tokens.lock().unwrap().insert(token.clone(), id);
+                    (200, json::object(&[("token", json::string(&token)), ("nation", id.to_string())]))
+                },
+                Some(Err(e))    => error(401, &e),
+                None            => stopped(),
+            }
+        },
+        _                       => {
+            let id = r.token.as_ref().and_then(|t| tokens.lock().unwrap().get(t).copied());
+            let Some(id) = id else { return error(401, "log in first") };
+            if (r.method.as_str(), r.path.as_str()) == ("POST", "/api/logout") {
+                tokens.lock().unwrap().remove(r.token.as_ref().unwrap());
+                return (200, json::object(&[("ok", "true".to_string())]));
+            }
+            host.call(move |w| match api(w, id, &r) {
+                Ok((v, changed))    => ((200, v), changed),
+                Err(e)              => (e, false),
+            }).unwrap_or_else(stopped)
+        },
+    }
+}
+
+// Is this connection asking for the events socket?  Peeks, so the
+// handshake can still read the request.
+fn wants_events(stream : &TcpStream) -> io::Result<bool> {
+    let mut buf = [0; EVENTS.len()];
+    for _ in 0..100 {
+        let n = stream.peek(&mut buf)?;
+        if n == 0 || n == buf.len() || !EVENTS.starts_with(&buf[..n]) {
+            return Ok(&buf[..n] == EVENTS);
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    Ok(false)
+}
+
+// Push events to a nation until it hangs up, logs out or the game stops.
+fn events(stream : TcpStream, host : Handle, tokens : Tokens) {
+    let mut who = None;
+    // tungstenite's own type for turning a handshake down
+    #[allow(clippy::result_large_err)]
+    let check = |req : &WsRequest, res : Response| -> Result<Response, ErrorResponse> {
+        let mut fields = HashMap::new();
+        parse_fields(req.uri().query().unwrap_or(""), &mut fields);
+        let token = fields.remove("token").unwrap_or_default();
+        let Some(&id) = tokens.lock().unwrap().get(&token) else {
+            let mut e = ErrorResponse::new(Some("log in first".to_string()));
+            *e.status_mut() = StatusCode::UNAUTHORIZED;
+            return Err(e);
+        };
+        // subscribe before the handshake is answered, so nothing is missed
+        who = Some((id, token, host.subscribe()));
+        Ok(res)
+    };
+    let Ok(mut ws) = tungstenite::accept_hdr(stream, check) else { return };
+    let Some((id, token, events)) = who else { return };
+
+    for e in events {
+        if !tokens.lock().unwrap().contains_key(&token) {
+            break;
+        }
+        let msg = match e {
+            Event::Turn { turn }                    => json::object(&[
+                ("event",       json::string("turn")),
+                ("turn",        turn.to_string()),
+            ]),
+            Event::Mail { to, id: m, from, subject } if to == id => json::object(&[
+                ("event",       json::string("mail")),
+                ("id",          m.to_string()),
+                ("from",        from.to_string()),
+                ("subject",     json::string(&subject)),
+            ]),
+            Event::Mail { .. }                      => continue,
+        };
+        if ws.send(Message::Text(msg)).is_err() {
+            return;
+        }
+    }
+    let _ = ws.close(None);
+}
+
+fn connection(stream : TcpStream, host : Handle, tokens : Tokens) -> io::Result<()> {
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    if wants_events(&stream)? {
+        stream.set_read_timeout(None)?;
+        events(stream, host, tokens);
+        return Ok(());
+    }
+    let reply = match read_request(&stream) {
+        Ok(r)   => answer(&host, &tokens, r),
+        Err(e)  => e,
+    };
+    write_reply(&stream, reply)
+}
+
+impl Web {
+    /// Serve the game host is running over HTTP on addr ("127.0.0.1:0"
+    /// picks a free port).
+    pub fn start(host : Handle, addr : &str) -> io::Result<Web> {
+        let listener = TcpListener::bind(addr)?;
+        let addr = listener.local_addr()?;
+        let stopping = Arc::new(AtomicBool::new(false));
+        let tokens = Tokens::default();
+
+        let listener = {
+            let stopping = stopping.clone();
+            thread::spawn(move || {
+                for stream in listener.incoming() {
+                    if stopping.load(SeqCst) {
+                        break;
+                    }
+                    let Ok(stream) = stream else { continue };
+                    let (host, tokens) = (host.clone(), tokens.clone());
+                    thread::spawn(move || connection(stream, host, tokens));
+                }
+            })
+        };
+        Ok(Web { addr, stopping, listener })
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Stop taking connections.
+    pub fn stop(self) {
+        self.stopping.store(true, SeqCst);
+        let _ = TcpStream::connect(self.addr);
+        let _ = self.listener.join();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::host::Host;
+    use crate::mail::Delivery;
+    use crate::testing::players;
+
+    // Ask addr, returning the status and body.
+    fn http(addr : SocketAddr, method : &str, path : &str, token : &str, body : &str) -> (u16, String) {
+        let mut s = TcpStream::connect(addr).unwrap();
+        write!(s, "{} {} HTTP/1.1\r\nHost: test\r\nAuthorization: Bearer {}\r\nContent-Length: {}\r\n\r\n{}",
+            method, path, token, body.len(), body).unwrap();
+        let mut reply = String::new();
+        s.read_to_string(&mut reply).unwrap();
+        let (head, body) = reply.split_once("\r\n\r\n").unwrap();
+        (head[9..12].parse().unwrap(), body.to_string())
+    }
+
+    fn login(addr : SocketAddr, nation : &str, passwd : &str) -> String {
+        let (status, body) = http(addr, "POST", "/api/login", "", &format!("nation={}&password={}", nation, passwd));
+        assert_eq!(status, 200, "{}", body);
+        body.split('"').nth(3).unwrap().to_string()
+    }
+
+    fn world() -> (World, i32, i32, i32) {
+        let (mut w, g, e, o) = players();
+        w.mail_delivery = Delivery::IMMEDIATE;
+        (w, g, e, o)
+    }
+
+    #[test]
+    fn players_see_their_own_corner_over_http() {
+        let (w, _, e, o) = world();
+        let host = Host::start(w, None);
+        let web = Web::start(host.handle(), "127.0.0.1:0").unwrap();
+        let addr = web.addr();
+
+        assert_eq!(http(addr, "GET", "/api/map", "", "").0, 401);
+        assert_eq!(http(addr, "POST", "/api/login", "", "nation=Elfland&password=friend").0, 401);
+        let elf = login(addr, "Elfland", "mellon");
+        let orc = login(addr, &o.to_string(), "grishnakh");
+
+        let (_, map) = http(addr, "GET", "/api/map", &elf, "");
+        assert!(map.contains("\"-B--  ") && map.contains("\"height\": 5"));
+        let (_, tiles) = http(addr, "GET", "/api/tiles?x=15&w=5", &elf, "");
+        assert_eq!(tiles, "[]");
+        let (_, tiles) = http(addr, "GET", "/api/tiles?x=1&y=1&w=1&h=1", &elf, "");
+        assert!(tiles.contains("\"fortress\"") && tiles.contains("\"designation\": \"C\""));
+
+        assert_eq!(http(addr, "POST", "/api/orders", &elf, "order=draft+1+1+10").0, 200);
+        assert_eq!(http(addr, "POST", "/api/orders", &elf, "order=fly+1+1").0, 400);
+        let (_, armies) = http(addr, "GET", "/api/armies", &elf, "");
+        assert!(armies.contains("\"army\": 1") && armies.contains("\"seen\": []"));
+
+        let (status, _) = http(addr, "POST", "/api/mail", &orc, "to=Elfland&subject=Greetings&body=We+come+in+peace");
+        assert_eq!(status, 200);
+        let (_, mail) = http(addr, "GET", "/api/mail", &elf, "");
+        let id : u32 = mail.split("\"id\": ").nth(1).unwrap().split(',').next().unwrap().parse().unwrap();
+        let (_, letter) = http(addr, "GET", &format!("/api/mail/{}", id), &elf, "");
+        assert!(letter.contains("We come in peace"));
+        assert_eq!(http(addr, "DELETE", &format!("/api/mail/{}", id), &elf, "").0, 200);
+        assert_eq!(http(addr, "GET", &format!("/api/mail/{}", id), &elf, "").0, 404);
+
+        assert_eq!(http(addr, "POST", "/api/update", &orc, "").0, 403);
+        assert_eq!(http(addr, "POST", "/api/logout", &orc, "").0, 200);
+        assert_eq!(http(addr, "GET", "/api/nation", &orc, "").0, 401);
+
+        web.stop();
+        let w = host.stop();
+        assert_eq!(w.nation(e).unwrap().arm.len(), 2);
+        assert!(w.inbox(e).unwrap().is_empty());
+    }
+
+    #[test]
+    fn events_reach_only_whom_they_concern() {
+        let (w, g, _, _) = world();
+        let host = Host::start(w, None);
+        let web = Web::start(host.handle(), "127.0.0.1:0").unwrap();
+        let addr = web.addr();
+        let (god, elf, orc) = (login(addr, &g.to_string(), "heaven"), login(addr, "Elfland", "mellon"), login(addr, "Orcland", "grishnakh"));
+
+        assert!(tungstenite::connect(format!("ws://{}/api/events?token=nonsense", addr)).is_err());
+        let (mut elf_ws, _) = tungstenite::connect(format!("ws://{}/api/events?token={}", addr, elf)).unwrap();
+        let (mut orc_ws, _) = tungstenite::connect(format!("ws://{}/api/events?token={}", addr, orc)).unwrap();
+
+        http(addr, "POST", "/api/mail", &orc, "to=Elfland&subject=Boo");
+        http(addr, "POST", "/api/update", &god, "");
+
+        let next = |ws : &mut tungstenite::WebSocket<_>| ws.read().unwrap().into_text().unwrap();
+        let mail = next(&mut elf_ws);
+        assert!(mail.contains("\"event\": \"mail\"") && mail.contains("\"subject\": \"Boo\""));
+        assert_eq!(next(&mut elf_ws), "{\"event\": \"turn\", \"turn\": 1}");
+        assert_eq!(next(&mut orc_ws), "{\"event\": \"turn\", \"turn\": 1}");
+
+        web.stop();
+        host.stop();
+    }
+}
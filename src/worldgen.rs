@@ -0,0 +1,155 @@
+// World generation, a much simplified makeworld.
+//
+// Land comes from a pile of random hills with the sea filled in to the
+// requested level.  Elevation follows height, vegetation follows latitude
+// with some luck, and the high country gets the metal and jewels.  Monster
+// lairs, if asked for, go on empty land as SPECIAL sectors, and trade goods
+// are scattered over what's left.
+
+use crate::units::{Lair, MINMONSTER, MONSTERS};
+use crate::{distance, Designation, Elevation, Vegetation, World};
+
+#[derive(Debug)]
+#[derive(Clone)]
+pub struct GenOptions {
+    pub pwater      : i32,      // percent of the map under water
+    pub lairs       : usize,    // monster lairs to place
+    pub tradegoods  : i32,      // percent of land with a trade good
+}
+
+impl Default for GenOptions {
+    fn default() -> GenOptions {
+        GenOptions {
+            pwater      : 65,
+            lairs       : 4,
+            tradegoods  : 10,
+        }
+    }
+}
+
+impl World {
+    // Make a new world of the given size.  The same seed and options always
+    // give the same world.
+    pub fn generate(mapx : i16, mapy : i16, seed : u64, opts : &GenOptions) -> World {
+        let mut w = World::new(mapx, mapy, seed);
+        w.make_terrain(opts.pwater);
+        w.place_lairs(opts.lairs);
+        w.place_tradegoods(opts.tradegoods);
+        w
+    }
+
+    fn make_terrain(&mut self, pwater : i32) {
+        let (mx, my) = (self.mapx as usize, self.mapy as usize);
+        if mx == 0 || my == 0 {
+            return;
+        }
+
+        // pile up some hills
+        let mut height = vec![vec![0i32; my]; mx];
+        let maxr = (mx.min(my) / 4).max(3) as u32;
+        for _ in 0..(mx * my / 16).max(1) {
+            let (cx, cy) = (self.rng.below(mx as u32) as i32, self.rng.below(my as u32) as i32);
+            let r = 2 + self.rng.below(maxr - 1) as i32;
+            for (x, col) in height.iter_mut().enumerate() {
+                for (y, h) in col.iter_mut().enumerate() {
+                    let d = distance(x as i32, y as i32, cx, cy);
+                    if d < r {
+                        *h += r - d;
+                    }
+                }
+            }
+        }
+
+        // flood to sea level, then split what's left by height
+        let mut all : Vec<i32> = height.iter().flatten().copied().collect();
+        all.sort();
+        let at = |pct : i32| all[((all.len() - 1) as i64 * pct.clamp(0, 100) as i64 / 100) as usize];
+        let land = 100 - pwater.clamp(0, 100);
+        let sea = at(pwater);
+        let hill = at(100 - land * 40 / 100);
+        let mountain = at(100 - land * 16 / 100);
+        let peak = at(100 - land * 4 / 100);
+
+        for (x, col) in height.iter().enumerate() {
+            for (y, &h) in col.iter().enumerate() {
+                let altitude = if h <= sea && pwater > 0 {
+                    Elevation::WATER
+                } else if h > peak {
+                    Elevation::PEAK
+                } else if h > mountain {
+                    Elevation::MOUNTAIN
+                } else if h > hill {
+                    Elevation::HILL
+                } else {
+                    Elevation::CLEAR
+                };
+                let lat = ((y as i32 * 2 - my as i32).abs() * 100 / my as i32).min(100);
+                let vegetation = self.pick_vegetation(&altitude, lat);
+
+                let high = matches!(altitude, Elevation::HILL | Elevation::MOUNTAIN | Elevation::PEAK);
+                let s = &mut self.sct[x][y];
+                s.altitude = altitude;
+                s.vegetation = vegetation;
+                if high && self.rng.percent(20) {
+                    s.metal = 1 + self.rng.below(6) as u8;
+                }
+                if high && self.rng.percent(10) {
+                    s.jewels = 1 + self.rng.below(4) as u8;
+                }
+            }
+        }
+    }
+
+    // Vegetation for a sector at lat, 0 at the equator to 100 at the poles.
+    fn pick_vegetation(&mut self, altitude : &Elevation, lat : i32) -> Vegetation {
+        use Vegetation::*;
+        let table : &[(Vegetation, u32)] = match altitude {
+            Elevation::WATER                        => return NONE,
+            _ if lat > 90                           => return ICE,
+            _ if lat > 75                           => &[(TUNDRA, 70), (BARREN, 20), (ICE, 10)],
+            Elevation::PEAK | Elevation::MOUNTAIN   => &[(BARREN, 45), (LT_VEG, 20), (WOOD, 20), (VOLCANO, 3), (ICE, 12)],
+            _ if lat < 20                           => &[(JUNGLE, 25), (SWAMP, 10), (GOOD, 35), (FOREST, 15), (DESERT, 15)],
+            _                                       => &[(GOOD, 30), (LT_VEG, 20), (WOOD, 20), (FOREST, 15), (DESERT, 5), (SWAMP, 5), (BARREN, 5)],
+        };
+        let mut roll = self.rng.below(table.iter().map(|(_, w)| w).sum());
+        for (v, weight) in table {
+            if roll < *weight {
+                return v.clone();
+            }
+            roll -= weight;
+        }
+        GOOD
+    }
+
+    // Put count monster lairs on empty land.
+    pub fn place_lairs(&mut self, count : usize) {
+        let mut spots = Vec::new();
+        for (x, col) in self.sct.iter().enumerate() {
+            for (y, s) in col.iter().enumerate() {
+                if s.altitude != Elevation::WATER && s.vegetation != Vegetation::VOLCANO
+                    && self.sector_owner(x, y).is_none() && s.designation == Designation::NONE {
+                    spots.push((x, y));
+                }
+            }
+        }
+
+        for _ in 0..count {
+            if spots.is_empty() {
+                break;
+            }
+            let (x, y) = spots.swap_remove(self.rng.below(spots.len() as u32) as usize);
+            let kind = self.rng.below(MONSTERS.len() as u32) as usize;
+            let strength = MONSTERS[kind].strength;
+            let count = if strength >= 500 { 1 } else { 1 + self.rng.below(3) as i32 };
+            self.sct[x][y].designation = Designation::SPECIAL;
+            self.lairs.push(Lair {
+                x,
+                y,
+                unittyp     : MINMONSTER + kind as u8,
+                count,
+                jewels      : count * strength / 2 + self.rng.below(500) as i32,
+                gold        : count * strength * 5 + self.rng.below(5000) as i32,
+            });
+        }
+    }
+}